@@ -0,0 +1,231 @@
+//! Calls the governor makes to other contracts: executed call and adapter
+//! proposals, governance token snapshots, identity gate, weight source and price
+//! oracle queries.
+
+use ink::{
+    env::{
+        call::{
+            build_call,
+            ExecutionInput,
+            Selector,
+        },
+        DefaultEnvironment,
+    },
+    primitives::AccountId,
+};
+
+type Balance = u128;
+
+/// Arguments that are already SCALE encoded, appended to the call input as is.
+pub struct RawInput<'a>(pub &'a [u8]);
+
+impl scale::Encode for RawInput<'_> {
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(self.0);
+    }
+}
+
+/// Calls the message `selector` of `target` with the encoded `input`, sending
+/// `value` along. Fails if the call traps or the message reverts.
+pub fn invoke(
+    target: AccountId,
+    selector: [u8; 4],
+    input: &[u8],
+    value: Balance,
+) -> Result<(), ()> {
+    build_call::<DefaultEnvironment>()
+        .call(target)
+        .transferred_value(value)
+        .exec_input(
+            ExecutionInput::new(Selector::new(selector)).push_arg(RawInput(input)),
+        )
+        .returns::<()>()
+        .try_invoke()
+        .map_err(|_| ())?
+        .map_err(|_| ())
+}
+
+/// Has the execution adapter `adapter` execute `payload`, sending `value` along,
+/// through its `execute(Vec<u8>)` message. Adapters are expected to only accept
+/// calls from the governor. Fails if the call traps or the message reverts.
+pub fn execute_adapter(
+    adapter: AccountId,
+    payload: &[u8],
+    value: Balance,
+) -> Result<(), ()> {
+    build_call::<DefaultEnvironment>()
+        .call(adapter)
+        .transferred_value(value)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!("execute")))
+                .push_arg(payload),
+        )
+        .returns::<()>()
+        .try_invoke()
+        .map_err(|_| ())?
+        .map_err(|_| ())
+}
+
+/// Balance of `account` in the governance token `token` as of `timestamp`, and its
+/// total supply then, through its `balance_of_at(AccountId, Timestamp) -> Balance`
+/// and `total_supply_at(Timestamp) -> Balance` snapshot messages. `None` if either
+/// query fails.
+#[cfg(not(any(test, feature = "testing")))]
+pub fn token_balances_at(
+    token: AccountId,
+    account: AccountId,
+    timestamp: u64,
+) -> Option<(Balance, Balance)> {
+    let balance = build_call::<DefaultEnvironment>()
+        .call(token)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!("balance_of_at")))
+                .push_arg(account)
+                .push_arg(timestamp),
+        )
+        .returns::<Balance>()
+        .try_invoke()
+        .ok()?
+        .ok()?;
+    let total_supply = build_call::<DefaultEnvironment>()
+        .call(token)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!("total_supply_at")))
+                .push_arg(timestamp),
+        )
+        .returns::<Balance>()
+        .try_invoke()
+        .ok()?
+        .ok()?;
+    Some((balance, total_supply))
+}
+
+/// Average balance of `account` in the governance token `token` from `start` until
+/// `end`, and its average total supply, through its `average_balance_of(AccountId,
+/// Timestamp, Timestamp) -> Balance` and `average_total_supply(Timestamp,
+/// Timestamp) -> Balance` messages. `None` if either query fails.
+#[cfg(not(any(test, feature = "testing")))]
+pub fn token_averages(
+    token: AccountId,
+    account: AccountId,
+    start: u64,
+    end: u64,
+) -> Option<(Balance, Balance)> {
+    let balance = build_call::<DefaultEnvironment>()
+        .call(token)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                "average_balance_of"
+            )))
+            .push_arg(account)
+            .push_arg(start)
+            .push_arg(end),
+        )
+        .returns::<Balance>()
+        .try_invoke()
+        .ok()?
+        .ok()?;
+    let total_supply = build_call::<DefaultEnvironment>()
+        .call(token)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                "average_total_supply"
+            )))
+            .push_arg(start)
+            .push_arg(end),
+        )
+        .returns::<Balance>()
+        .try_invoke()
+        .ok()?
+        .ok()?;
+    Some((balance, total_supply))
+}
+
+/// Asks the identity registry `gate` whether `account` is verified as a unique
+/// person, through its `is_verified(AccountId) -> bool` message. A failed query
+/// counts as unverified.
+#[cfg(not(any(test, feature = "testing")))]
+pub fn is_verified(gate: AccountId, account: AccountId) -> bool {
+    build_call::<DefaultEnvironment>()
+        .call(gate)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!("is_verified")))
+                .push_arg(account),
+        )
+        .returns::<bool>()
+        .try_invoke()
+        .map_or(false, |verified| verified.unwrap_or(false))
+}
+
+/// Voting power of `account` at `timestamp` according to the weight source
+/// `source`, through its `voting_power_of(AccountId, Timestamp) -> Balance` message.
+/// `None` if the query fails.
+#[cfg(not(any(test, feature = "testing")))]
+pub fn voting_power(
+    source: AccountId,
+    account: AccountId,
+    timestamp: u64,
+) -> Option<Balance> {
+    build_call::<DefaultEnvironment>()
+        .call(source)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!("voting_power_of")))
+                .push_arg(account)
+                .push_arg(timestamp),
+        )
+        .returns::<Balance>()
+        .try_invoke()
+        .ok()?
+        .ok()
+}
+
+/// Total voting power at `timestamp` according to the weight source `source`,
+/// through its `total_voting_power(Timestamp) -> Balance` message.
+#[cfg(not(any(test, feature = "testing")))]
+pub fn total_voting_power(source: AccountId, timestamp: u64) -> Option<Balance> {
+    build_call::<DefaultEnvironment>()
+        .call(source)
+        .exec_input(
+            ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                "total_voting_power"
+            )))
+            .push_arg(timestamp),
+        )
+        .returns::<Balance>()
+        .try_invoke()
+        .ok()?
+        .ok()
+}
+
+/// Price of one unit of the native currency in the reference stable unit, scaled by
+/// `math::PRECISION`, according to the price oracle `oracle`, through its
+/// `price() -> Balance` message. `None` if the query fails.
+#[cfg(not(any(test, feature = "testing")))]
+pub fn price(oracle: AccountId) -> Option<Balance> {
+    build_call::<DefaultEnvironment>()
+        .call(oracle)
+        .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
+            "price"
+        ))))
+        .returns::<Balance>()
+        .try_invoke()
+        .ok()?
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scale::Encode;
+
+    #[test]
+    fn raw_input_is_not_length_prefixed() {
+        let args = (7u32, true).encode();
+        assert_eq!(RawInput(&args).encode(), args);
+        assert_eq!(RawInput(&[]).encode(), Vec::<u8>::new());
+    }
+}
@@ -0,0 +1,217 @@
+//! Gas and storage-deposit benchmarks for the governor's hot paths.
+//!
+//! These run against a local `substrate-contracts-node` and are only compiled with the
+//! `e2e-tests` feature:
+//!
+//! ```sh
+//! cargo test --features e2e-tests -- --nocapture
+//! ```
+//!
+//! Each message is dry-run before it is submitted; the measured cost is printed and
+//! checked against a budget. When an optimisation lowers a cost, lower its budget in
+//! the same change so later regressions are caught.
+
+use crate::dao::{
+    GovernorRef,
+    ProposalId,
+    VoteType,
+    MIN_VOTING_PERIOD,
+};
+use governance_token::governance_token::GovernanceTokenRef;
+use ink::primitives::AccountId;
+use ink_e2e::{
+    build_message,
+    Keypair,
+};
+use openbrush::contracts::traits::psp22::psp22_external::PSP22;
+
+type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Voters taking part in the benchmarked proposal.
+const VOTERS: u128 = 32;
+const TOKENS_PER_VOTER: u128 = 1_000_000;
+const QUORUM: u8 = 10;
+const TREASURY: u128 = 1_000_000_000;
+const VOTER_FUNDS: u128 = 1_000_000_000_000;
+
+/// Upper bounds for a single message.
+struct Budget {
+    ref_time: u64,
+    proof_size: u64,
+    storage_deposit: u128,
+}
+
+const PROPOSE_BUDGET: Budget = Budget {
+    ref_time: 5_000_000_000,
+    proof_size: 100_000,
+    storage_deposit: 1_000_000_000_000,
+};
+
+const VOTE_BUDGET: Budget = Budget {
+    ref_time: 15_000_000_000,
+    proof_size: 250_000,
+    storage_deposit: 1_000_000_000_000,
+};
+
+const EXECUTE_BUDGET: Budget = Budget {
+    ref_time: 5_000_000_000,
+    proof_size: 100_000,
+    storage_deposit: 0,
+};
+
+/// How much more the last vote on a busy proposal may cost than the first one.
+const VOTE_GROWTH_PERCENT: u64 = 25;
+
+#[derive(Debug)]
+struct Cost {
+    ref_time: u64,
+    proof_size: u64,
+    storage_deposit: u128,
+}
+
+impl Cost {
+    fn assert_within(&self, name: &str, budget: &Budget) {
+        println!("{name}: {self:?}");
+        assert!(
+            self.ref_time <= budget.ref_time,
+            "{name} ref_time {} exceeds budget {}",
+            self.ref_time,
+            budget.ref_time
+        );
+        assert!(
+            self.proof_size <= budget.proof_size,
+            "{name} proof_size {} exceeds budget {}",
+            self.proof_size,
+            budget.proof_size
+        );
+        assert!(
+            self.storage_deposit <= budget.storage_deposit,
+            "{name} storage deposit {} exceeds budget {}",
+            self.storage_deposit,
+            budget.storage_deposit
+        );
+    }
+}
+
+/// Dry-runs `$message` as `$signer` and returns what it would cost.
+macro_rules! measure {
+    ($client:expr, $signer:expr, $message:expr) => {{
+        let dry_run = $client.call_dry_run($signer, &$message, 0, None).await;
+        assert!(
+            !dry_run.is_err(),
+            "dry run failed: {:?}",
+            dry_run.exec_result.result
+        );
+        let exec_result = &dry_run.exec_result;
+        Cost {
+            ref_time: exec_result.gas_required.ref_time(),
+            proof_size: exec_result.gas_required.proof_size(),
+            storage_deposit: exec_result.storage_deposit.charge_or_zero(),
+        }
+    }};
+}
+
+fn account_id(keypair: &Keypair) -> AccountId {
+    AccountId::from(keypair.public_key().0)
+}
+
+#[ink_e2e::test(additional_contracts = "../governance-token/Cargo.toml")]
+async fn hot_paths_stay_within_budget(
+    mut client: ink_e2e::Client<C, E>,
+) -> E2EResult<()> {
+    let token_constructor =
+        GovernanceTokenRef::new(TOKENS_PER_VOTER * (VOTERS + 1), None, None, 12);
+    let token = client
+        .instantiate(
+            "governance-token",
+            &ink_e2e::alice(),
+            token_constructor,
+            0,
+            None,
+        )
+        .await
+        .expect("token instantiation failed")
+        .account_id;
+    let governor_constructor = GovernorRef::new(token, QUORUM);
+    let governor = client
+        .instantiate(
+            "dao",
+            &ink_e2e::alice(),
+            governor_constructor,
+            TREASURY,
+            None,
+        )
+        .await
+        .expect("governor instantiation failed")
+        .account_id;
+
+    let mut voters = Vec::new();
+    for _ in 0..VOTERS {
+        let voter = client
+            .create_and_fund_account(&ink_e2e::alice(), VOTER_FUNDS)
+            .await;
+        let transfer = build_message::<GovernanceTokenRef>(token).call(|token| {
+            token.transfer(account_id(&voter), TOKENS_PER_VOTER, Vec::new())
+        });
+        client
+            .call(&ink_e2e::alice(), transfer, 0, None)
+            .await
+            .expect("token transfer failed");
+        voters.push(voter);
+    }
+
+    let propose = build_message::<GovernorRef>(governor).call(|governor| {
+        governor.propose(account_id(&voters[0]), 1_000, MIN_VOTING_PERIOD)
+    });
+    measure!(client, &ink_e2e::alice(), propose)
+        .assert_within("propose", &PROPOSE_BUDGET);
+    client
+        .call(&ink_e2e::alice(), propose, 0, None)
+        .await
+        .expect("propose failed");
+
+    let mut first_vote = None;
+    let mut last_vote = None;
+    for (index, voter) in voters.iter().enumerate() {
+        let vote = build_message::<GovernorRef>(governor)
+            .call(|governor| governor.vote(ProposalId(0), VoteType::For));
+        if index == 0 || index == voters.len() - 1 {
+            let cost = measure!(client, voter, vote);
+            cost.assert_within("vote", &VOTE_BUDGET);
+            if index == 0 {
+                first_vote = Some(cost);
+            } else {
+                last_vote = Some(cost);
+            }
+        }
+        client
+            .call(voter, vote, 0, None)
+            .await
+            .expect("vote failed");
+    }
+    let (first_vote, last_vote) = (first_vote.unwrap(), last_vote.unwrap());
+    assert!(
+        last_vote.ref_time * 100 <= first_vote.ref_time * (100 + VOTE_GROWTH_PERCENT),
+        "vote cost grows with participation: {first_vote:?} -> {last_vote:?}"
+    );
+
+    // Blocks are stamped with the wall clock, seal one once the vote is over.
+    std::thread::sleep(std::time::Duration::from_secs(MIN_VOTING_PERIOD + 1));
+    let transfer = build_message::<GovernanceTokenRef>(token)
+        .call(|token| token.transfer(account_id(&voters[0]), 0, Vec::new()));
+    client
+        .call(&ink_e2e::alice(), transfer, 0, None)
+        .await
+        .expect("token transfer failed");
+
+    let execute = build_message::<GovernorRef>(governor)
+        .call(|governor| governor.execute(ProposalId(0)));
+    measure!(client, &ink_e2e::alice(), execute)
+        .assert_within("execute", &EXECUTE_BUDGET);
+    client
+        .call(&ink_e2e::alice(), execute, 0, None)
+        .await
+        .expect("execute failed");
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+//! End-to-end tests against a real PSP22 token on a local `substrate-contracts-node`.
+//!
+//! Unit tests stub the token out; these cover the cross-contract `balance_of` and
+//! `total_supply` calls. Run them with:
+//!
+//! ```sh
+//! cargo test --features e2e-tests
+//! ```
+
+use crate::dao::{
+    GovernorError,
+    GovernorRef,
+    ProposalId,
+    VoteType,
+    MIN_VOTING_PERIOD,
+};
+use governance_token::governance_token::GovernanceTokenRef;
+use ink::primitives::AccountId;
+use ink_e2e::{
+    build_message,
+    Keypair,
+};
+use openbrush::contracts::traits::psp22::psp22_external::PSP22;
+
+type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+const TOTAL_SUPPLY: u128 = 1_000;
+const QUORUM: u8 = 50;
+const TREASURY: u128 = 1_000_000_000;
+const AMOUNT: u128 = 1_000_000;
+
+fn account_id(keypair: &Keypair) -> AccountId {
+    AccountId::from(keypair.public_key().0)
+}
+
+/// Deploys the token, with the whole supply minted to Alice, and a funded governor.
+macro_rules! deploy {
+    ($client:expr) => {{
+        let token_constructor = GovernanceTokenRef::new(TOTAL_SUPPLY, None, None, 12);
+        let token = $client
+            .instantiate(
+                "governance-token",
+                &ink_e2e::alice(),
+                token_constructor,
+                0,
+                None,
+            )
+            .await
+            .expect("token instantiation failed")
+            .account_id;
+        let governor_constructor = GovernorRef::new(token, QUORUM);
+        let governor = $client
+            .instantiate(
+                "dao",
+                &ink_e2e::alice(),
+                governor_constructor,
+                TREASURY,
+                None,
+            )
+            .await
+            .expect("governor instantiation failed")
+            .account_id;
+        (token, governor)
+    }};
+}
+
+/// Sends `amount` governance tokens from Alice to `to`.
+macro_rules! transfer_tokens {
+    ($client:expr, $token:expr, $to:expr, $amount:expr) => {{
+        let transfer = build_message::<GovernanceTokenRef>($token)
+            .call(|token| token.transfer(account_id($to), $amount, Vec::new()));
+        $client
+            .call(&ink_e2e::alice(), transfer, 0, None)
+            .await
+            .expect("token transfer failed");
+    }};
+}
+
+/// Waits out a `MIN_VOTING_PERIOD` vote. The node stamps blocks with the wall
+/// clock, so a block is sealed afterwards for dry runs to see the new time.
+macro_rules! end_vote {
+    ($client:expr, $token:expr) => {{
+        std::thread::sleep(std::time::Duration::from_secs(MIN_VOTING_PERIOD + 1));
+        transfer_tokens!($client, $token, &ink_e2e::bob(), 0);
+    }};
+}
+
+#[ink_e2e::test(additional_contracts = "../governance-token/Cargo.toml")]
+async fn propose_vote_execute_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+    let (token, governor) = deploy!(client);
+    transfer_tokens!(client, token, &ink_e2e::bob(), 300);
+    transfer_tokens!(client, token, &ink_e2e::charlie(), 100);
+    let recipient = account_id(&ink_e2e::dave());
+    let recipient_balance = client.balance(recipient).await?;
+
+    let propose = build_message::<GovernorRef>(governor)
+        .call(|governor| governor.propose(recipient, AMOUNT, MIN_VOTING_PERIOD));
+    client
+        .call(&ink_e2e::alice(), propose, 0, None)
+        .await
+        .expect("propose failed");
+
+    for (voter, vote) in [
+        (ink_e2e::alice(), VoteType::For),
+        (ink_e2e::bob(), VoteType::Against),
+        (ink_e2e::charlie(), VoteType::For),
+    ] {
+        let ballot = build_message::<GovernorRef>(governor)
+            .call(|governor| governor.vote(ProposalId(0), vote));
+        let result = client
+            .call(&voter, ballot, 0, None)
+            .await
+            .expect("vote failed");
+        assert_eq!(result.return_value(), Ok(()));
+    }
+
+    let has_voted = build_message::<GovernorRef>(governor)
+        .call(|governor| governor.has_voted(ProposalId(0), account_id(&ink_e2e::bob())));
+    assert!(client
+        .call_dry_run(&ink_e2e::alice(), &has_voted, 0, None)
+        .await
+        .return_value());
+
+    end_vote!(client, token);
+    let execute = build_message::<GovernorRef>(governor)
+        .call(|governor| governor.execute(ProposalId(0)));
+    let result = client
+        .call(&ink_e2e::alice(), execute, 0, None)
+        .await
+        .expect("execute failed");
+    assert_eq!(result.return_value(), Ok(()));
+    assert_eq!(client.balance(recipient).await?, recipient_balance + AMOUNT);
+
+    let execute = build_message::<GovernorRef>(governor)
+        .call(|governor| governor.execute(ProposalId(0)));
+    assert_eq!(
+        client
+            .call_dry_run(&ink_e2e::alice(), &execute, 0, None)
+            .await
+            .return_value(),
+        Err(GovernorError::ProposalAlreadyExecuted)
+    );
+
+    Ok(())
+}
+
+#[ink_e2e::test(additional_contracts = "../governance-token/Cargo.toml")]
+async fn votes_are_weighted_by_total_supply(
+    mut client: ink_e2e::Client<C, E>,
+) -> E2EResult<()> {
+    let (token, governor) = deploy!(client);
+    // Alice keeps 10% of the supply, far below the 50% quorum.
+    transfer_tokens!(client, token, &ink_e2e::bob(), 900);
+
+    let propose = build_message::<GovernorRef>(governor).call(|governor| {
+        governor.propose(account_id(&ink_e2e::dave()), AMOUNT, MIN_VOTING_PERIOD)
+    });
+    client
+        .call(&ink_e2e::alice(), propose, 0, None)
+        .await
+        .expect("propose failed");
+    let vote = build_message::<GovernorRef>(governor)
+        .call(|governor| governor.vote(ProposalId(0), VoteType::For));
+    client
+        .call(&ink_e2e::alice(), vote, 0, None)
+        .await
+        .expect("vote failed");
+
+    end_vote!(client, token);
+    let execute = build_message::<GovernorRef>(governor)
+        .call(|governor| governor.execute(ProposalId(0)));
+    assert_eq!(
+        client
+            .call_dry_run(&ink_e2e::alice(), &execute, 0, None)
+            .await
+            .return_value(),
+        Err(GovernorError::QuorumNotReached {
+            needed: 500_000_000_000,
+            got: 100_000_000_000,
+        })
+    );
+
+    Ok(())
+}
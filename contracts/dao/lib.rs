@@ -1,19 +1,168 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+mod call;
+mod math;
+mod merkle;
+pub mod psp22;
+pub mod psp34;
+mod qf;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+#[cfg(all(test, feature = "e2e-tests"))]
+mod e2e_benches;
+#[cfg(all(test, feature = "e2e-tests"))]
+mod e2e_tests;
+
 #[ink::contract]
 pub mod dao {
 
-    use crate::ensure;
-    use ink::storage::Mapping;
-    use openbrush::contracts::traits::psp22::*;
+    use crate::{
+        call,
+        ensure,
+        invariant,
+        math,
+        merkle,
+        psp34::Id,
+        qf,
+    };
+    #[cfg(not(any(test, feature = "testing")))]
+    use crate::{
+        psp22::PSP22,
+        psp34::PSP34,
+    };
+    use ink::{
+        prelude::{
+            string::String,
+            vec::Vec,
+        },
+        storage::{
+            Lazy,
+            Mapping,
+        },
+    };
     use scale::{
         Decode,
+        DecodeAll,
         Encode,
     };
 
-    type ProposalId = u32;
+    /// Identifies a proposal. Its own type rather than a bare integer, so it can't be
+    /// mixed up with positions, counts or other ids.
+    #[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ProposalId(pub u64);
+
+    impl ProposalId {
+        /// The id assigned after this one.
+        pub fn next(self) -> Self {
+            Self(self.0 + 1)
+        }
+    }
 
-    #[derive(Encode, Decode)]
+    type ReceiptBucket = (ProposalId, u8);
+    /// Tag grouping proposals by subject, e.g. DeFi deals or operations spending.
+    pub type Category = u8;
+    type TopicDelegation = (AccountId, Category);
+    /// An account's general (`None`) or topic delegation.
+    type Delegation = (AccountId, Option<Category>);
+    /// Position in the history of a `Delegation`: `(account, category, index)`.
+    type DelegationIndex = (AccountId, Option<Category>, u32);
+    /// Delegate of an account from a time on: `(taken_at, delegate)`.
+    type DelegateCheckpoint = (u64, Option<AccountId>);
+    /// Direct delegators of a delegate from a time on: `(taken_at, delegators)`.
+    type DelegatorsCheckpoint = (u64, Vec<AccountId>);
+    type LedgerPeriod = (u32, LedgerCategory, Asset);
+    /// A contributor's stake in one candidate of a funding round: `(round, contributor,
+    /// candidate index)`.
+    type RoundContribution = (ProposalId, AccountId, u8);
+    /// A sentiment signaled on a proposal and its weight.
+    type SignaledSentiment = (Sentiment, u64);
+    /// Recipients of a batch transfer and the amount paid to each.
+    type Transfers = Vec<(AccountId, Balance)>;
+    /// `(target, selector)` pairs proposals may invoke.
+    type CallAllowlist = Vec<(AccountId, [u8; 4])>;
+
+    /// Category of proposals submitted without one.
+    pub const GENERAL_CATEGORY: Category = 0;
+
+    /// Time after the end of voting (in milliseconds) before a proposal may be pruned.
+    pub const PRUNE_CHALLENGE_WINDOW: u64 = 7 * 24 * 60 * 60 * 1000;
+
+    /// Time after a tally is anchored (in milliseconds) during which voters may
+    /// dispute their ballot.
+    pub const TALLY_CHALLENGE_WINDOW: u64 = 2 * 24 * 60 * 60 * 1000;
+
+    /// Time (in milliseconds) after a proposal commitment during which it may be
+    /// revealed.
+    pub const REVEAL_WINDOW: u64 = 24 * 60 * 60 * 1000;
+
+    /// Longest chain of delegations voting power flows through.
+    pub const MAX_DELEGATION_DEPTH: u8 = 4;
+
+    /// Most accounts that may delegate directly to the same delegate.
+    pub const MAX_DELEGATORS: usize = 16;
+
+    /// Most delegates `Governor::top_delegates` ranks.
+    pub const MAX_TOP_DELEGATES: usize = 16;
+
+    /// Voting period (in seconds) of fast-tracked proposals.
+    pub const FAST_TRACK_DURATION: u64 = 6 * 60 * 60;
+
+    /// Shortest voting period (in seconds) a proposal may ask for.
+    pub const MIN_VOTING_PERIOD: u64 = 60;
+
+    /// Longest voting period (in seconds) a proposal may ask for.
+    pub const MAX_VOTING_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+    /// Version of the event schema, carried by every event. Bumped whenever the
+    /// fields of an event change, so indexers can tell payload layouts apart.
+    pub const EVENTS_VERSION: u16 = 1;
+
+    /// Version of the storage layout. Bumped whenever the fields of `Governor` or
+    /// of the types it stores change, so upgrades can tell when to migrate.
+    pub const STORAGE_VERSION: u16 = 1;
+
+    /// Version of the layout of `Governor::export_config`, leading every export.
+    /// Bumped whenever the fields of `GovernorConfig` change.
+    pub const CONFIG_EXPORT_VERSION: u16 = 1;
+
+    /// Default minimum voting period (in milliseconds) of constitutional amendments.
+    pub const AMENDMENT_DURATION: u64 = 14 * 24 * 60 * 60 * 1000;
+
+    /// Gas a ballot is assumed to use when pricing its fee for reimbursement, the
+    /// vote budget of the benchmarks.
+    pub const REIMBURSED_VOTE_GAS: u64 = 15_000_000_000;
+
+    /// Period (in milliseconds) delegate fee rates are quoted over.
+    pub const FEE_PERIOD: u64 = 24 * 60 * 60 * 1000;
+
+    /// Length (in milliseconds) of the periods the ledger is rolled up over.
+    pub const LEDGER_PERIOD: u64 = 30 * 24 * 60 * 60 * 1000;
+
+    /// Most options a poll may offer.
+    pub const MAX_POLL_OPTIONS: usize = 8;
+
+    /// Most grant candidates a quadratic funding round may list.
+    pub const MAX_GRANT_CANDIDATES: usize = 32;
+
+    /// Most recipients a batch transfer proposal may pay.
+    pub const MAX_BATCH_RECIPIENTS: usize = 32;
+
+    /// Most tranches an installments proposal may be split into.
+    pub const MAX_TRANCHES: u32 = 120;
+
+    /// Most redemption requests a single settlement pays out.
+    pub const MAX_SETTLED_REDEMPTIONS: u32 = 32;
+
+    /// Number of receipt buckets the voters of a single proposal are spread over. At
+    /// most 64, one bit each in `ProposalVote::receipt_buckets`.
+    pub const RECEIPT_BUCKETS: u8 = 64;
+
+    #[derive(Copy, Clone, Encode, Decode)]
     #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
     pub enum VoteType {
         // to implement
@@ -27,13 +176,142 @@ pub mod dao {
         AmountShouldNotBeZero,
         DurationError,
         ProposalNotFound,
-        VotePeriodEnded,
+        VotePeriodEnded {
+            ended_at: u64,
+        },
         ProposalAlreadyExecuted,
         AlreadyVoted,
-        QuorumNotReached,
+        /// Share of the supply (in `math::PRECISION` units) that had to vote, and
+        /// that did.
+        QuorumNotReached {
+            needed: u64,
+            got: u64,
+        },
         ProposalNotAccepted,
+        TransferFailed,
+        ProposalNotFinalized,
+        TokenCallFailed,
+        VotingIsOffChain,
+        NotTallyAggregator,
+        VotePeriodNotEnded,
+        TallyAlreadyAnchored,
+        TallyNotAnchored,
+        InvalidTally,
+        ChallengeWindowOpen,
+        ChallengeWindowClosed,
+        NotBallotVoter,
+        AlreadyDisputed,
+        InvalidProof,
+        NothingToClaim,
+        NotTrustedForwarder,
+        InvalidSignature,
+        InvalidNonce,
+        NotVotingKey,
+        PowerDelegated,
+        DelegationCycle,
+        DelegationTooDeep,
+        TooManyDelegators,
+        NotDelegating,
+        UnknownCategory,
+        NotGuardian,
+        FastTrackApprovalNotReached,
+        RewardPoolDepleted,
+        NotDefaultDelegate,
+        NotInactive,
+        CallNotAllowed,
+        CallFailed,
+        InvalidPollOptions,
+        IsPoll,
+        NotPoll,
+        InvalidAllocation,
+        IsAllocation,
+        NotAllocation,
+        NoObjectionWindow,
+        ObjectionWindowStarted,
+        ObjectionWindowNotStarted,
+        ObjectionWindowOpen,
+        ObjectionWindowClosed,
+        AlreadyObjected,
+        InvalidCandidates,
+        UnknownCandidate,
+        NotFundingRound,
+        RoundNotOpen,
+        RoundOpen,
+        RoundClosed,
+        NotVerified,
+        NotRetroRound,
+        NominationsOpen,
+        NominationsClosed,
+        NotBadgeHolder,
+        UnknownCause,
+        NftNotHeld,
+        NotNftOwner,
+        NftCallFailed,
+        UnknownRevenueSource,
+        RevenueNotReceived,
+        PoolDepleted,
+        NoDues,
+        InvalidDuesPayment,
+        DuesLapsed,
+        TierTooLow,
+        AlreadyMember,
+        NotMember,
+        MembershipPending,
+        Kicked,
+        InsufficientLoot,
+        AlreadyOnCouncil,
+        NotOnCouncil,
+        WeightSourceFailed,
+        SupermajorityNotReached,
+        InsufficientTreasury {
+            requested: Balance,
+            available: Balance,
+        },
+        InvalidBatch,
+        InvalidTreasuryShare,
+        NoPriceOracle,
+        PriceOracleFailed,
+        InvalidSlippage,
+        /// The oracle price moved further from the reference price than the
+        /// proposal allows.
+        PriceOutOfBounds {
+            price: Balance,
+        },
+        InvalidInstallments,
+        NotInstallments,
+        InstallmentsNotStarted,
+        InstallmentsHalted,
+        NoTrancheDue,
+        InvalidConfigExport,
+        CommitmentExists,
+        UnknownCommitment,
+        RevealWindowClosed,
+        InvalidStartTime,
+        VotingNotStarted,
+        InvalidThresholds,
+        ApprovalNotReached,
+        InvalidActivationTime,
+        OutsideExecutionWindow,
+        NotAdministered,
+        UnknownAdapter,
+        /// End of the lockup of the shares or loot being redeemed.
+        LockedUp {
+            until: u64,
+        },
+        RedemptionsDisabled,
+        InsufficientShares,
+        /// Earliest time the redemption queue may be settled again.
+        SettlementNotDue {
+            due_at: u64,
+        },
+        /// Start of the next deposit window.
+        DepositWindowClosed {
+            opens_at: u64,
+        },
     }
 
+    /// Stored as a single cell per proposal; the scalar fields use compact SCALE
+    /// encoding so small timestamps and amounts don't pay for their full width.
     #[derive(Encode, Decode)]
     #[cfg_attr(
         feature = "std",
@@ -48,12 +326,56 @@ pub mod dao {
 
     pub struct Proposal {
         to: AccountId,
+        #[codec(compact)]
         vote_start: u64,
+        #[codec(compact)]
         vote_end: u64,
         executed: bool,
+        #[codec(compact)]
         amount: Balance,
     }
 
+    impl Proposal {
+        /// Block timestamp (in milliseconds) voting starts at: when the proposal was
+        /// created, unless it was scheduled with `Governor::propose_at`.
+        pub fn vote_start(&self) -> u64 {
+            self.vote_start
+        }
+
+        /// Block timestamp (in milliseconds) voting ends at, `vote_start` plus the
+        /// requested voting period.
+        pub fn vote_end(&self) -> u64 {
+            self.vote_end
+        }
+    }
+
+    /// Arguments of a single `propose` call, used by `propose_many`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    pub struct ProposalInput {
+        pub to: AccountId,
+        pub amount: Balance,
+        /// Voting period, in seconds.
+        pub duration: u64,
+    }
+
+    /// Contents of a proposal committed to before being revealed, see
+    /// `Governor::commit_proposal`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    pub enum CommittedProposal {
+        /// Arguments of `propose`.
+        Transfer(ProposalInput),
+        /// Arguments of `propose_call`.
+        Call {
+            target: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+            value: Balance,
+            duration: u64,
+        },
+    }
+
     #[derive(Encode, Decode, Default)]
     #[cfg_attr(
         feature = "std",
@@ -70,236 +392,11884 @@ pub mod dao {
         // to implement
         for_votes: u64,
         against_vote: u64,
+        /// Bitmap of the receipt buckets holding at least one voter, so pruning
+        /// only visits cells that were written.
+        receipt_buckets: u64,
     }
 
-    #[ink(storage)]
-    pub struct Governor {
-        governance_token: AccountId,
-        quorum: u8,
-        proposals: Mapping<ProposalId, Proposal>,
-        proposal_votes: Mapping<ProposalId, ProposalVote>,
-        votes: Mapping<(ProposalId, AccountId), ()>,
-        next_proposal_id: ProposalId,
+    /// Governance parameters, kept in a single storage cell that each message reads
+    /// at most once.
+    #[derive(Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct GovernorConfig {
+        /// PSP22 token whose balances weigh the votes, as of the start of each vote
+        /// through its `balance_of_at` and `total_supply_at` snapshot messages.
+        pub governance_token: AccountId,
+        /// Share of the token supply (in %) that must vote for a proposal to pass.
+        pub quorum: u8,
+        /// Cap on the native amount refunded per ballot towards its transaction
+        /// fee, priced as `REIMBURSED_VOTE_GAS` at the current rate and paid from
+        /// the `reimbursement_budget`. Zero disables reimbursement.
+        pub vote_reimbursement: Balance,
+        /// Relayer allowed to submit calls on behalf of other accounts through
+        /// `forward`. The governor checks the accounts' signatures itself.
+        pub trusted_forwarder: Option<AccountId>,
+        /// Taxonomy of proposal categories: the name of each `Category`, by index.
+        /// `GENERAL_CATEGORY` is always valid, named or not.
+        pub categories: Vec<String>,
+        /// Council allowed to open fast-tracked proposals.
+        pub guardians: Vec<AccountId>,
+        /// Share of the token supply (in %) that must vote for a fast-tracked
+        /// proposal for it to pass, on top of the quorum.
+        pub fast_track_approval: u8,
+        /// Time (in milliseconds) holders have to object to a passed proposal
+        /// before it may be executed. Zero disables objections.
+        pub objection_window: u64,
+        /// Share of the token supply (in %) whose objections send a passed
+        /// proposal back to a confirmation vote.
+        pub objection_threshold: u8,
+        /// Time (in milliseconds) over which the quorum of a proposal decays
+        /// linearly from `quorum` to `min_quorum`, so that low turnout can't stall
+        /// the DAO forever. The decay stops when the vote ends. Zero keeps the
+        /// quorum fixed.
+        pub quorum_decay_period: u64,
+        /// Quorum (in %) once `quorum_decay_period` has elapsed.
+        pub min_quorum: u8,
+        /// Native amount the rewards pool pays per ballot of at least the
+        /// `min_reward_weight` once its proposal has concluded. Zero disables
+        /// participation rewards.
+        pub participation_reward: Balance,
+        /// Length (in milliseconds) of the voting epochs participation streaks are
+        /// counted in. Zero disables streaks.
+        pub epoch_length: u64,
+        /// Extra participation reward (in %) per epoch of a voter's streak.
+        pub streak_bonus: u8,
+        /// Cap (in %) on the streak bonus.
+        pub max_streak_bonus: u8,
+        /// Epochs a member may miss before the weight of their balance starts to
+        /// decay. The weight is restored once the member's power is voted with
+        /// again. Zero disables inactivity penalties.
+        pub inactivity_grace_epochs: u32,
+        /// Share of the weight (in %) lost per epoch missed beyond the grace period.
+        pub inactivity_decay: u8,
+        /// Delegates that may vote with the power of inactive holders, see
+        /// `Governor::vote_for_inactive`.
+        pub default_delegates: Vec<AccountId>,
+        /// Epochs a holder must have missed for their power to pass to the default
+        /// delegates. Zero disables auto-delegation.
+        pub auto_delegation_epochs: u32,
+        /// `(target, selector)` pairs call proposals may invoke.
+        pub call_allowlist: Vec<(AccountId, [u8; 4])>,
+        /// Identity registry vouching that an account belongs to a unique person,
+        /// see `call::is_verified`. Quadratic funding only counts contributions
+        /// from verified accounts when set.
+        pub identity_gate: Option<AccountId>,
+        /// Whether only accounts verified by the `identity_gate` may vote, checked
+        /// when the ballot is cast. Has no effect without a gate.
+        pub verified_voting: bool,
+        /// Whether only voters verified by the `identity_gate` earn participation
+        /// rewards. Has no effect without a gate.
+        pub verified_rewards: bool,
+        /// Accounts allocating retro funding rounds, with equal weight. When empty,
+        /// token holders allocate them with the weight of their balance.
+        pub retro_badge_holders: Vec<AccountId>,
+        /// Share (in %) of each donation made through `donate` that the treasury
+        /// adds on top, e.g. 100 for 1:1 matching. Zero disables matching.
+        pub donation_match: u8,
+        /// Causes accepting donations through `donate`, each with the most the
+        /// treasury matches for it in total.
+        pub causes: Vec<(AccountId, Balance)>,
+        /// Contracts whose payments through `collect_revenue` and
+        /// `collect_token_revenue` count as revenue, each with the tag its revenue
+        /// is reported under.
+        pub revenue_sources: Vec<(AccountId, String)>,
+        /// Shares (in %) of revenue earmarked for the contributor and the
+        /// distribution pool. The rest goes to the treasury.
+        pub revenue_split: (u8, u8),
+        /// Fee members pay per `dues_period` through `pay_dues` to keep their
+        /// voting rights. Zero disables dues.
+        pub dues: Balance,
+        /// Time (in milliseconds) covered by each payment of `dues`.
+        pub dues_period: u64,
+        /// Time (in milliseconds) past the paid period before a member's voting
+        /// rights are suspended.
+        pub dues_grace_period: u64,
+        /// Membership tiers and their rights. `None` gives every account the same
+        /// rights and full weight.
+        pub tiers: Option<TierPolicy>,
+        /// Shares minted to each member admitted through a membership proposal,
+        /// and burned when they are removed. Zero disables share accounting.
+        pub membership_shares: Balance,
+        /// Term of council seats won through council elections, after which they
+        /// expire. Zero keeps seats until recalled.
+        pub council_term: u64,
+        /// Members assigned to review proposals each epoch, rotating through the
+        /// member registry. Zero disables review assignments.
+        pub review_panel: u8,
+        pub review_rotation: ReviewRotation,
+        /// Contract voting power is read from instead of the governance token, e.g.
+        /// a staking or vote-escrow contract, see `call::voting_power`. Power is
+        /// queried as of the proposal's start.
+        pub weight_source: Option<AccountId>,
+        /// Time (in milliseconds) before the start of a vote over which governance
+        /// token balances are averaged into voting power, from the token's
+        /// checkpoints, so that tokens acquired just before the snapshot only
+        /// count for the time they were held. Ignored with a `weight_source`. Zero
+        /// weighs the balances at the start of the vote.
+        pub twab_window: u64,
+        /// Cap (in %) on the share of the supply a single ballot weighs, delegated
+        /// power included. Zero disables the cap.
+        pub max_vote_weight: u8,
+        /// Share of the votes cast (in %) that must be for a constitutional
+        /// amendment for it to pass.
+        pub amendment_approval: u8,
+        /// Minimum voting period (in milliseconds) of constitutional amendments,
+        /// which can't be executed before it ends.
+        pub amendment_duration: u64,
+        /// Oracle quoting the native currency in a reference stable unit, see
+        /// `call::price`. Stable-denominated proposals can't be made without one.
+        pub price_oracle: Option<AccountId>,
+        /// Longest time (in milliseconds) ahead a proposer may schedule the start
+        /// of the vote, see `Governor::propose_at`. Zero disables scheduling.
+        pub max_start_delay: u64,
+        /// Highest quorum (in %) a proposer may require of their proposal, see
+        /// `Governor::propose_with_thresholds`. Zero disables stricter quorums.
+        pub max_requested_quorum: u8,
+        /// Highest share of the votes cast (in %) a proposer may require to be for
+        /// their proposal. Zero disables stricter approvals.
+        pub max_requested_approval: u8,
+        /// Voting periods of the proposals in each category. Categories without
+        /// an entry are bounded by `MIN_VOTING_PERIOD` and `MAX_VOTING_PERIOD`
+        /// only.
+        pub voting_periods: Vec<(Category, VotingPeriods)>,
+        /// Fixed cadence of submission, voting and execution windows proposals
+        /// follow, if any.
+        pub cycle: Option<GovernanceCycle>,
+        /// Execution adapters adapter proposals may invoke, each with its name,
+        /// e.g. a swap or lending integration, see `call::execute_adapter`.
+        pub adapters: Vec<(AccountId, String)>,
+        /// Weight (as a share of the supply scaled by `math::PRECISION`) a ballot
+        /// must carry to be reimbursed or rewarded, so that splitting a balance
+        /// over many accounts doesn't multiply what it earns.
+        pub min_reward_weight: u64,
+        /// Messages, like `accept_ownership`, admin proposals may call without
+        /// arguments to take over a contract, see `AdminAction::Accept`.
+        pub accept_selectors: Vec<[u8; 4]>,
+        /// `(target, selector)` pairs admin proposals may invoke on administered
+        /// contracts, see `AdminAction::Call` and `AdminAction::Release`.
+        pub admin_allowlist: Vec<(AccountId, [u8; 4])>,
+        /// Time (in milliseconds) a member's shares and loot stay locked after
+        /// each of its capital deposits, see `Governor::lockup_end`. Zero locks
+        /// nothing at deposit.
+        pub deposit_lockup: u64,
+        /// Time (in milliseconds) between settlements of the redemption queue,
+        /// see `Governor::settle_redemptions`. Zero disables redemption requests.
+        pub settlement_period: u64,
+        /// Share (in %) of the `Governor::redeemable_balance` a settlement leaves
+        /// in the treasury as a liquidity buffer.
+        pub liquidity_buffer: u8,
+        /// Windows capital may be deposited in, each deposit then minting shares
+        /// at the net asset value per share, see `Governor::deposit_capital`.
+        /// `None` accepts deposits at any time, without minting shares.
+        pub deposit_windows: Option<DepositWindows>,
     }
 
-    impl Governor {
-        #[ink(constructor, payable)]
-        pub fn new(governance_token: AccountId, quorum: u8) -> Self {
-            Self {
-                governance_token,
-                quorum,
-                proposals: Mapping::new(),
-                proposal_votes: Mapping::new(),
-                votes: Mapping::new(),
-                next_proposal_id: 0,
-            }
-        }
-
-        #[ink(message)]
-        pub fn next_proposal_id(&mut self) -> ProposalId {
-            self.next_proposal_id + 1
-        }
+    /// A change of the governance parameters, see `Governor::propose_config_change`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct ParamChange {
+        pub old: GovernorConfig,
+        pub new: GovernorConfig,
+        pub proposal_id: ProposalId,
+        pub timestamp: u64,
+    }
 
-        #[ink(message)]
-        pub fn get_proposal(
-            &mut self,
-            proposal_id: ProposalId,
-        ) -> Result<Proposal, GovernorError> {
-            Ok(self.proposals.get(proposal_id).unwrap())
-        }
+    /// A passed change of the governance parameters awaiting its activation, see
+    /// `Governor::propose_scheduled_config_change`.
+    #[derive(Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct ScheduledConfig {
+        pub config: GovernorConfig,
+        pub proposal_id: ProposalId,
+        pub activates_at: u64,
+    }
 
-        #[ink(message)]
-        pub fn propose(
-            &mut self,
-            to: AccountId,
-            amount: Balance,
-            duration: u64,
-        ) -> Result<(), GovernorError> {
-            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
-            ensure!(duration == 0, GovernorError::DurationError);
-            let proposal = Proposal {
-                to,
-                vote_start: self.env().block_timestamp(),
-                vote_end: duration,
-                executed: false,
-                amount,
-            };
-            self.proposals.insert(self.next_proposal_id, &proposal);
-            Ok(())
-        }
+    /// An amount denominated in the reference stable unit of the `price_oracle`, see
+    /// `Governor::propose_stable_transfer`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct StableAmount {
+        pub amount: Balance,
+        /// Oracle price when the proposal was made, scaled by `math::PRECISION`.
+        pub reference_price: Balance,
+        /// How far (in %) the price may move from `reference_price` by the time
+        /// the proposal is executed.
+        pub max_slippage: u8,
+    }
 
-        #[ink(message)]
-        pub fn vote(
-            &mut self,
-            proposal_id: ProposalId,
-            vote: VoteType,
-        ) -> Result<(), GovernorError> {
-            ensure!(
-                self.proposals.get(proposal_id).is_none(),
-                GovernorError::ProposalNotFound
-            );
-            ensure!(
-                self.proposals.get(proposal_id).unwrap().executed == true,
-                GovernorError::ProposalAlreadyExecuted
-            );
-            // ensure!(self.proposals.get(proposal_id).unwrap().vote_end >=
-            // self.env().block_timestamp(),GovernorError::VotePeriodEnded);
-            ensure!(
-                self.votes.get((proposal_id, self.env().caller())).is_some(),
-                GovernorError::AlreadyVoted
-            );
-            self.votes.insert((proposal_id, self.env().caller()), &());
-            let mut weight = self.env().balance()/1000;
-            // let total_supply = ink::env::call::build_call::
-            // <ink::env::DefaultEnvironment>() .call(self.governance_token)
-            // .gas_limit(5_000_000_000)
-            // .exec_input(
-            //     ink::env::call::ExecutionInput::new(ink::env::call::Selector::new(ink::selector_bytes!("PSP22::total_supply")))
-            // )
-            // .returns::<Balance>()
-            // .try_invoke().unwrap();
-            // weight = weight/total_supply.unwrap().unwrap();
-
-            match vote {
-                VoteType::For => {
-                    if self.proposal_votes.get(proposal_id).is_some() {
-                        self.proposal_votes.get(proposal_id).unwrap().for_votes as u128
-                            + weight;
-                    } else {
-                        let proposal_vote = ProposalVote {
-                            for_votes: weight as u64,
-                            against_vote: 0,
-                        };
-                        self.proposal_votes.insert(proposal_id, &proposal_vote);
-                    }
-                }
-                VoteType::Against => {
-                    if self.proposal_votes.get(proposal_id).is_some() {
-                        self.proposal_votes.get(proposal_id).unwrap().against_vote
-                            as u128
-                            + weight;
-                    } else {
-                        let proposal_vote = ProposalVote {
-                            for_votes: 0,
-                            against_vote: weight as u64,
-                        };
-                        self.proposal_votes.insert(proposal_id, &proposal_vote);
-                    }
-                }
-            }
+    /// Stricter requirements a proposer set for their proposal to pass, see
+    /// `Governor::propose_with_thresholds`.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct VotingThresholds {
+        /// Share of the token supply (in %) that must vote, in place of the
+        /// quorum when higher. Zero keeps the default.
+        pub quorum: u8,
+        /// Share of the votes cast (in %) that must be for the proposal, on top
+        /// of outnumbering those against. Zero keeps the default.
+        pub approval: u8,
+    }
 
-            Ok(())
-        }
+    /// A version of the constitution, see `Governor::propose_amendment`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct Amendment {
+        /// Hash of the constitution document, published off-chain.
+        pub document: [u8; 32],
+        pub proposal_id: ProposalId,
+        pub adopted_at: u64,
+    }
 
-        #[ink(message)]
-        pub fn execute(&mut self, proposal_id: ProposalId) -> Result<(), GovernorError> {
-            ensure!(
-                self.proposals.get(proposal_id).is_none(),
-                GovernorError::ProposalNotFound
-            );
-            ensure!(
-                self.proposals.get(proposal_id).unwrap().executed == true,
-                GovernorError::ProposalAlreadyExecuted
-            );
-            let total_votes = (self.proposal_votes.get(proposal_id).unwrap().for_votes
-                + self.proposal_votes.get(proposal_id).unwrap().against_vote)
-                as u8;
-            if total_votes < self.quorum {
-                return Err(GovernorError::QuorumNotReached)
-            }
-            ensure!(
-                self.proposal_votes.get(proposal_id).unwrap().for_votes >= 50,
-                GovernorError::ProposalNotAccepted
-            );
-            ensure!(
-                self.votes.get((proposal_id, self.env().caller())).is_none(),
-                GovernorError::AlreadyVoted
-            );
-            self.proposals.get(proposal_id).unwrap().executed = true;
-            // self.proposals.get(proposal_id).unwrap().to.transfer(self.proposals.
-            // get(proposal_id).unwrap().amount);
+    /// How review panels rotate through the member registry between epochs.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum ReviewRotation {
+        /// Consecutive members in order of admission, moving on by a panel each
+        /// epoch.
+        RoundRobin,
+        /// Members ordered by the hash of the epoch and their account. Panels are
+        /// not predictable from the registry order, but anyone can compute them in
+        /// advance.
+        Shuffled,
+    }
 
-            Ok(())
-        }
+    /// Change to the council a council proposal makes.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum CouncilChange {
+        /// Seats a member for a `council_term`.
+        Elect,
+        /// Removes a sitting member before the end of its term.
+        Recall,
+    }
 
-        // used for test
-        #[ink(message)]
-        pub fn now(&self) -> u64 {
-            self.env().block_timestamp()
-        }
+    /// Change to the member registry a membership proposal makes.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum MembershipChange {
+        Add,
+        Remove,
+        /// Removes a member and strips its voting rights, converting its shares
+        /// into loot it can still redeem.
+        Kick,
     }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+    /// Level of membership, from least to most rights.
+    #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(Debug, scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum Tier {
+        Observer,
+        Member,
+        Partner,
+    }
 
-        const ONE_MINUTE: u64 = 1;
+    /// Voting periods (in seconds) allowed for the proposals in one category,
+    /// see `GovernorConfig::voting_periods`.
+    #[derive(Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct VotingPeriods {
+        /// Voting period of the proposals made with a duration of zero.
+        pub default: u64,
+        pub min: u64,
+        pub max: u64,
+    }
 
-        fn create_contract(initial_balance: Balance) -> Governor {
-            let accounts: ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> =
-                default_accounts();
-            set_sender(accounts.alice);
-            set_balance(contract_id(), initial_balance);
-            Governor::new(AccountId::from([0x01; 32]), 50)
-        }
+    /// Governance cycles of `length` milliseconds, counted from the epoch of the
+    /// clock. Each opens with a `submission` window, followed by a `voting` one in
+    /// which the proposals submitted are voted on, and the rest of the cycle is
+    /// for executing them. Proposals submitted after the submission window queue
+    /// for the next cycle.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct GovernanceCycle {
+        pub length: u64,
+        pub submission: u64,
+        pub voting: u64,
+    }
+
+    /// Deposit windows open for the first `length` milliseconds of every `period`,
+    /// counted from the epoch of the clock.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct DepositWindows {
+        pub period: u64,
+        pub length: u64,
+    }
+
+    /// Window of a `GovernanceCycle`.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    pub enum CyclePhase {
+        Submission,
+        Voting,
+        Execution,
+    }
+
+    /// How members are tiered and what each tier may do. A member's tier is the
+    /// higher of the one their stake reaches and the one assigned by governance.
+    #[derive(Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct TierPolicy {
+        /// Governance token balance from which holders are `Tier::Member`s.
+        pub member_stake: Balance,
+        /// Governance token balance from which holders are `Tier::Partner`s.
+        pub partner_stake: Balance,
+        /// Share (in %, at most 100) of their balance members of each tier vote
+        /// with, in `Tier` order.
+        pub weights: [u8; 3],
+        /// Lowest tier allowed to open proposals.
+        pub propose: Tier,
+        /// Lowest tier of guardians allowed to fast-track proposals.
+        pub fast_track: Tier,
+        /// Lowest tier allowed to sit on committees: retro funding badge holders
+        /// and default delegates.
+        pub committee: Tier,
+    }
+
+    /// Secondary index of live proposals, see `Governor::indexed_proposals`.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    enum ProposalIndex {
+        Category(Category),
+        Proposer(AccountId),
+    }
+
+    /// Message a call proposal invokes on its `Proposal::to` account, sending its
+    /// `Proposal::amount` along.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct ProposalCall {
+        selector: [u8; 4],
+        /// SCALE encoded arguments.
+        input: Vec<u8>,
+    }
+
+    /// What an admin proposal does with the contract at its `Proposal::to`, by
+    /// invoking a message of it, see `Governor::propose_admin_action`.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum AdminAction {
+        /// Accepts ownership or admin rights the contract has offered the
+        /// governor, which then administers it.
+        Accept,
+        /// Exercises an owner-only message of an administered contract.
+        Call,
+        /// Hands over or renounces the governor's rights on an administered
+        /// contract, which it then no longer administers.
+        Release,
+    }
+
+    /// What an NFT proposal does with the PSP34 collection at its `Proposal::to`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum NftAction {
+        /// Transfers a token held by the treasury to `to`.
+        Transfer { id: Id, to: AccountId },
+        /// Lets `operator`, e.g. a marketplace, transfer the token `id`, or all of
+        /// the treasury's tokens if `None`.
+        Approve {
+            operator: AccountId,
+            id: Option<Id>,
+            approved: bool,
+        },
+    }
+
+    /// Non-binding multi-choice vote, tallied per option.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct Poll {
+        options: Vec<String>,
+        /// Weight behind each option, in `math::PRECISION` units.
+        tallies: Vec<u64>,
+    }
+
+    /// Treasury budget split between recipients in proportion to the weight
+    /// voters allocate to each.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct Allocation {
+        recipients: Vec<AccountId>,
+        /// Weight allocated to each recipient, in `math::PRECISION` units.
+        tallies: Vec<u64>,
+    }
+
+    /// Quadratic funding round opened by executing its proposal, whose `amount` is
+    /// the matching pool.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct FundingRound {
+        candidates: Vec<AccountId>,
+        #[codec(compact)]
+        matching_pool: Balance,
+        /// Time (in milliseconds) contributions stay open once the round opens.
+        #[codec(compact)]
+        contribution_period: u64,
+        /// End of contributions, `None` until the round's proposal is executed.
+        closes_at: Option<u64>,
+        /// Total contributed to each candidate.
+        contributions: Vec<Balance>,
+        /// Sum over contributors of the square root of their total contribution to
+        /// each candidate.
+        sqrt_sums: Vec<u128>,
+        /// Matching paid to each candidate, `None` until the round is closed.
+        matched: Option<Vec<Balance>>,
+    }
+
+    /// Amount paid out in equal tranches, one per `interval`, once its proposal is
+    /// executed. See `Governor::propose_installments`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct Installments {
+        recipient: AccountId,
+        amount: Balance,
+        tranches: u32,
+        /// Time (in milliseconds) between tranches.
+        interval: u64,
+        /// Due time of the first tranche, `None` until the proposal is executed.
+        started_at: Option<u64>,
+        /// Tranches paid out so far.
+        released: u32,
+        /// Set by governance to stop the tranches not released yet.
+        halted: bool,
+    }
+
+    impl Installments {
+        /// Amount paid out by the `released` tranches. The last tranche also pays
+        /// the remainder of the division into tranches.
+        fn released_amount(&self) -> Balance {
+            if self.released == self.tranches {
+                return self.amount
+            }
+            self.amount / Balance::from(self.tranches) * Balance::from(self.released)
+        }
+    }
+
+    /// Work done for the DAO that a retro funding round may reward.
+    #[derive(Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct Nomination {
+        /// Hash of the contribution's description, published off-chain.
+        pub contribution: [u8; 32],
+        pub recipient: AccountId,
+    }
+
+    /// Retroactive funding round opened by executing its proposal, whose `amount`
+    /// is the pot shared between the nominations.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct RetroRound {
+        /// Past period (timestamps in milliseconds) whose contributions are
+        /// rewarded.
+        period: (u64, u64),
+        #[codec(compact)]
+        pot: Balance,
+        /// Time (in milliseconds) nominations stay open once the round opens.
+        #[codec(compact)]
+        nomination_period: u64,
+        /// Time (in milliseconds) allocations stay open after nominations.
+        #[codec(compact)]
+        allocation_period: u64,
+        /// Start of nominations, `None` until the round's proposal is executed.
+        opened_at: Option<u64>,
+        nominations: Vec<Nomination>,
+        /// Weight allocated to each nomination, in `math::PRECISION` units.
+        tallies: Vec<u64>,
+        closed: bool,
+    }
+
+    /// What a balance is held in.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum Asset {
+        Native,
+        Psp22(AccountId),
+    }
+
+    /// Pools revenue is earmarked for, see `GovernorConfig::revenue_split`.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum RevenuePool {
+        Contributors,
+        Distribution,
+    }
+
+    /// Purpose of a treasury movement, see `Governor::ledger_entry`.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum LedgerCategory {
+        /// Transfers and calls made by executed proposals.
+        Investment,
+        /// Payouts from the contributor pool.
+        Payroll,
+        /// Budget allocations, funding rounds and donations.
+        Grant,
+        Revenue,
+        Fee,
+        /// Payouts from the distribution pool.
+        Distribution,
+        /// Participation rewards and vote reimbursements.
+        Rewards,
+        /// Membership dues.
+        Dues,
+        /// Capital paid in by members and returned on their exit.
+        Capital,
+    }
+
+    /// Side of the treasury's books a movement is entered on.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum Flow {
+        Income,
+        Expense,
+    }
+
+    /// A movement of funds into or out of the treasury.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct LedgerEntry {
+        pub category: LedgerCategory,
+        pub flow: Flow,
+        pub asset: Asset,
+        /// Account the funds came from or went to.
+        pub counterparty: AccountId,
+        /// Proposal or round the movement belongs to, if any.
+        pub proposal_id: Option<ProposalId>,
+        #[codec(compact)]
+        pub amount: Balance,
+        #[codec(compact)]
+        pub timestamp: u64,
+    }
+
+    /// Income and expenses of one category over a `LEDGER_PERIOD`.
+    #[derive(Encode, Decode, Default)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct LedgerTotals {
+        pub income: Balance,
+        pub expense: Balance,
+    }
+
+    /// A member's native-asset capital account.
+    #[derive(Encode, Decode, Default)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct CapitalAccount {
+        /// Capital deposited with `deposit_capital`.
+        pub contributed: Balance,
+        /// Profits distributed to the member from the distribution pool.
+        pub allocated: Balance,
+        /// Capital returned on exit, by redeeming loot.
+        pub withdrawn: Balance,
+    }
+
+    /// Shares a member asked to redeem, see `Governor::request_redemption`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct RedemptionRequest {
+        pub member: AccountId,
+        pub shares: Balance,
+        pub requested_at: u64,
+    }
+
+    /// Donations routed to a cause through `Governor::donate`.
+    #[derive(Encode, Decode, Default)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct CauseAccount {
+        donated: Balance,
+        /// Amount the treasury matched, counted against the cause's cap.
+        matched: Balance,
+    }
+
+    /// What a ballot is cast for.
+    enum Choice {
+        Vote(VoteType),
+        /// Index of a poll option.
+        Option(u8),
+        /// Share (in %) of the ballot's weight going to each allocation recipient.
+        Split(Vec<u8>),
+    }
+
+    /// A call relayed by the trusted forwarder, see `Governor::forward`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    pub enum ForwardedCall {
+        Propose {
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+        },
+        Vote {
+            proposal_id: ProposalId,
+            vote: VoteType,
+        },
+        ClaimReimbursements {
+            proposal_ids: Vec<ProposalId>,
+        },
+    }
+
+    /// A ballot cast off-chain. The leaves of an anchored tally are
+    /// `merkle::leaf(&(proposal_id, ballot))`.
+    #[derive(Clone, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    pub struct Ballot {
+        pub voter: AccountId,
+        pub vote: VoteType,
+        pub weight: u64,
+    }
+
+    /// Tally of a proposal whose votes are aggregated off-chain and anchored by its
+    /// aggregator once voting has ended.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct AnchoredTally {
+        aggregator: AccountId,
+        /// Merkle root of the ballots, `None` until the tally is anchored.
+        root: Option<[u8; 32]>,
+        #[codec(compact)]
+        anchored_at: u64,
+        for_votes: u64,
+        against_vote: u64,
+        /// Voters who have corrected their ballot; each may do so once.
+        disputed: Vec<AccountId>,
+    }
+
+    /// Objections lodged against a passed proposal, see
+    /// `Governor::start_objection_window`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct ObjectionWindow {
+        #[codec(compact)]
+        started_at: u64,
+        /// Share of the supply objecting, in `math::PRECISION` units.
+        weight: u64,
+        objectors: Vec<AccountId>,
+    }
+
+    /// Sentiment a member signals on a proposal ahead of, or alongside, its vote.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub enum Sentiment {
+        Endorse,
+        Oppose,
+    }
+
+    /// Endorsements and objections signaled on a proposal, weighted by the
+    /// signalers' share of the supply in `math::PRECISION` units when signaled.
+    /// They don't count towards the vote.
+    #[derive(Encode, Decode, Default)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct Endorsements {
+        pub endorsed: u64,
+        pub opposed: u64,
+        pub signalers: u32,
+    }
+
+    /// Compact record of a pruned proposal, kept so its result stays verifiable on
+    /// chain.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct ProposalSummary {
+        pub executed: bool,
+        pub for_votes: u64,
+        pub against_vote: u64,
+        pub amount: Balance,
+        /// `blake2x256` hash of the SCALE encoded recipient.
+        pub recipient_hash: [u8; 32],
+    }
+
+    /// What a deployed governor was built from, see `Governor::code_info`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    pub struct CodeInfo {
+        /// Semantic version of the contract crate.
+        pub version: String,
+        /// Optional cargo features the code was built with.
+        pub features: Vec<String>,
+        pub storage_version: u16,
+        pub events_version: u16,
+        pub config_export_version: u16,
+    }
+
+    /// Lifetime governance counters, see `Governor::stats`.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    pub struct GovernanceStats {
+        pub proposals_created: u64,
+        pub proposals_executed: u64,
+        /// Proposals pruned without having been executed.
+        pub proposals_defeated: u64,
+        /// Weight cast per proposal created, as a share of the supply in
+        /// `math::PRECISION` units.
+        pub average_turnout: u64,
+        /// Accounts that have cast at least one ballot.
+        pub unique_voters: u32,
+        /// Native currency the treasury ledger recorded coming in and going out.
+        pub treasury: LedgerTotals,
+    }
+
+    /// Operation reported by an `OperationFailed` event.
+    #[derive(Copy, Clone, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    pub enum Operation {
+        Execute,
+    }
+
+    /// What `simulate_execute` found: how each check `execute` makes would go for a
+    /// proposal right now.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(Debug, PartialEq, Eq, scale_info::TypeInfo))]
+    pub struct ExecutionReport {
+        /// Error `execute` would fail with, or `None` if it would succeed.
+        pub error: Option<GovernorError>,
+        /// Whether enough of the supply voted. `false` while the tally of an
+        /// off-chain vote isn't final.
+        pub quorum_reached: bool,
+        /// Whether the tally carries the proposal, quorum and approval thresholds
+        /// included.
+        pub passed: bool,
+        /// Whether the objection window and the minimum voting period of
+        /// amendments are over.
+        pub unlocked: bool,
+        /// Whether the call of a call proposal is on the `call_allowlist`.
+        pub call_allowed: bool,
+        /// Whether the treasury holds the native amount paid out.
+        pub funded: bool,
+    }
+
+    /// Consecutive epochs in which a member voted on every proposal opened.
+    #[derive(Encode, Decode, Default)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct VotingStreak {
+        /// Latest epoch the member voted on a proposal of.
+        epoch: u32,
+        /// Ballots the member cast on proposals of `epoch`.
+        ballots: u32,
+        /// Latest epoch in which the member voted on every proposal.
+        completed_epoch: Option<u32>,
+        /// Consecutive completed epochs up to `completed_epoch`.
+        length: u32,
+        longest: u32,
+    }
+
+    /// Prepaid fee a delegator streams to their delegate.
+    #[derive(Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct FeeStream {
+        delegate: AccountId,
+        /// Amount paid per `FEE_PERIOD`, fixed when the stream is opened.
+        rate: Balance,
+        /// Deposit left to stream.
+        remaining: Balance,
+        settled_at: u64,
+    }
+
+    /// Profile a candidate delegate publishes for delegation UIs, see
+    /// `Governor::set_delegate_profile`.
+    #[derive(Clone, Encode, Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct DelegateProfile {
+        /// Hash of the delegate's statement, published off-chain.
+        pub statement: [u8; 32],
+        /// Hash of the delegate's contact details.
+        pub contact: [u8; 32],
+        /// Categories the delegate is interested in voting on, in order.
+        pub categories: Vec<Category>,
+    }
+
+    /// Aggregate general delegation, counting each delegator's balance as of when
+    /// they delegated. See `Governor::delegation_stats`.
+    #[derive(Encode, Decode, Default)]
+    #[cfg_attr(
+        feature = "std",
+        derive(
+            Debug,
+            PartialEq,
+            Eq,
+            scale_info::TypeInfo,
+            ink::storage::traits::StorageLayout
+        )
+    )]
+    pub struct DelegationStats {
+        pub delegated_supply: Balance,
+        /// Delegates with a non-zero delegated balance.
+        pub active_delegates: u32,
+    }
+
+    #[ink(storage)]
+    pub struct Governor {
+        /// Governance parameters in force, without the lists below: few messages
+        /// need those, so each is kept in its own cell, see `store_config`.
+        config: Lazy<GovernorConfig>,
+        categories: Lazy<Vec<String>>,
+        guardians: Lazy<Vec<AccountId>>,
+        default_delegates: Lazy<Vec<AccountId>>,
+        call_allowlist: Lazy<CallAllowlist>,
+        retro_badge_holders: Lazy<Vec<AccountId>>,
+        causes: Lazy<Vec<(AccountId, Balance)>>,
+        revenue_sources: Lazy<Vec<(AccountId, String)>>,
+        voting_periods: Lazy<Vec<(Category, VotingPeriods)>>,
+        adapters: Lazy<Vec<(AccountId, String)>>,
+        accept_selectors: Lazy<Vec<[u8; 4]>>,
+        admin_allowlist: Lazy<CallAllowlist>,
+        proposals: Mapping<ProposalId, Proposal>,
+        proposal_votes: Mapping<ProposalId, ProposalVote>,
+        /// Sorted voter lists per `(proposal, bucket)`, packing many receipts into
+        /// one storage cell.
+        receipts: Mapping<ReceiptBucket, Vec<AccountId>>,
+        /// Enumerable set of live (not yet pruned) proposal ids, since `Mapping` can't
+        /// be iterated.
+        proposal_ids: Mapping<u32, ProposalId>,
+        proposal_id_positions: Mapping<ProposalId, u32>,
+        proposal_count: u32,
+        next_proposal_id: ProposalId,
+        /// Secondary indexes of live proposals, laid out like `proposal_ids`.
+        indexed_proposals: Mapping<(ProposalIndex, u32), ProposalId>,
+        indexed_positions: Mapping<(ProposalIndex, ProposalId), u32>,
+        index_lengths: Mapping<ProposalIndex, u32>,
+        fees_collected: Mapping<AccountId, Balance>,
+        /// Cold fields live in their own storage cells so that the hot messages
+        /// (`propose`, `vote`, `execute`) don't load and re-encode them with the root.
+        total_fees_collected: Lazy<Balance>,
+        /// Off-chain tallies of hybrid proposals.
+        anchored_tallies: Mapping<ProposalId, AnchoredTally>,
+        /// Unclaimed fee reimbursements per `(proposal, voter)`.
+        reimbursements: Mapping<(ProposalId, AccountId), Balance>,
+        /// Funds left for fee reimbursements, see `fund_reimbursements`.
+        reimbursement_budget: Lazy<Balance>,
+        /// Funds set aside for participation rewards, see `fund_rewards`.
+        reward_pool: Lazy<Balance>,
+        /// Unclaimed participation rewards per `(proposal, voter)`.
+        participation_rewards: Mapping<(ProposalId, AccountId), Balance>,
+        /// Proposals opened in each epoch.
+        epoch_proposals: Mapping<u32, u32>,
+        voting_streaks: Mapping<AccountId, VotingStreak>,
+        /// Latest epoch in which each account's power was voted with.
+        last_active_epochs: Mapping<AccountId, u32>,
+        /// Hot accounts allowed to vote for a holder, keyed by holder.
+        voting_keys: Mapping<AccountId, AccountId>,
+        /// Nonce the next call forwarded for each account must be signed with.
+        forward_nonces: Mapping<AccountId, u64>,
+        /// Delegate of each delegating account.
+        delegates: Mapping<AccountId, AccountId>,
+        /// Accounts delegating directly to each delegate.
+        delegators: Mapping<AccountId, Vec<AccountId>>,
+        /// Balance each delegating account had when it delegated.
+        delegated_balances: Mapping<AccountId, Balance>,
+        /// Sum of the `delegated_balances` of each delegate's direct delegators.
+        delegated_weights: Mapping<AccountId, Balance>,
+        delegation_stats: Lazy<DelegationStats>,
+        /// Up to `MAX_TOP_DELEGATES` delegates by `delegated_weights`, heaviest
+        /// first.
+        top_delegates: Lazy<Vec<(AccountId, Balance)>>,
+        polls: Mapping<ProposalId, Poll>,
+        /// Recipients and tallies of budget allocations, whose budget is the
+        /// proposal's `amount`.
+        allocations: Mapping<ProposalId, Allocation>,
+        /// Recipients and amounts of batch transfers, which add up to the
+        /// proposal's `amount`.
+        batch_transfers: Mapping<ProposalId, Transfers>,
+        /// Share of the treasury (in %) each treasury share proposal pays out,
+        /// capped at the proposal's `amount`.
+        treasury_shares: Mapping<ProposalId, u8>,
+        stable_amounts: Mapping<ProposalId, StableAmount>,
+        voting_thresholds: Mapping<ProposalId, VotingThresholds>,
+        /// Installment payouts, keyed by the proposal approving them. They outlive
+        /// the proposal until every tranche is released.
+        installments: Mapping<ProposalId, Installments>,
+        /// Installment payouts each halt proposal stops.
+        installment_halts: Mapping<ProposalId, ProposalId>,
+        /// Quadratic funding rounds, keyed by the proposal funding them. They
+        /// outlive the proposal until closed.
+        funding_rounds: Mapping<ProposalId, FundingRound>,
+        /// Total each contributor gave to a candidate of a round, by index.
+        round_contributions: Mapping<RoundContribution, Balance>,
+        /// Retroactive funding rounds, keyed by the proposal funding them. They
+        /// outlive the proposal until closed.
+        retro_rounds: Mapping<ProposalId, RetroRound>,
+        /// Accounts that have allocated each retro round.
+        retro_allocators: Mapping<(ProposalId, AccountId), ()>,
+        cause_accounts: Mapping<AccountId, CauseAccount>,
+        /// Messages invoked by call proposals.
+        proposal_calls: Mapping<ProposalId, ProposalCall>,
+        /// Actions of admin proposals, and the messages they invoke.
+        admin_actions: Mapping<ProposalId, (AdminAction, ProposalCall)>,
+        /// Contracts the governor owns or is the admin of.
+        administered: Lazy<Vec<AccountId>>,
+        /// Payloads adapter proposals pass to the adapter at their `Proposal::to`.
+        adapter_payloads: Mapping<ProposalId, Vec<u8>>,
+        /// Actions of NFT proposals.
+        nft_actions: Mapping<ProposalId, NftAction>,
+        /// Collections the treasury holds tokens of.
+        nft_collections: Lazy<Vec<AccountId>>,
+        /// Tokens the treasury holds, per collection.
+        nft_holdings: Mapping<AccountId, Vec<Id>>,
+        /// Revenue collected from each source, per asset.
+        revenue: Mapping<(AccountId, Asset), Balance>,
+        /// Revenue earmarked for each pool and not yet paid out, per asset.
+        revenue_pools: Mapping<(RevenuePool, Asset), Balance>,
+        /// Balance of each PSP22 token reported as revenue and still held, which
+        /// new reports are checked against.
+        token_revenue: Mapping<AccountId, Balance>,
+        /// Pool each pool payout proposal is paid from.
+        pool_payouts: Mapping<ProposalId, (RevenuePool, Asset)>,
+        /// Every treasury movement, in order.
+        ledger: Mapping<u32, LedgerEntry>,
+        ledger_length: Lazy<u32>,
+        /// Native totals of the whole ledger.
+        treasury_totals: Lazy<LedgerTotals>,
+        /// Roll-ups of the ledger per `(period, category, asset)`, periods being
+        /// counted in `LEDGER_PERIOD`s since the epoch.
+        ledger_totals: Mapping<LedgerPeriod, LedgerTotals>,
+        /// End of the period each member's dues cover.
+        dues_paid_until: Mapping<AccountId, u64>,
+        /// Tiers assigned by governance, see `TierPolicy`.
+        assigned_tiers: Mapping<AccountId, Tier>,
+        /// Member and tier of each tier assignment proposal.
+        tier_assignments: Mapping<ProposalId, (AccountId, Tier)>,
+        /// Member registry.
+        members: Mapping<AccountId, ()>,
+        member_count: Lazy<u32>,
+        /// Members in order of admission.
+        member_list: Lazy<Vec<AccountId>>,
+        /// Account and change of each membership proposal.
+        membership_changes: Mapping<ProposalId, (AccountId, MembershipChange)>,
+        /// Membership proposal awaiting execution, per account.
+        pending_memberships: Mapping<AccountId, ProposalId>,
+        /// Shares held by each member, see `membership_shares`.
+        shares: Mapping<AccountId, Balance>,
+        total_shares: Lazy<Balance>,
+        /// Non-voting, redeemable stake of kicked members.
+        loot: Mapping<AccountId, Balance>,
+        total_loot: Lazy<Balance>,
+        /// Accounts stripped of voting rights by a guild kick, until readmitted.
+        kicked: Mapping<AccountId, ()>,
+        capital_accounts: Mapping<AccountId, CapitalAccount>,
+        /// End of each member's lockup, before which its shares and loot can't be
+        /// redeemed.
+        lockups: Mapping<AccountId, u64>,
+        /// Member and lockup end of each lockup proposal.
+        lockup_changes: Mapping<ProposalId, (AccountId, u64)>,
+        /// Pending redemption requests by position, in order from
+        /// `redemption_head` up to `redemption_tail`.
+        redemption_requests: Mapping<u32, RedemptionRequest>,
+        redemption_head: Lazy<u32>,
+        redemption_tail: Lazy<u32>,
+        /// Shares each member has pending in the redemption queue.
+        queued_shares: Mapping<AccountId, Balance>,
+        /// Time of the latest settlement of the redemption queue.
+        last_settlement: Lazy<u64>,
+        /// End of the term of each elected council member, which acts as a
+        /// guardian while it lasts.
+        council_terms: Mapping<AccountId, u64>,
+        /// Accounts elected to the council, including expired terms.
+        council_members: Lazy<Vec<AccountId>>,
+        council_changes: Mapping<ProposalId, (AccountId, CouncilChange)>,
+        /// Document hash each amendment proposal would adopt.
+        amendments: Mapping<ProposalId, [u8; 32]>,
+        /// Versions of the constitution, oldest first.
+        constitution_history: Mapping<u32, Amendment>,
+        constitution_versions: Lazy<u32>,
+        /// Parameters each config change proposal would put in force.
+        config_changes: Mapping<ProposalId, GovernorConfig>,
+        /// Changes of the governance parameters, oldest first.
+        param_history: Mapping<u32, ParamChange>,
+        param_history_length: Lazy<u32>,
+        /// Time each scheduled config change proposal would take effect at.
+        config_activations: Mapping<ProposalId, u64>,
+        /// Executed config changes yet to take effect.
+        scheduled_configs: Mapping<ProposalId, ScheduledConfig>,
+        /// Activation time of each of the `scheduled_configs`, soonest first.
+        config_schedule: Lazy<Vec<(u64, ProposalId)>>,
+        /// Proposals opened through `propose_fast_track`.
+        fast_tracked: Mapping<ProposalId, ()>,
+        /// Objections against passed proposals awaiting execution.
+        objection_windows: Mapping<ProposalId, ObjectionWindow>,
+        endorsements: Mapping<ProposalId, Endorsements>,
+        /// Latest sentiment and weight each account signaled on a proposal.
+        sentiments: Mapping<(ProposalId, AccountId), SignaledSentiment>,
+        /// Proposals sent back to a confirmation vote by objections, which can't
+        /// be objected to again.
+        confirmation_votes: Mapping<ProposalId, ()>,
+        /// Account that submitted each live proposal.
+        proposers: Mapping<ProposalId, AccountId>,
+        /// Category of each proposal not in `GENERAL_CATEGORY`.
+        proposal_categories: Mapping<ProposalId, Category>,
+        /// Delegate of each account for one category, overriding `delegates` on
+        /// proposals in that category.
+        topic_delegates: Mapping<TopicDelegation, AccountId>,
+        /// Accounts delegating directly to each delegate for one category.
+        topic_delegators: Mapping<TopicDelegation, Vec<AccountId>>,
+        /// History of the general and topic delegate of each account, see
+        /// `delegate_at`.
+        delegate_checkpoints: Mapping<DelegationIndex, DelegateCheckpoint>,
+        delegate_checkpoint_counts: Mapping<Delegation, u32>,
+        /// History of the direct general and topic delegators of each delegate, see
+        /// `delegators_at`.
+        delegator_checkpoints: Mapping<DelegationIndex, DelegatorsCheckpoint>,
+        delegator_checkpoint_counts: Mapping<Delegation, u32>,
+        /// Fee rate each delegate asks per `FEE_PERIOD`.
+        delegate_fees: Mapping<AccountId, Balance>,
+        fee_streams: Mapping<AccountId, FeeStream>,
+        /// Streamed fees delegates haven't claimed yet.
+        delegate_earnings: Mapping<AccountId, Balance>,
+        /// Native balance held for others, like fee deposits and unclaimed
+        /// earnings, which treasury payouts can't spend.
+        reserved_balance: Lazy<Balance>,
+        delegate_profiles: Mapping<AccountId, DelegateProfile>,
+        executed_count: Lazy<u64>,
+        /// Proposals pruned without having been executed.
+        defeated_count: Lazy<u64>,
+        /// Sum of the weight of every ballot cast.
+        weight_cast: Lazy<u128>,
+        /// Accounts that have cast a ballot.
+        voters: Mapping<AccountId, ()>,
+        voter_count: Lazy<u32>,
+        /// Final results of pruned proposals.
+        archive: Mapping<ProposalId, ProposalSummary>,
+        /// Time each pending proposal commitment was made at.
+        proposal_commitments: Mapping<[u8; 32], u64>,
+        /// Delegates with a profile, in order of registration.
+        profiled_delegates: Lazy<Vec<AccountId>>,
+        /// How far `advance_time` moved the clock ahead of the block timestamp.
+        #[cfg(feature = "test-utils")]
+        time_offset: Lazy<u64>,
+    }
+
+    /// Emitted when a source contract routes protocol fees into the treasury.
+    #[ink(event)]
+    pub struct FeeCollected {
+        schema_version: u16,
+        #[ink(topic)]
+        source: AccountId,
+        amount: Balance,
+    }
+
+    /// Emitted when a revenue source pays the treasury.
+    #[ink(event)]
+    pub struct RevenueCollected {
+        schema_version: u16,
+        #[ink(topic)]
+        source: AccountId,
+        tag: String,
+        asset: Asset,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct MemberAdded {
+        schema_version: u16,
+        #[ink(topic)]
+        member: AccountId,
+        shares: Balance,
+    }
+
+    #[ink(event)]
+    pub struct MemberRemoved {
+        schema_version: u16,
+        #[ink(topic)]
+        member: AccountId,
+        /// Shares burned.
+        shares: Balance,
+    }
+
+    #[ink(event)]
+    pub struct ConfigChanged {
+        schema_version: u16,
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        /// Position of the change in the parameter history.
+        index: u32,
+    }
+
+    #[ink(event)]
+    pub struct ConstitutionAmended {
+        schema_version: u16,
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        version: u32,
+        document: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct CouncilElected {
+        schema_version: u16,
+        #[ink(topic)]
+        member: AccountId,
+        term_end: u64,
+    }
+
+    #[ink(event)]
+    pub struct CouncilRecalled {
+        schema_version: u16,
+        #[ink(topic)]
+        member: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct MemberKicked {
+        schema_version: u16,
+        #[ink(topic)]
+        member: AccountId,
+        /// Shares converted to loot.
+        loot: Balance,
+    }
+
+    #[ink(event)]
+    pub struct CapitalDeposited {
+        schema_version: u16,
+        #[ink(topic)]
+        member: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct LootRedeemed {
+        schema_version: u16,
+        #[ink(topic)]
+        member: AccountId,
+        loot: Balance,
+        amount: Balance,
+    }
+
+    /// Emitted when a member queues shares for redemption.
+    #[ink(event)]
+    pub struct RedemptionRequested {
+        schema_version: u16,
+        #[ink(topic)]
+        member: AccountId,
+        shares: Balance,
+        position: u32,
+    }
+
+    /// Emitted when a settlement pays out a redemption request.
+    #[ink(event)]
+    pub struct SharesRedeemed {
+        schema_version: u16,
+        #[ink(topic)]
+        member: AccountId,
+        shares: Balance,
+        amount: Balance,
+    }
+
+    /// Emitted when a donation is routed to a cause.
+    #[ink(event)]
+    pub struct DonationMatched {
+        schema_version: u16,
+        #[ink(topic)]
+        cause: AccountId,
+        #[ink(topic)]
+        donor: AccountId,
+        amount: Balance,
+        matched: Balance,
+    }
+
+    /// Emitted when an aggregator anchors the off-chain tally of a proposal.
+    #[ink(event)]
+    pub struct TallyAnchored {
+        schema_version: u16,
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        root: [u8; 32],
+        for_votes: u64,
+        against_vote: u64,
+    }
+
+    /// Emitted when a voter corrects their ballot in an anchored tally.
+    #[ink(event)]
+    pub struct BallotDisputed {
+        schema_version: u16,
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        voter: AccountId,
+        weight: u64,
+        vote: VoteType,
+    }
+
+    #[ink(event)]
+    pub struct VotingKeySet {
+        schema_version: u16,
+        #[ink(topic)]
+        holder: AccountId,
+        key: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        schema_version: u16,
+        #[ink(topic)]
+        delegator: AccountId,
+        delegate: Option<AccountId>,
+        /// Category the delegation is scoped to, `None` for general delegation.
+        category: Option<Category>,
+    }
+
+    #[ink(event)]
+    pub struct ObjectionLodged {
+        schema_version: u16,
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        objector: AccountId,
+        weight: u64,
+    }
+
+    #[ink(event)]
+    pub struct Endorsed {
+        schema_version: u16,
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        #[ink(topic)]
+        endorser: AccountId,
+        sentiment: Sentiment,
+        weight: u64,
+    }
+
+    /// Emitted when objections send a passed proposal back to a vote.
+    #[ink(event)]
+    pub struct ConfirmationVoteStarted {
+        schema_version: u16,
+        #[ink(topic)]
+        proposal_id: ProposalId,
+    }
+
+    /// Archival trail of a proposal whose storage has been reclaimed.
+    #[ink(event)]
+    pub struct ProposalPruned {
+        schema_version: u16,
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        executed: bool,
+        for_votes: u64,
+        against_vote: u64,
+    }
+
+    /// Emitted when an attempted operation is rejected without reverting, so
+    /// monitoring can alert on repeated failures, see `Governor::attempt_execute`.
+    #[ink(event)]
+    pub struct OperationFailed {
+        schema_version: u16,
+        op: Operation,
+        #[ink(topic)]
+        proposal_id: ProposalId,
+        error: GovernorError,
+    }
+
+    impl Governor {
+        #[ink(constructor, payable)]
+        pub fn new(governance_token: AccountId, quorum: u8) -> Self {
+            Self::with_config(GovernorConfig {
+                governance_token,
+                quorum,
+                vote_reimbursement: 0,
+                trusted_forwarder: None,
+                categories: Vec::new(),
+                guardians: Vec::new(),
+                fast_track_approval: 67,
+                objection_window: 0,
+                objection_threshold: 10,
+                quorum_decay_period: 0,
+                min_quorum: quorum,
+                participation_reward: 0,
+                epoch_length: 0,
+                streak_bonus: 0,
+                max_streak_bonus: 0,
+                inactivity_grace_epochs: 0,
+                inactivity_decay: 0,
+                default_delegates: Vec::new(),
+                auto_delegation_epochs: 0,
+                call_allowlist: Vec::new(),
+                identity_gate: None,
+                verified_voting: false,
+                verified_rewards: false,
+                retro_badge_holders: Vec::new(),
+                donation_match: 0,
+                causes: Vec::new(),
+                revenue_sources: Vec::new(),
+                revenue_split: (0, 0),
+                dues: 0,
+                dues_period: 0,
+                dues_grace_period: 0,
+                tiers: None,
+                membership_shares: 0,
+                council_term: 0,
+                review_panel: 0,
+                review_rotation: ReviewRotation::RoundRobin,
+                weight_source: None,
+                twab_window: 0,
+                max_vote_weight: 0,
+                amendment_approval: 67,
+                amendment_duration: AMENDMENT_DURATION,
+                price_oracle: None,
+                max_start_delay: 0,
+                max_requested_quorum: 0,
+                max_requested_approval: 0,
+                voting_periods: Vec::new(),
+                cycle: None,
+                adapters: Vec::new(),
+                min_reward_weight: 0,
+                accept_selectors: Vec::new(),
+                admin_allowlist: Vec::new(),
+                deposit_lockup: 0,
+                settlement_period: 0,
+                liquidity_buffer: 0,
+                deposit_windows: None,
+            })
+        }
+
+        /// Deploys a governor with the rules of another one, from the output of its
+        /// `export_config`. Fails if the export was made with another
+        /// `CONFIG_EXPORT_VERSION` or is malformed.
+        #[ink(constructor, payable)]
+        pub fn from_export(exported: Vec<u8>) -> Result<Self, GovernorError> {
+            let (version, config) =
+                <(u16, GovernorConfig)>::decode_all(&mut exported.as_slice())
+                    .map_err(|_| GovernorError::InvalidConfigExport)?;
+            ensure!(
+                version != CONFIG_EXPORT_VERSION,
+                GovernorError::InvalidConfigExport
+            );
+            Ok(Self::with_config(config))
+        }
+
+        #[ink(constructor, payable)]
+        pub fn with_config(config: GovernorConfig) -> Self {
+            let mut governor = Self {
+                config: Lazy::new(),
+                categories: Lazy::new(),
+                guardians: Lazy::new(),
+                default_delegates: Lazy::new(),
+                call_allowlist: Lazy::new(),
+                retro_badge_holders: Lazy::new(),
+                causes: Lazy::new(),
+                revenue_sources: Lazy::new(),
+                voting_periods: Lazy::new(),
+                adapters: Lazy::new(),
+                accept_selectors: Lazy::new(),
+                admin_allowlist: Lazy::new(),
+                proposals: Mapping::new(),
+                proposal_votes: Mapping::new(),
+                receipts: Mapping::new(),
+                proposal_ids: Mapping::new(),
+                proposal_id_positions: Mapping::new(),
+                proposal_count: 0,
+                next_proposal_id: ProposalId::default(),
+                indexed_proposals: Mapping::new(),
+                indexed_positions: Mapping::new(),
+                index_lengths: Mapping::new(),
+                fees_collected: Mapping::new(),
+                total_fees_collected: Lazy::new(),
+                anchored_tallies: Mapping::new(),
+                reimbursements: Mapping::new(),
+                reimbursement_budget: Lazy::new(),
+                reward_pool: Lazy::new(),
+                participation_rewards: Mapping::new(),
+                epoch_proposals: Mapping::new(),
+                voting_streaks: Mapping::new(),
+                last_active_epochs: Mapping::new(),
+                voting_keys: Mapping::new(),
+                forward_nonces: Mapping::new(),
+                delegates: Mapping::new(),
+                delegators: Mapping::new(),
+                delegated_balances: Mapping::new(),
+                delegated_weights: Mapping::new(),
+                delegation_stats: Lazy::new(),
+                top_delegates: Lazy::new(),
+                polls: Mapping::new(),
+                allocations: Mapping::new(),
+                batch_transfers: Mapping::new(),
+                treasury_shares: Mapping::new(),
+                stable_amounts: Mapping::new(),
+                voting_thresholds: Mapping::new(),
+                installments: Mapping::new(),
+                installment_halts: Mapping::new(),
+                funding_rounds: Mapping::new(),
+                round_contributions: Mapping::new(),
+                retro_rounds: Mapping::new(),
+                retro_allocators: Mapping::new(),
+                cause_accounts: Mapping::new(),
+                proposal_calls: Mapping::new(),
+                admin_actions: Mapping::new(),
+                administered: Lazy::new(),
+                adapter_payloads: Mapping::new(),
+                nft_actions: Mapping::new(),
+                nft_collections: Lazy::new(),
+                nft_holdings: Mapping::new(),
+                revenue: Mapping::new(),
+                revenue_pools: Mapping::new(),
+                token_revenue: Mapping::new(),
+                pool_payouts: Mapping::new(),
+                ledger: Mapping::new(),
+                ledger_length: Lazy::new(),
+                treasury_totals: Lazy::new(),
+                executed_count: Lazy::new(),
+                defeated_count: Lazy::new(),
+                weight_cast: Lazy::new(),
+                voters: Mapping::new(),
+                voter_count: Lazy::new(),
+                archive: Mapping::new(),
+                proposal_commitments: Mapping::new(),
+                ledger_totals: Mapping::new(),
+                dues_paid_until: Mapping::new(),
+                assigned_tiers: Mapping::new(),
+                tier_assignments: Mapping::new(),
+                members: Mapping::new(),
+                member_count: Lazy::new(),
+                member_list: Lazy::new(),
+                membership_changes: Mapping::new(),
+                pending_memberships: Mapping::new(),
+                shares: Mapping::new(),
+                total_shares: Lazy::new(),
+                loot: Mapping::new(),
+                total_loot: Lazy::new(),
+                kicked: Mapping::new(),
+                capital_accounts: Mapping::new(),
+                lockups: Mapping::new(),
+                lockup_changes: Mapping::new(),
+                redemption_requests: Mapping::new(),
+                redemption_head: Lazy::new(),
+                redemption_tail: Lazy::new(),
+                queued_shares: Mapping::new(),
+                last_settlement: Lazy::new(),
+                council_terms: Mapping::new(),
+                council_members: Lazy::new(),
+                council_changes: Mapping::new(),
+                amendments: Mapping::new(),
+                constitution_history: Mapping::new(),
+                constitution_versions: Lazy::new(),
+                config_changes: Mapping::new(),
+                param_history: Mapping::new(),
+                param_history_length: Lazy::new(),
+                config_activations: Mapping::new(),
+                scheduled_configs: Mapping::new(),
+                config_schedule: Lazy::new(),
+                fast_tracked: Mapping::new(),
+                objection_windows: Mapping::new(),
+                endorsements: Mapping::new(),
+                sentiments: Mapping::new(),
+                confirmation_votes: Mapping::new(),
+                proposers: Mapping::new(),
+                proposal_categories: Mapping::new(),
+                topic_delegates: Mapping::new(),
+                topic_delegators: Mapping::new(),
+                delegate_checkpoints: Mapping::new(),
+                delegate_checkpoint_counts: Mapping::new(),
+                delegator_checkpoints: Mapping::new(),
+                delegator_checkpoint_counts: Mapping::new(),
+                delegate_fees: Mapping::new(),
+                delegate_profiles: Mapping::new(),
+                profiled_delegates: Lazy::new(),
+                fee_streams: Mapping::new(),
+                delegate_earnings: Mapping::new(),
+                reserved_balance: Lazy::new(),
+                #[cfg(feature = "test-utils")]
+                time_offset: Lazy::new(),
+            };
+            governor.store_config(&config);
+            governor
+        }
+
+        /// Governance parameters in force, scheduled changes included once their
+        /// activation time has come.
+        #[ink(message)]
+        pub fn config(&self) -> GovernorConfig {
+            self.due_config().unwrap_or_else(|| self.stored_config())
+        }
+
+        /// The whole configuration, SCALE encoded after `CONFIG_EXPORT_VERSION`, for
+        /// factories and migration tools to deploy a governor with the same rules
+        /// through `from_export`.
+        #[ink(message)]
+        pub fn export_config(&self) -> Vec<u8> {
+            (CONFIG_EXPORT_VERSION, self.config()).encode()
+        }
+
+        /// Version of the schema of the events emitted, see `EVENTS_VERSION`.
+        #[ink(message)]
+        pub fn events_version(&self) -> u16 {
+            EVENTS_VERSION
+        }
+
+        /// Semantic version of the contract crate.
+        #[ink(message)]
+        pub fn version(&self) -> String {
+            String::from(env!("CARGO_PKG_VERSION"))
+        }
+
+        #[ink(message)]
+        pub fn code_info(&self) -> CodeInfo {
+            let features = [
+                ("views", cfg!(feature = "views")),
+                ("testing", cfg!(feature = "testing")),
+                ("invariants", cfg!(feature = "invariants")),
+                ("test-utils", cfg!(feature = "test-utils")),
+            ]
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(feature, _)| String::from(feature))
+            .collect();
+            CodeInfo {
+                version: self.version(),
+                features,
+                storage_version: STORAGE_VERSION,
+                events_version: EVENTS_VERSION,
+                config_export_version: CONFIG_EXPORT_VERSION,
+            }
+        }
+
+        #[ink(message)]
+        pub fn next_proposal_id(&mut self) -> ProposalId {
+            self.next_proposal_id
+        }
+
+        #[ink(message)]
+        pub fn get_proposal(
+            &mut self,
+            proposal_id: ProposalId,
+        ) -> Result<Proposal, GovernorError> {
+            self.proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)
+        }
+
+        /// Number of live proposals, i.e. created and not yet pruned.
+        #[ink(message)]
+        pub fn proposal_count(&self) -> u32 {
+            self.proposal_count
+        }
+
+        /// Pages through the ids of live proposals. Pruning moves the last id into
+        /// the freed slot, so the order is stable only between prunes.
+        #[cfg(feature = "views")]
+        #[ink(message)]
+        pub fn proposal_ids(&self, offset: u32, limit: u32) -> Vec<ProposalId> {
+            let end = offset.saturating_add(limit).min(self.proposal_count);
+            (offset..end)
+                .filter_map(|position| self.proposal_ids.get(position))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn has_voted(&self, proposal_id: ProposalId, account: AccountId) -> bool {
+            self.receipts
+                .get((proposal_id, Self::receipt_bucket(&account)))
+                .map_or(false, |receipts| receipts.binary_search(&account).is_ok())
+        }
+
+        /// Proposes to send `amount` from the treasury to `to`, with a voting period
+        /// of `duration` seconds, between `MIN_VOTING_PERIOD` and `MAX_VOTING_PERIOD`.
+        /// If the `voting_periods` of the proposal's category are configured, the
+        /// duration must be within them too, zero standing for their default.
+        #[ink(message)]
+        pub fn propose(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            self.create_proposal(
+                self.env().caller(),
+                to,
+                amount,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            Ok(())
+        }
+
+        /// First step of a proposal whose contents must stay hidden until it is
+        /// open, e.g. so a deal can't be front-run: commits to the
+        /// `proposal_commitment` of the caller, the proposal and a secret salt. The
+        /// proposal is made when revealed, within `REVEAL_WINDOW`.
+        #[ink(message)]
+        pub fn commit_proposal(
+            &mut self,
+            commitment: [u8; 32],
+        ) -> Result<(), GovernorError> {
+            let now = self.now();
+            ensure!(
+                self.proposal_commitments
+                    .get(commitment)
+                    .map_or(false, |committed_at| {
+                        now < committed_at.saturating_add(REVEAL_WINDOW)
+                    }),
+                GovernorError::CommitmentExists
+            );
+            self.proposal_commitments.insert(commitment, &now);
+            Ok(())
+        }
+
+        /// Makes the proposal the caller committed to with `salt`.
+        #[ink(message)]
+        pub fn reveal_proposal(
+            &mut self,
+            proposal: CommittedProposal,
+            salt: [u8; 32],
+        ) -> Result<(), GovernorError> {
+            let commitment = Self::commitment(self.env().caller(), &proposal, salt);
+            let committed_at = self
+                .proposal_commitments
+                .take(commitment)
+                .ok_or(GovernorError::UnknownCommitment)?;
+            ensure!(
+                self.now() >= committed_at.saturating_add(REVEAL_WINDOW),
+                GovernorError::RevealWindowClosed
+            );
+            match proposal {
+                CommittedProposal::Transfer(ProposalInput {
+                    to,
+                    amount,
+                    duration,
+                }) => self.propose(to, amount, duration),
+                CommittedProposal::Call {
+                    target,
+                    selector,
+                    input,
+                    value,
+                    duration,
+                } => self.propose_call(target, selector, input, value, duration),
+            }
+        }
+
+        /// Commitment to pass to `commit_proposal` for `proposer` to reveal
+        /// `proposal` with `salt`.
+        #[ink(message)]
+        pub fn proposal_commitment(
+            &self,
+            proposer: AccountId,
+            proposal: CommittedProposal,
+            salt: [u8; 32],
+        ) -> [u8; 32] {
+            Self::commitment(proposer, &proposal, salt)
+        }
+
+        /// Like `propose`, with the vote starting at `vote_start` rather than right
+        /// away, e.g. to line it up with a community call. It may be at most
+        /// `max_start_delay` ahead.
+        #[ink(message)]
+        pub fn propose_at(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+            vote_start: u64,
+        ) -> Result<(), GovernorError> {
+            let now = self.now();
+            ensure!(
+                vote_start < now || vote_start - now > self.settings().max_start_delay,
+                GovernorError::InvalidStartTime
+            );
+            Self::check_proposal_input(amount, duration)?;
+            self.insert_proposal_at(
+                self.env().caller(),
+                to,
+                amount,
+                duration,
+                GENERAL_CATEGORY,
+                vote_start,
+            )?;
+            Ok(())
+        }
+
+        /// Like `propose`, with the proposal held to `thresholds` on top of the
+        /// defaults, e.g. to require a supermajority for a controversial deal.
+        /// Each threshold may only be stricter than the default and at most the
+        /// governance-set `max_requested_quorum` or `max_requested_approval`.
+        #[ink(message)]
+        pub fn propose_with_thresholds(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+            thresholds: VotingThresholds,
+        ) -> Result<(), GovernorError> {
+            let config = self.settings();
+            ensure!(
+                thresholds.quorum > 0
+                    && (thresholds.quorum <= config.quorum
+                        || thresholds.quorum > config.max_requested_quorum),
+                GovernorError::InvalidThresholds
+            );
+            ensure!(
+                thresholds.approval > 0
+                    && (thresholds.approval <= 50
+                        || thresholds.approval > config.max_requested_approval),
+                GovernorError::InvalidThresholds
+            );
+            ensure!(
+                thresholds.quorum == 0 && thresholds.approval == 0,
+                GovernorError::InvalidThresholds
+            );
+            let proposal_id = self.create_proposal(
+                self.env().caller(),
+                to,
+                amount,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.voting_thresholds.insert(proposal_id, &thresholds);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn voting_thresholds(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Option<VotingThresholds> {
+            self.voting_thresholds.get(proposal_id)
+        }
+
+        /// Proposes to call the message `selector` of `target` with the SCALE
+        /// encoded `input`, sending `value` from the treasury along. The pair must
+        /// be on the `call_allowlist`, both now and when the proposal is executed.
+        #[ink(message)]
+        pub fn propose_call(
+            &mut self,
+            target: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+            value: Balance,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            ensure!(
+                !self.call_allowlist().contains(&(target, selector)),
+                GovernorError::CallNotAllowed
+            );
+            let proposal_id = self.insert_proposal(
+                self.env().caller(),
+                target,
+                value,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.proposal_calls
+                .insert(proposal_id, &ProposalCall { selector, input });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn proposal_call(&self, proposal_id: ProposalId) -> Option<ProposalCall> {
+            self.proposal_calls.get(proposal_id)
+        }
+
+        /// Proposes to take `action` on the contract `target` by calling its message
+        /// `selector` with the SCALE encoded `input`, e.g. `accept_ownership` to
+        /// make the governor its owner, or an owner-only message once it is.
+        /// Acceptances must call one of the `accept_selectors` without input. Calls
+        /// and releases are only possible on `administered_contracts`, with a pair
+        /// on the `admin_allowlist`. Both are checked now and when the proposal is
+        /// executed.
+        #[ink(message)]
+        pub fn propose_admin_action(
+            &mut self,
+            target: AccountId,
+            action: AdminAction,
+            selector: [u8; 4],
+            input: Vec<u8>,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            ensure!(
+                !matches!(action, AdminAction::Accept) && !self.is_administered(target),
+                GovernorError::NotAdministered
+            );
+            let admin_call = ProposalCall { selector, input };
+            self.check_admin_call(target, action, &admin_call)?;
+            let proposal_id = self.insert_proposal(
+                self.env().caller(),
+                target,
+                0,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.admin_actions
+                .insert(proposal_id, &(action, admin_call));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn admin_action(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Option<(AdminAction, ProposalCall)> {
+            self.admin_actions.get(proposal_id)
+        }
+
+        /// Proposes to have the registered execution `adapter` execute `payload`,
+        /// sending `value` from the treasury along. The adapter must still be
+        /// registered when the proposal is executed.
+        #[ink(message)]
+        pub fn propose_adapter_call(
+            &mut self,
+            adapter: AccountId,
+            payload: Vec<u8>,
+            value: Balance,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            self.check_adapter(adapter)?;
+            let proposal_id = self.insert_proposal(
+                self.env().caller(),
+                adapter,
+                value,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.adapter_payloads.insert(proposal_id, &payload);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn adapter_payload(&self, proposal_id: ProposalId) -> Option<Vec<u8>> {
+            self.adapter_payloads.get(proposal_id)
+        }
+
+        #[ink(message)]
+        pub fn administered_contracts(&self) -> Vec<AccountId> {
+            self.administered.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn is_administered(&self, target: AccountId) -> bool {
+            self.administered.get_or_default().contains(&target)
+        }
+
+        /// Records that the treasury holds the token `id` of the PSP34 `collection`,
+        /// once it has been transferred to the governor. Anyone may call it; the
+        /// ownership is checked with the collection.
+        #[ink(message)]
+        pub fn register_nft(
+            &mut self,
+            collection: AccountId,
+            id: Id,
+        ) -> Result<(), GovernorError> {
+            ensure!(
+                Self::nft_owner(collection, id.clone())? != Some(self.env().account_id()),
+                GovernorError::NotNftOwner
+            );
+            let mut held = self.nft_holdings.get(collection).unwrap_or_default();
+            if held.contains(&id) {
+                return Ok(())
+            }
+            if held.is_empty() {
+                let mut collections = self.nft_collections.get_or_default();
+                collections.push(collection);
+                self.nft_collections.set(&collections);
+            }
+            held.push(id);
+            self.nft_holdings.insert(collection, &held);
+            Ok(())
+        }
+
+        /// Proposes to transfer the token `id` of `collection`, held by the
+        /// treasury, to `to`.
+        #[ink(message)]
+        pub fn propose_nft_transfer(
+            &mut self,
+            collection: AccountId,
+            id: Id,
+            to: AccountId,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            ensure!(
+                !self.nft_holdings(collection).contains(&id),
+                GovernorError::NftNotHeld
+            );
+            self.insert_nft_proposal(collection, NftAction::Transfer { id, to }, duration)
+        }
+
+        /// Proposes to approve, or revoke, `operator` to transfer the treasury's
+        /// token `id` of `collection`, or all of them if `None`.
+        #[ink(message)]
+        pub fn propose_nft_approval(
+            &mut self,
+            collection: AccountId,
+            operator: AccountId,
+            id: Option<Id>,
+            approved: bool,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            if let Some(id) = &id {
+                ensure!(
+                    !self.nft_holdings(collection).contains(id),
+                    GovernorError::NftNotHeld
+                );
+            }
+            self.insert_nft_proposal(
+                collection,
+                NftAction::Approve {
+                    operator,
+                    id,
+                    approved,
+                },
+                duration,
+            )
+        }
+
+        #[ink(message)]
+        pub fn nft_action(&self, proposal_id: ProposalId) -> Option<NftAction> {
+            self.nft_actions.get(proposal_id)
+        }
+
+        #[ink(message)]
+        pub fn nft_collections(&self) -> Vec<AccountId> {
+            self.nft_collections.get_or_default()
+        }
+
+        /// Tokens of `collection` the treasury holds.
+        #[ink(message)]
+        pub fn nft_holdings(&self, collection: AccountId) -> Vec<Id> {
+            self.nft_holdings.get(collection).unwrap_or_default()
+        }
+
+        /// Opens a poll between 2 and `MAX_POLL_OPTIONS` labeled options, e.g.
+        /// candidate investment targets. Polls are voted on with `vote_poll` and
+        /// can't be executed.
+        #[ink(message)]
+        pub fn propose_poll(
+            &mut self,
+            options: Vec<String>,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            ensure!(
+                !(2..=MAX_POLL_OPTIONS).contains(&options.len()),
+                GovernorError::InvalidPollOptions
+            );
+            let proposer = self.env().caller();
+            let proposal_id =
+                self.insert_proposal(proposer, proposer, 0, duration, GENERAL_CATEGORY)?;
+            self.polls.insert(
+                proposal_id,
+                &Poll {
+                    tallies: ink::prelude::vec![0; options.len()],
+                    options,
+                },
+            );
+            Ok(())
+        }
+
+        /// Backs the poll option at index `option` with the caller's weight.
+        #[ink(message)]
+        pub fn vote_poll(
+            &mut self,
+            proposal_id: ProposalId,
+            option: u8,
+        ) -> Result<(), GovernorError> {
+            let config = self.settings();
+            self.cast_ballot(
+                &config,
+                self.env().caller(),
+                proposal_id,
+                Choice::Option(option),
+                Vec::new(),
+            )
+        }
+
+        #[ink(message)]
+        pub fn poll(&self, proposal_id: ProposalId) -> Option<Poll> {
+            self.polls.get(proposal_id)
+        }
+
+        /// Proposes to pay each of up to `MAX_BATCH_RECIPIENTS` recipients its amount
+        /// from the treasury, all in the same `execute`. If any transfer fails,
+        /// none of them is made.
+        #[ink(message)]
+        pub fn propose_batch_transfer(
+            &mut self,
+            transfers: Transfers,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            ensure!(
+                !(1..=MAX_BATCH_RECIPIENTS).contains(&transfers.len()),
+                GovernorError::InvalidBatch
+            );
+            let mut total: Balance = 0;
+            for (_, amount) in &transfers {
+                ensure!(*amount == 0, GovernorError::AmountShouldNotBeZero);
+                total = total
+                    .checked_add(*amount)
+                    .ok_or(GovernorError::InvalidBatch)?;
+            }
+            Self::check_proposal_input(total, duration)?;
+            let proposer = self.env().caller();
+            let proposal_id = self.insert_proposal(
+                proposer,
+                proposer,
+                total,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.batch_transfers.insert(proposal_id, &transfers);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn batch_transfer(&self, proposal_id: ProposalId) -> Option<Transfers> {
+            self.batch_transfers.get(proposal_id)
+        }
+
+        /// Proposes to send `to` a `share` (in %) of the treasury's `free_balance` at
+        /// the time of execution, rather than a fixed amount, but no more than `cap`.
+        #[ink(message)]
+        pub fn propose_treasury_share(
+            &mut self,
+            to: AccountId,
+            share: u8,
+            cap: Balance,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            ensure!(
+                !(1..=100).contains(&share),
+                GovernorError::InvalidTreasuryShare
+            );
+            let proposal_id = self.create_proposal(
+                self.env().caller(),
+                to,
+                cap,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.treasury_shares.insert(proposal_id, &share);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn treasury_share(&self, proposal_id: ProposalId) -> Option<u8> {
+            self.treasury_shares.get(proposal_id)
+        }
+
+        /// Proposes to send `to` an `amount` denominated in the reference stable unit
+        /// of the `price_oracle`, converted to the native currency at the oracle
+        /// price when executed. Execution fails if the price has moved more than
+        /// `max_slippage` (in %) from the price at the time of the proposal.
+        #[ink(message)]
+        pub fn propose_stable_transfer(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            max_slippage: u8,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            ensure!(max_slippage >= 100, GovernorError::InvalidSlippage);
+            let stable = StableAmount {
+                amount,
+                reference_price: Self::price(&self.settings())?,
+                max_slippage,
+            };
+            // Voters approve the most the treasury may pay, at the lowest price.
+            let (low, _) = Self::price_bounds(&stable);
+            let max_amount =
+                math::mul_div(amount, math::PRECISION, low).unwrap_or(Balance::MAX);
+            let proposal_id = self.create_proposal(
+                self.env().caller(),
+                to,
+                max_amount,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.stable_amounts.insert(proposal_id, &stable);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn stable_amount(&self, proposal_id: ProposalId) -> Option<StableAmount> {
+            self.stable_amounts.get(proposal_id)
+        }
+
+        /// Proposes to pay `amount` to `to` in `tranches` equal installments,
+        /// `interval` milliseconds apart, the first one once the proposal is
+        /// executed. The tranches are reserved from then on and paid through
+        /// `release_tranche`; a later proposal may stop the rest, see
+        /// `propose_halt_installments`.
+        #[ink(message)]
+        pub fn propose_installments(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            tranches: u32,
+            interval: u64,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            ensure!(
+                !(1..=MAX_TRANCHES).contains(&tranches) || interval == 0,
+                GovernorError::InvalidInstallments
+            );
+            let proposal_id = self.create_proposal(
+                self.env().caller(),
+                to,
+                amount,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.installments.insert(
+                proposal_id,
+                &Installments {
+                    recipient: to,
+                    amount,
+                    tranches,
+                    interval,
+                    started_at: None,
+                    released: 0,
+                    halted: false,
+                },
+            );
+            Ok(())
+        }
+
+        /// Proposes to stop the tranches of the installments proposal `target`
+        /// that haven't been released by the time this proposal is executed.
+        #[ink(message)]
+        pub fn propose_halt_installments(
+            &mut self,
+            target: ProposalId,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            ensure!(
+                !self.installments.contains(target),
+                GovernorError::NotInstallments
+            );
+            let proposal_id = self.insert_proposal(
+                self.env().caller(),
+                self.env().account_id(),
+                0,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.installment_halts.insert(proposal_id, &target);
+            Ok(())
+        }
+
+        /// Pays out the tranches of `proposal_id` that have come due. Anyone may
+        /// call it. The last tranche also pays the remainder of the division into
+        /// tranches.
+        #[ink(message)]
+        pub fn release_tranche(
+            &mut self,
+            proposal_id: ProposalId,
+        ) -> Result<(), GovernorError> {
+            let mut installments = self
+                .installments
+                .get(proposal_id)
+                .ok_or(GovernorError::NotInstallments)?;
+            let started_at = installments
+                .started_at
+                .ok_or(GovernorError::InstallmentsNotStarted)?;
+            ensure!(installments.halted, GovernorError::InstallmentsHalted);
+            let elapsed = self.now().saturating_sub(started_at);
+            let due = (elapsed / installments.interval)
+                .saturating_add(1)
+                .min(installments.tranches.into()) as u32;
+            ensure!(due <= installments.released, GovernorError::NoTrancheDue);
+            let released = installments.released_amount();
+            installments.released = due;
+            let amount = installments.released_amount() - released;
+            self.installments.insert(proposal_id, &installments);
+            self.release(amount);
+            self.pay(proposal_id, installments.recipient, amount)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn installments(&self, proposal_id: ProposalId) -> Option<Installments> {
+            self.installments.get(proposal_id)
+        }
+
+        /// Proposes to split `budget` between up to `MAX_POLL_OPTIONS` `recipients`,
+        /// in proportion to the weight voters allocate to each with
+        /// `vote_allocation`. It passes once the allocated weight reaches the
+        /// quorum.
+        #[ink(message)]
+        pub fn propose_allocation(
+            &mut self,
+            budget: Balance,
+            recipients: Vec<AccountId>,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::check_proposal_input(budget, duration)?;
+            ensure!(
+                !(2..=MAX_POLL_OPTIONS).contains(&recipients.len()),
+                GovernorError::InvalidAllocation
+            );
+            let proposer = self.env().caller();
+            let proposal_id = self.insert_proposal(
+                proposer,
+                proposer,
+                budget,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.allocations.insert(
+                proposal_id,
+                &Allocation {
+                    tallies: ink::prelude::vec![0; recipients.len()],
+                    recipients,
+                },
+            );
+            Ok(())
+        }
+
+        /// Spreads the caller's weight over the recipients of an allocation,
+        /// `shares` holding the percentage for each of them in order.
+        #[ink(message)]
+        pub fn vote_allocation(
+            &mut self,
+            proposal_id: ProposalId,
+            shares: Vec<u8>,
+        ) -> Result<(), GovernorError> {
+            let config = self.settings();
+            self.cast_ballot(
+                &config,
+                self.env().caller(),
+                proposal_id,
+                Choice::Split(shares),
+                Vec::new(),
+            )
+        }
+
+        #[ink(message)]
+        pub fn allocation(&self, proposal_id: ProposalId) -> Option<Allocation> {
+            self.allocations.get(proposal_id)
+        }
+
+        /// Proposes a quadratic funding round between up to `MAX_GRANT_CANDIDATES`
+        /// grant `candidates`, backed by a `matching_pool` from the treasury.
+        ///
+        /// Once the proposal is executed, members contribute to candidates with
+        /// `contribute` for `contribution_period`, after which `close_funding_round`
+        /// pays each candidate its contributions plus its quadratic match.
+        #[ink(message)]
+        pub fn propose_funding_round(
+            &mut self,
+            matching_pool: Balance,
+            candidates: Vec<AccountId>,
+            contribution_period: u64,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::check_proposal_input(matching_pool, duration)?;
+            ensure!(contribution_period == 0, GovernorError::DurationError);
+            ensure!(
+                !(2..=MAX_GRANT_CANDIDATES).contains(&candidates.len()),
+                GovernorError::InvalidCandidates
+            );
+            let proposer = self.env().caller();
+            let proposal_id = self.insert_proposal(
+                proposer,
+                proposer,
+                matching_pool,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.funding_rounds.insert(
+                proposal_id,
+                &FundingRound {
+                    contributions: ink::prelude::vec![0; candidates.len()],
+                    sqrt_sums: ink::prelude::vec![0; candidates.len()],
+                    candidates,
+                    matching_pool,
+                    contribution_period,
+                    closes_at: None,
+                    matched: None,
+                },
+            );
+            Ok(())
+        }
+
+        /// Contributes the transferred value to the candidate at index `candidate`
+        /// of an open funding round. Contributions are reserved until the round
+        /// closes. Sybil resistance is left to the `identity_gate`: without one,
+        /// splitting a contribution across accounts inflates its match.
+        #[ink(message, payable)]
+        pub fn contribute(
+            &mut self,
+            round_id: ProposalId,
+            candidate: u8,
+        ) -> Result<(), GovernorError> {
+            let amount = self.env().transferred_value();
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            let mut round = self
+                .funding_rounds
+                .get(round_id)
+                .ok_or(GovernorError::NotFundingRound)?;
+            let closes_at = round.closes_at.ok_or(GovernorError::RoundNotOpen)?;
+            ensure!(self.now() >= closes_at, GovernorError::RoundClosed);
+            let index = candidate as usize;
+            ensure!(
+                index >= round.candidates.len(),
+                GovernorError::UnknownCandidate
+            );
+            let contributor = self.env().caller();
+            ensure!(
+                !Self::is_verified(&self.settings(), contributor),
+                GovernorError::NotVerified
+            );
+            let key = (round_id, contributor, candidate);
+            let previous = self.round_contributions.get(key).unwrap_or_default();
+            let total = previous + amount;
+            round.sqrt_sums[index] =
+                round.sqrt_sums[index] - qf::sqrt(previous) + qf::sqrt(total);
+            round.contributions[index] += amount;
+            self.round_contributions.insert(key, &total);
+            self.funding_rounds.insert(round_id, &round);
+            self.reserve(amount);
+            self.record_entry(
+                LedgerCategory::Grant,
+                Flow::Income,
+                Asset::Native,
+                contributor,
+                Some(round_id),
+                amount,
+            );
+            Ok(())
+        }
+
+        /// Pays out a funding round whose contribution period has ended. Matching
+        /// lost to rounding stays in the treasury.
+        #[ink(message)]
+        pub fn close_funding_round(
+            &mut self,
+            round_id: ProposalId,
+        ) -> Result<(), GovernorError> {
+            let mut round = self
+                .funding_rounds
+                .get(round_id)
+                .ok_or(GovernorError::NotFundingRound)?;
+            let closes_at = round.closes_at.ok_or(GovernorError::RoundNotOpen)?;
+            ensure!(self.now() < closes_at, GovernorError::RoundOpen);
+            ensure!(round.matched.is_some(), GovernorError::RoundClosed);
+            let matched =
+                qf::matches(round.matching_pool, &round.sqrt_sums, &round.contributions);
+            round.matched = Some(matched.clone());
+            self.funding_rounds.insert(round_id, &round);
+            self.release(
+                round.matching_pool + round.contributions.iter().sum::<Balance>(),
+            );
+            for ((candidate, contributed), matched) in round
+                .candidates
+                .into_iter()
+                .zip(round.contributions)
+                .zip(matched)
+            {
+                let amount = contributed + matched;
+                if amount > 0 {
+                    self.env()
+                        .transfer(candidate, amount)
+                        .map_err(|_| GovernorError::TransferFailed)?;
+                    self.record_entry(
+                        LedgerCategory::Grant,
+                        Flow::Expense,
+                        Asset::Native,
+                        candidate,
+                        Some(round_id),
+                        amount,
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn funding_round(&self, round_id: ProposalId) -> Option<FundingRound> {
+            self.funding_rounds.get(round_id)
+        }
+
+        /// Total `contributor` gave to the candidate at index `candidate` of a round.
+        #[ink(message)]
+        pub fn contribution(
+            &self,
+            round_id: ProposalId,
+            contributor: AccountId,
+            candidate: u8,
+        ) -> Balance {
+            self.round_contributions
+                .get((round_id, contributor, candidate))
+                .unwrap_or_default()
+        }
+
+        /// Proposes a retroactive funding round sharing `pot` between the work done
+        /// for the DAO over the past `period`.
+        ///
+        /// Once the proposal is executed, nominees register their contributions
+        /// with `nominate` for `nomination_period`. Badge holders, or token holders
+        /// if there are none, then split their weight between the nominations with
+        /// `allocate_retro` for `allocation_period`, after which `close_retro_round`
+        /// pays out the pot in proportion.
+        #[ink(message)]
+        pub fn propose_retro_round(
+            &mut self,
+            pot: Balance,
+            period: (u64, u64),
+            nomination_period: u64,
+            allocation_period: u64,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::check_proposal_input(pot, duration)?;
+            ensure!(
+                period.0 > period.1
+                    || period.1 > self.now()
+                    || nomination_period == 0
+                    || allocation_period == 0,
+                GovernorError::DurationError
+            );
+            let proposer = self.env().caller();
+            let proposal_id = self.insert_proposal(
+                proposer,
+                proposer,
+                pot,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.retro_rounds.insert(
+                proposal_id,
+                &RetroRound {
+                    period,
+                    pot,
+                    nomination_period,
+                    allocation_period,
+                    opened_at: None,
+                    nominations: Vec::new(),
+                    tallies: Vec::new(),
+                    closed: false,
+                },
+            );
+            Ok(())
+        }
+
+        /// Nominates the contribution hashed as `contribution` for a retro round,
+        /// to be paid to `recipient`. A round takes up to `MAX_GRANT_CANDIDATES`
+        /// nominations.
+        #[ink(message)]
+        pub fn nominate(
+            &mut self,
+            round_id: ProposalId,
+            contribution: [u8; 32],
+            recipient: AccountId,
+        ) -> Result<(), GovernorError> {
+            let mut round = self
+                .retro_rounds
+                .get(round_id)
+                .ok_or(GovernorError::NotRetroRound)?;
+            let opened_at = round.opened_at.ok_or(GovernorError::RoundNotOpen)?;
+            ensure!(
+                self.now() >= opened_at.saturating_add(round.nomination_period),
+                GovernorError::NominationsClosed
+            );
+            ensure!(
+                round.nominations.len() >= MAX_GRANT_CANDIDATES,
+                GovernorError::InvalidCandidates
+            );
+            round.nominations.push(Nomination {
+                contribution,
+                recipient,
+            });
+            round.tallies.push(0);
+            self.retro_rounds.insert(round_id, &round);
+            Ok(())
+        }
+
+        /// Splits the caller's weight between the nominations of a retro round,
+        /// `shares` holding the percentage for each of them in order.
+        ///
+        /// Token holders allocate with the weight of their own balance when the round
+        /// opened; delegated power doesn't count here.
+        #[ink(message)]
+        pub fn allocate_retro(
+            &mut self,
+            round_id: ProposalId,
+            shares: Vec<u8>,
+        ) -> Result<(), GovernorError> {
+            let mut round = self
+                .retro_rounds
+                .get(round_id)
+                .ok_or(GovernorError::NotRetroRound)?;
+            let opened_at = round.opened_at.ok_or(GovernorError::RoundNotOpen)?;
+            let allocation_start = opened_at.saturating_add(round.nomination_period);
+            let now = self.now();
+            ensure!(now < allocation_start, GovernorError::NominationsOpen);
+            ensure!(
+                now >= allocation_start.saturating_add(round.allocation_period),
+                GovernorError::RoundClosed
+            );
+            let allocator = self.env().caller();
+            ensure!(
+                self.retro_allocators.contains((round_id, allocator)),
+                GovernorError::AlreadyVoted
+            );
+            let config = self.settings();
+            let badge_holders = self.retro_badge_holders();
+            let weight = if badge_holders.is_empty() {
+                let (balance, total_supply) = Self::token_balances_at(
+                    config.governance_token,
+                    allocator,
+                    opened_at,
+                )?;
+                math::ratio(balance, total_supply)
+            } else {
+                ensure!(
+                    !badge_holders.contains(&allocator),
+                    GovernorError::NotBadgeHolder
+                );
+                self.check_tier(&config, allocator, opened_at, |policy| {
+                    policy.committee
+                })?;
+                math::PRECISION / badge_holders.len() as u128
+            };
+            Self::add_split(&mut round.tallies, &shares, weight as u64)?;
+            self.retro_allocators.insert((round_id, allocator), &());
+            self.retro_rounds.insert(round_id, &round);
+            Ok(())
+        }
+
+        /// Pays out the pot of a retro round whose allocation period has ended.
+        /// What rounding leaves, or the whole pot if nothing was allocated, is
+        /// released back to the treasury.
+        #[ink(message)]
+        pub fn close_retro_round(
+            &mut self,
+            round_id: ProposalId,
+        ) -> Result<(), GovernorError> {
+            let mut round = self
+                .retro_rounds
+                .get(round_id)
+                .ok_or(GovernorError::NotRetroRound)?;
+            let opened_at = round.opened_at.ok_or(GovernorError::RoundNotOpen)?;
+            ensure!(
+                self.now()
+                    < opened_at
+                        .saturating_add(round.nomination_period)
+                        .saturating_add(round.allocation_period),
+                GovernorError::RoundOpen
+            );
+            ensure!(round.closed, GovernorError::RoundClosed);
+            round.closed = true;
+            self.retro_rounds.insert(round_id, &round);
+            self.release(round.pot);
+            self.pay_allocation(
+                round_id,
+                round.pot,
+                Allocation {
+                    recipients: round
+                        .nominations
+                        .into_iter()
+                        .map(|nomination| nomination.recipient)
+                        .collect(),
+                    tallies: round.tallies,
+                },
+            )?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn retro_round(&self, round_id: ProposalId) -> Option<RetroRound> {
+            self.retro_rounds.get(round_id)
+        }
+
+        /// Opens an emergency proposal with a `FAST_TRACK_DURATION` voting period,
+        /// for time-sensitive actions like rescuing funds from a failing venue.
+        ///
+        /// Only guardians may call it, and the proposal only passes if the share of
+        /// the supply voting for it reaches `fast_track_approval`.
+        #[ink(message)]
+        pub fn propose_fast_track(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), GovernorError> {
+            let proposer = self.env().caller();
+            let config = self.settings();
+            ensure!(!self.is_guardian(proposer), GovernorError::NotGuardian);
+            self.check_tier(&config, proposer, self.stake_snapshot(), |policy| {
+                policy.fast_track
+            })?;
+            let proposal_id = self.create_proposal(
+                proposer,
+                to,
+                amount,
+                FAST_TRACK_DURATION,
+                GENERAL_CATEGORY,
+            )?;
+            self.fast_tracked.insert(proposal_id, &());
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_fast_tracked(&self, proposal_id: ProposalId) -> bool {
+            self.fast_tracked.contains(proposal_id)
+        }
+
+        /// Like `propose`, tagging the proposal with `category` from the configured
+        /// taxonomy. Votes on it follow the voters' delegations for that category,
+        /// see `delegate_topic`.
+        #[ink(message)]
+        pub fn propose_in_category(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+            category: Category,
+        ) -> Result<(), GovernorError> {
+            ensure!(
+                category != GENERAL_CATEGORY
+                    && usize::from(category) >= self.categories().len(),
+                GovernorError::UnknownCategory
+            );
+            self.create_proposal(self.env().caller(), to, amount, duration, category)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn proposal_category(&self, proposal_id: ProposalId) -> Category {
+            self.proposal_categories
+                .get(proposal_id)
+                .unwrap_or(GENERAL_CATEGORY)
+        }
+
+        /// Pages through the ids of live proposals in `category`, in the same
+        /// order as `proposal_ids`.
+        #[ink(message)]
+        pub fn proposals_by_category(
+            &self,
+            category: Category,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<ProposalId> {
+            self.indexed_proposals(ProposalIndex::Category(category), offset, limit)
+        }
+
+        #[ink(message)]
+        pub fn proposer(&self, proposal_id: ProposalId) -> Option<AccountId> {
+            self.proposers.get(proposal_id)
+        }
+
+        /// Pages through the ids of live proposals submitted by `proposer`, in the
+        /// same order as `proposal_ids`.
+        #[ink(message)]
+        pub fn proposals_by(
+            &self,
+            proposer: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<ProposalId> {
+            self.indexed_proposals(ProposalIndex::Proposer(proposer), offset, limit)
+        }
+
+        /// Submits a slate of proposals in one transaction and returns their ids.
+        ///
+        /// Either all proposals are created or, if any input is invalid, none are.
+        #[ink(message)]
+        pub fn propose_many(
+            &mut self,
+            inputs: Vec<ProposalInput>,
+        ) -> Result<Vec<ProposalId>, GovernorError> {
+            for input in &inputs {
+                Self::check_proposal_input(input.amount, input.duration)?;
+            }
+            let proposer = self.env().caller();
+            inputs
+                .into_iter()
+                .map(|input| {
+                    self.create_proposal(
+                        proposer,
+                        input.to,
+                        input.amount,
+                        input.duration,
+                        GENERAL_CATEGORY,
+                    )
+                })
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn vote(
+            &mut self,
+            proposal_id: ProposalId,
+            vote: VoteType,
+        ) -> Result<(), GovernorError> {
+            let config = self.settings();
+            self.cast_vote(&config, self.env().caller(), proposal_id, vote)
+        }
+
+        /// Votes as one of the `default_delegates`, adding the power of `holders`
+        /// who have missed `auto_delegation_epochs` epochs. A holder stays eligible
+        /// until their power is voted with again or they delegate it themselves.
+        #[ink(message)]
+        pub fn vote_for_inactive(
+            &mut self,
+            proposal_id: ProposalId,
+            vote: VoteType,
+            holders: Vec<AccountId>,
+        ) -> Result<(), GovernorError> {
+            let config = self.settings();
+            let voter = self.env().caller();
+            ensure!(
+                !self.default_delegates().contains(&voter),
+                GovernorError::NotDefaultDelegate
+            );
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            self.check_tier(&config, voter, proposal.vote_start, |policy| {
+                policy.committee
+            })?;
+            self.cast_ballot(&config, voter, proposal_id, Choice::Vote(vote), holders)
+        }
+
+        /// Authorizes `key` to vote with the caller's weight through `vote_as`, or
+        /// revokes the current key if `None`. The key can't do anything else on the
+        /// caller's behalf.
+        #[ink(message)]
+        pub fn set_voting_key(&mut self, key: Option<AccountId>) {
+            let holder = self.env().caller();
+            match key {
+                Some(key) => {
+                    self.voting_keys.insert(holder, &key);
+                }
+                None => self.voting_keys.remove(holder),
+            }
+            self.env().emit_event(VotingKeySet {
+                schema_version: EVENTS_VERSION,
+                holder,
+                key,
+            });
+        }
+
+        #[ink(message)]
+        pub fn voting_key(&self, holder: AccountId) -> Option<AccountId> {
+            self.voting_keys.get(holder)
+        }
+
+        /// Delegates the caller's voting power to `to`, or takes it back if `None`.
+        ///
+        /// Power flows on through `to`'s own delegate, up to `MAX_DELEGATION_DEPTH`
+        /// hops counted from the start of the longest chain through the caller.
+        /// Chains are resolved as they stood at the start of each vote: the voting
+        /// delegate's weight includes every delegator down the chain at that time
+        /// that hasn't voted on the proposal yet. An account that had delegated by
+        /// then can't vote on it itself.
+        #[ink(message)]
+        pub fn delegate(&mut self, to: Option<AccountId>) -> Result<(), GovernorError> {
+            self.set_delegate(self.env().caller(), to, None)
+        }
+
+        /// Delegates the caller's voting power on proposals in `category` to `to`,
+        /// overriding their general delegate there, or removes the override if
+        /// `None`. Fee streams only follow general delegation.
+        #[ink(message)]
+        pub fn delegate_topic(
+            &mut self,
+            category: Category,
+            to: Option<AccountId>,
+        ) -> Result<(), GovernorError> {
+            self.set_delegate(self.env().caller(), to, Some(category))
+        }
+
+        #[ink(message)]
+        pub fn delegate_of(&self, delegator: AccountId) -> Option<AccountId> {
+            self.delegates.get(delegator)
+        }
+
+        /// Delegate `delegator`'s power goes to on proposals in `category`.
+        #[ink(message)]
+        pub fn delegate_for(
+            &self,
+            delegator: AccountId,
+            category: Category,
+        ) -> Option<AccountId> {
+            self.next_delegate(delegator, Some(category))
+        }
+
+        /// Balance delegated directly to `delegate`, as of when each delegator
+        /// delegated. Topic delegations aren't counted.
+        #[ink(message)]
+        pub fn delegated_weight(&self, delegate: AccountId) -> Balance {
+            self.delegated_weights.get(delegate).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn delegation_stats(&self) -> DelegationStats {
+            self.delegation_stats.get_or_default()
+        }
+
+        /// Up to `count` of the heaviest delegates by `delegated_weight`, heaviest
+        /// first. At most `MAX_TOP_DELEGATES` are ranked.
+        #[ink(message)]
+        pub fn top_delegates(&self, count: u32) -> Vec<(AccountId, Balance)> {
+            let mut top = self.top_delegates.get_or_default();
+            top.truncate(count as usize);
+            top
+        }
+
+        /// Sets the fee the caller asks per `FEE_PERIOD` from delegators who fund a
+        /// fee stream to them. Open streams keep the rate they started with.
+        #[ink(message)]
+        pub fn set_delegate_fee(&mut self, rate: Balance) {
+            let delegate = self.env().caller();
+            if rate == 0 {
+                self.delegate_fees.remove(delegate);
+            } else {
+                self.delegate_fees.insert(delegate, &rate);
+            }
+        }
+
+        #[ink(message)]
+        pub fn delegate_fee(&self, delegate: AccountId) -> Balance {
+            self.delegate_fees.get(delegate).unwrap_or_default()
+        }
+
+        /// Registers or updates the caller's delegate profile, or removes it if
+        /// `None`. Its categories must be in the configured taxonomy.
+        #[ink(message)]
+        pub fn set_delegate_profile(
+            &mut self,
+            profile: Option<DelegateProfile>,
+        ) -> Result<(), GovernorError> {
+            let delegate = self.env().caller();
+            let mut delegates = self.profiled_delegates.get_or_default();
+            let Some(mut profile) = profile else {
+                if self.delegate_profiles.take(delegate).is_some() {
+                    delegates.retain(|profiled| *profiled != delegate);
+                    self.profiled_delegates.set(&delegates);
+                }
+                return Ok(())
+            };
+            let categories = self.categories().len();
+            ensure!(
+                profile.categories.iter().any(|category| {
+                    *category != GENERAL_CATEGORY && usize::from(*category) >= categories
+                }),
+                GovernorError::UnknownCategory
+            );
+            profile.categories.sort_unstable();
+            profile.categories.dedup();
+            if self.delegate_profiles.insert(delegate, &profile).is_none() {
+                delegates.push(delegate);
+                self.profiled_delegates.set(&delegates);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn delegate_profile(&self, delegate: AccountId) -> Option<DelegateProfile> {
+            self.delegate_profiles.get(delegate)
+        }
+
+        /// Delegates with a profile, in order of registration.
+        #[ink(message)]
+        pub fn profiled_delegates(&self) -> Vec<AccountId> {
+            self.profiled_delegates.get_or_default()
+        }
+
+        /// Deposits the transferred value into a fee stream paying the caller's
+        /// current delegate at their asking rate. Topping up keeps the stream's
+        /// rate. Changing or removing the delegation refunds what hasn't streamed.
+        #[ink(message, payable)]
+        pub fn fund_delegation_fee(&mut self) -> Result<(), GovernorError> {
+            let amount = self.env().transferred_value();
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            let delegator = self.env().caller();
+            let delegate = self
+                .delegates
+                .get(delegator)
+                .ok_or(GovernorError::NotDelegating)?;
+            let now = self.now();
+            let mut stream = match self.fee_streams.get(delegator) {
+                Some(stream) => self.settle_fee_stream(stream, now),
+                None => {
+                    FeeStream {
+                        delegate,
+                        rate: self.delegate_fee(delegate),
+                        remaining: 0,
+                        settled_at: now,
+                    }
+                }
+            };
+            stream.remaining += amount;
+            self.fee_streams.insert(delegator, &stream);
+            self.reserve(amount);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn fee_stream(&self, delegator: AccountId) -> Option<FeeStream> {
+            self.fee_streams.get(delegator)
+        }
+
+        /// Settles the fee streams of `delegators` to the caller and pays out
+        /// everything the caller has earned so far.
+        #[ink(message)]
+        pub fn claim_delegate_fees(
+            &mut self,
+            delegators: Vec<AccountId>,
+        ) -> Result<Balance, GovernorError> {
+            let delegate = self.env().caller();
+            let now = self.now();
+            for delegator in delegators {
+                if let Some(stream) = self.fee_streams.get(delegator) {
+                    if stream.delegate == delegate {
+                        let stream = self.settle_fee_stream(stream, now);
+                        self.fee_streams.insert(delegator, &stream);
+                    }
+                }
+            }
+            let amount = self.delegate_earnings.take(delegate).unwrap_or_default();
+            ensure!(amount == 0, GovernorError::NothingToClaim);
+            self.env()
+                .transfer(delegate, amount)
+                .map_err(|_| GovernorError::TransferFailed)?;
+            self.release(amount);
+            Ok(amount)
+        }
+
+        /// Casts `holder`'s vote; the caller must be `holder`'s voting key.
+        #[ink(message)]
+        pub fn vote_as(
+            &mut self,
+            holder: AccountId,
+            proposal_id: ProposalId,
+            vote: VoteType,
+        ) -> Result<(), GovernorError> {
+            ensure!(
+                self.voting_keys.get(holder) != Some(self.env().caller()),
+                GovernorError::NotVotingKey
+            );
+            let config = self.settings();
+            self.cast_vote(&config, holder, proposal_id, vote)
+        }
+
+        /// Casts the caller's votes on several proposals in one transaction.
+        ///
+        /// Each ballot is applied independently; the returned results are in the
+        /// same order as `votes`.
+        #[ink(message)]
+        pub fn vote_many(
+            &mut self,
+            votes: Vec<(ProposalId, VoteType)>,
+        ) -> Vec<Result<(), GovernorError>> {
+            let voter = self.env().caller();
+            let config = self.settings();
+            votes
+                .into_iter()
+                .map(|(proposal_id, vote)| {
+                    self.cast_vote(&config, voter, proposal_id, vote)
+                })
+                .collect()
+        }
+
+        /// Like `execute`, except that when one of its checks fails, the attempt is
+        /// recorded in an `OperationFailed` event and its error returned as
+        /// `Ok(Some(error))`, since failing the call would revert the event too.
+        /// Errors raised while executing still fail the call.
+        #[ink(message)]
+        pub fn attempt_execute(
+            &mut self,
+            proposal_id: ProposalId,
+        ) -> Result<Option<GovernorError>, GovernorError> {
+            let error = match self.simulate_execute(proposal_id) {
+                Ok(report) => report.error,
+                Err(error) => Some(error),
+            };
+            if let Some(error) = error {
+                self.env().emit_event(OperationFailed {
+                    schema_version: EVENTS_VERSION,
+                    op: Operation::Execute,
+                    proposal_id,
+                    error,
+                });
+                return Ok(Some(error))
+            }
+            self.execute(proposal_id).map(|()| None)
+        }
+
+        /// Runs the checks of `execute` on `proposal_id` without executing it, so
+        /// executors can tell whether an attempt would succeed, and why not. The
+        /// call of a call proposal is only checked against the allowlist, not run.
+        #[ink(message)]
+        pub fn simulate_execute(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Result<ExecutionReport, GovernorError> {
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            let config = self.settings();
+            let passed = self.check_passed(&config, proposal_id, &proposal);
+            let unlocked = self.check_unlocked(&config, proposal_id, &proposal);
+            let call_allowed = self.check_call_allowed(
+                &proposal,
+                self.proposal_calls.get(proposal_id).as_ref(),
+            );
+            let funded = self.check_funded(&config, proposal_id, &proposal);
+            let error = [
+                if proposal.executed {
+                    Err(GovernorError::ProposalAlreadyExecuted)
+                } else {
+                    Ok(())
+                },
+                if self.polls.contains(proposal_id) {
+                    Err(GovernorError::IsPoll)
+                } else {
+                    Ok(())
+                },
+                passed,
+                unlocked,
+                call_allowed,
+                funded,
+            ]
+            .into_iter()
+            .find_map(Result::err);
+            Ok(ExecutionReport {
+                error,
+                quorum_reached: matches!(
+                    passed,
+                    Ok(())
+                        | Err(GovernorError::FastTrackApprovalNotReached)
+                        | Err(GovernorError::ProposalNotAccepted)
+                        | Err(GovernorError::SupermajorityNotReached)
+                        | Err(GovernorError::ApprovalNotReached)
+                ),
+                passed: passed.is_ok(),
+                unlocked: unlocked.is_ok(),
+                call_allowed: call_allowed.is_ok(),
+                funded: funded.is_ok(),
+            })
+        }
+
+        #[ink(message)]
+        pub fn execute(&mut self, proposal_id: ProposalId) -> Result<(), GovernorError> {
+            let mut proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            ensure!(proposal.executed, GovernorError::ProposalAlreadyExecuted);
+            ensure!(self.polls.contains(proposal_id), GovernorError::IsPoll);
+            let config = self.settings();
+            self.check_passed(&config, proposal_id, &proposal)?;
+            self.check_unlocked(&config, proposal_id, &proposal)?;
+            let proposal_call = self.proposal_calls.get(proposal_id);
+            self.check_call_allowed(&proposal, proposal_call.as_ref())?;
+            self.check_funded(&config, proposal_id, &proposal)?;
+            let amendment = self.amendments.get(proposal_id);
+            proposal.executed = true;
+            self.proposals.insert(proposal_id, &proposal);
+            self.executed_count
+                .set(&(self.executed_count.get_or_default() + 1));
+            #[cfg(feature = "invariants")]
+            let treasury = self.env().balance();
+            #[cfg_attr(not(feature = "invariants"), allow(unused_variables))]
+            let paid = if let Some(proposal_call) = proposal_call {
+                call::invoke(
+                    proposal.to,
+                    proposal_call.selector,
+                    &proposal_call.input,
+                    proposal.amount,
+                )
+                .map_err(|_| GovernorError::CallFailed)?;
+                if proposal.amount > 0 {
+                    self.record_entry(
+                        LedgerCategory::Investment,
+                        Flow::Expense,
+                        Asset::Native,
+                        proposal.to,
+                        Some(proposal_id),
+                        proposal.amount,
+                    );
+                }
+                proposal.amount
+            } else if let Some(payload) = self.adapter_payloads.get(proposal_id) {
+                self.check_adapter(proposal.to)?;
+                call::execute_adapter(proposal.to, &payload, proposal.amount)
+                    .map_err(|_| GovernorError::CallFailed)?;
+                if proposal.amount > 0 {
+                    self.record_entry(
+                        LedgerCategory::Investment,
+                        Flow::Expense,
+                        Asset::Native,
+                        proposal.to,
+                        Some(proposal_id),
+                        proposal.amount,
+                    );
+                }
+                proposal.amount
+            } else if let Some((action, admin_call)) = self.admin_actions.get(proposal_id)
+            {
+                self.execute_admin_action(proposal.to, action, admin_call)?;
+                0
+            } else if let Some((pool, asset)) = self.pool_payouts.get(proposal_id) {
+                self.pay_from_pool(
+                    proposal_id,
+                    pool,
+                    asset,
+                    proposal.to,
+                    proposal.amount,
+                )?;
+                match asset {
+                    Asset::Native => proposal.amount,
+                    Asset::Psp22(_) => 0,
+                }
+            } else if let Some((account, change)) =
+                self.membership_changes.get(proposal_id)
+            {
+                self.pending_memberships.remove(account);
+                self.change_membership(&config, account, change)?;
+                0
+            } else if let Some(new) = self.config_changes.get(proposal_id) {
+                match self.config_activations.get(proposal_id) {
+                    Some(activates_at) if activates_at > self.now() => {
+                        self.schedule_config(proposal_id, new, activates_at)
+                    }
+                    _ => {
+                        self.activate_configs();
+                        let old = self.stored_config();
+                        self.change_config(proposal_id, old, new, self.now())
+                    }
+                }
+                0
+            } else if let Some(document) = amendment {
+                self.adopt_amendment(proposal_id, document);
+                0
+            } else if let Some((member, change)) = self.council_changes.get(proposal_id) {
+                self.change_council(&config, member, change)?;
+                0
+            } else if let Some((member, tier)) = self.tier_assignments.get(proposal_id) {
+                self.assigned_tiers.insert(member, &tier);
+                0
+            } else if let Some((member, until)) = self.lockup_changes.get(proposal_id) {
+                self.lockups.insert(member, &until);
+                0
+            } else if let Some(action) = self.nft_actions.get(proposal_id) {
+                self.execute_nft_action(proposal.to, action)?;
+                0
+            } else if let Some(allocation) = self.allocations.get(proposal_id) {
+                self.pay_allocation(proposal_id, proposal.amount, allocation)?
+            } else if let Some(mut round) = self.funding_rounds.get(proposal_id) {
+                // The matching pool stays in the treasury, reserved, until the
+                // round closes.
+                round.closes_at =
+                    Some(self.now().saturating_add(round.contribution_period));
+                self.funding_rounds.insert(proposal_id, &round);
+                self.reserve(round.matching_pool);
+                0
+            } else if let Some(mut round) = self.retro_rounds.get(proposal_id) {
+                // The pot stays in the treasury, reserved, until the round closes.
+                round.opened_at = Some(self.now());
+                self.retro_rounds.insert(proposal_id, &round);
+                self.reserve(round.pot);
+                0
+            } else if let Some(transfers) = self.batch_transfers.get(proposal_id) {
+                self.pay_batch(proposal_id, transfers)?
+            } else if let Some(share) = self.treasury_shares.get(proposal_id) {
+                let amount = self.treasury_share_amount(share, proposal.amount);
+                self.pay(proposal_id, proposal.to, amount)?
+            } else if let Some(mut installments) = self.installments.get(proposal_id) {
+                // The tranches stay in the treasury, reserved, until released.
+                installments.started_at = Some(self.now());
+                self.installments.insert(proposal_id, &installments);
+                if !installments.halted {
+                    self.reserve(installments.amount);
+                }
+                0
+            } else if let Some(target) = self.installment_halts.get(proposal_id) {
+                if let Some(mut installments) = self.installments.get(target) {
+                    if installments.started_at.is_some() && !installments.halted {
+                        self.release(
+                            installments.amount - installments.released_amount(),
+                        );
+                    }
+                    installments.halted = true;
+                    self.installments.insert(target, &installments);
+                }
+                0
+            } else if let Some(stable) = self.stable_amounts.get(proposal_id) {
+                let amount = Self::stable_payout(&config, &stable)?.min(proposal.amount);
+                self.pay(proposal_id, proposal.to, amount)?
+            } else {
+                self.pay(proposal_id, proposal.to, proposal.amount)?
+            };
+            invariant!(
+                paid <= proposal.amount && self.env().balance() == treasury - paid,
+                "treasury balance doesn't match the executed amount"
+            );
+
+            Ok(())
+        }
+
+        /// Index of the current governance cycle and its window, if cycles are
+        /// enabled.
+        #[ink(message)]
+        pub fn cycle_phase(&self) -> Option<(u64, CyclePhase)> {
+            self.settings()
+                .cycle
+                .map(|cycle| Self::cycle_phase_at(&cycle, self.now()))
+        }
+
+        /// Share of the supply (in `math::PRECISION` units) that must vote on
+        /// `proposal_id` by now for it to pass, see `quorum_decay_period`.
+        #[ink(message)]
+        pub fn current_quorum(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Result<u64, GovernorError> {
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            Ok(self.quorum_at(&self.settings(), proposal_id, &proposal) as u64)
+        }
+
+        /// Opens the objection window of a passed proposal. Until
+        /// `objection_window` has elapsed, holders may `object` to it; if the
+        /// objections reach `objection_threshold`, the tally is cleared and the
+        /// proposal goes back to a confirmation vote instead of being executed.
+        #[ink(message)]
+        pub fn start_objection_window(
+            &mut self,
+            proposal_id: ProposalId,
+        ) -> Result<(), GovernorError> {
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            ensure!(proposal.executed, GovernorError::ProposalAlreadyExecuted);
+            let config = self.settings();
+            ensure!(
+                config.objection_window == 0
+                    || self.confirmation_votes.contains(proposal_id),
+                GovernorError::NoObjectionWindow
+            );
+            ensure!(
+                self.objection_windows.contains(proposal_id),
+                GovernorError::ObjectionWindowStarted
+            );
+            self.check_passed(&config, proposal_id, &proposal)?;
+            self.objection_windows.insert(
+                proposal_id,
+                &ObjectionWindow {
+                    started_at: self.now(),
+                    weight: 0,
+                    objectors: Vec::new(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Objects to a passed proposal with the caller's share of the supply at the
+        /// start of its vote.
+        #[ink(message)]
+        pub fn object(&mut self, proposal_id: ProposalId) -> Result<(), GovernorError> {
+            let objector = self.env().caller();
+            let config = self.settings();
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            let mut window = self
+                .objection_windows
+                .get(proposal_id)
+                .ok_or(GovernorError::ObjectionWindowNotStarted)?;
+            ensure!(
+                self.now() >= window.started_at.saturating_add(config.objection_window),
+                GovernorError::ObjectionWindowClosed
+            );
+            ensure!(
+                window.objectors.contains(&objector),
+                GovernorError::AlreadyObjected
+            );
+            let (balance, total_supply) =
+                Self::voting_power(&config, objector, proposal.vote_start)?;
+            let weight = math::ratio(balance, total_supply) as u64;
+            window.weight += weight;
+            window.objectors.push(objector);
+            self.env().emit_event(ObjectionLodged {
+                schema_version: EVENTS_VERSION,
+                proposal_id,
+                objector,
+                weight,
+            });
+
+            if (window.weight as u128) < math::percent(config.objection_threshold) {
+                self.objection_windows.insert(proposal_id, &window);
+                return Ok(())
+            }
+            self.objection_windows.remove(proposal_id);
+            self.confirmation_votes.insert(proposal_id, &());
+            self.reset_tally(proposal_id);
+            // The confirmation vote lasts as long as the original one.
+            let mut proposal = proposal;
+            let now = self.now();
+            proposal.vote_end =
+                now.saturating_add(proposal.vote_end - proposal.vote_start);
+            proposal.vote_start = now;
+            self.proposals.insert(proposal_id, &proposal);
+            self.env().emit_event(ConfirmationVoteStarted {
+                schema_version: EVENTS_VERSION,
+                proposal_id,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn objection_window(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Option<ObjectionWindow> {
+            self.objection_windows.get(proposal_id)
+        }
+
+        /// Endorses or opposes a pending proposal with the caller's share of the
+        /// supply, as a signal of sentiment independent of the vote. Before the vote
+        /// starts the share is read at the `stake_snapshot`, and from then on at the
+        /// start of the vote. Signaling again replaces the caller's previous
+        /// sentiment.
+        #[ink(message)]
+        pub fn endorse(
+            &mut self,
+            proposal_id: ProposalId,
+            sentiment: Sentiment,
+        ) -> Result<(), GovernorError> {
+            let endorser = self.env().caller();
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            ensure!(proposal.executed, GovernorError::ProposalAlreadyExecuted);
+            let snapshot = if self.now() < proposal.vote_start {
+                self.stake_snapshot()
+            } else {
+                proposal.vote_start
+            };
+            let config = self.settings();
+            let (balance, total_supply) =
+                Self::voting_power(&config, endorser, snapshot)?;
+            let weight = math::ratio(balance, total_supply) as u64;
+            let mut endorsements = self.endorsements(proposal_id);
+            match self.sentiments.get((proposal_id, endorser)) {
+                Some((Sentiment::Endorse, previous)) => endorsements.endorsed -= previous,
+                Some((Sentiment::Oppose, previous)) => endorsements.opposed -= previous,
+                None => endorsements.signalers += 1,
+            }
+            match sentiment {
+                Sentiment::Endorse => endorsements.endorsed += weight,
+                Sentiment::Oppose => endorsements.opposed += weight,
+            }
+            self.endorsements.insert(proposal_id, &endorsements);
+            self.sentiments
+                .insert((proposal_id, endorser), &(sentiment, weight));
+            self.env().emit_event(Endorsed {
+                schema_version: EVENTS_VERSION,
+                proposal_id,
+                endorser,
+                sentiment,
+                weight,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn endorsements(&self, proposal_id: ProposalId) -> Endorsements {
+            self.endorsements.get(proposal_id).unwrap_or_default()
+        }
+
+        /// Sentiment `account` signaled on the proposal, and its weight.
+        #[ink(message)]
+        pub fn sentiment(
+            &self,
+            proposal_id: ProposalId,
+            account: AccountId,
+        ) -> Option<(Sentiment, u64)> {
+            self.sentiments.get((proposal_id, account))
+        }
+
+        /// Creates a proposal whose votes are collected off-chain by `aggregator`, who
+        /// anchors the resulting tally with `anchor_tally` once voting has ended.
+        /// On-chain `vote` is disabled for it.
+        #[ink(message)]
+        pub fn propose_anchored(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+            aggregator: AccountId,
+        ) -> Result<(), GovernorError> {
+            let proposal_id = self.create_proposal(
+                self.env().caller(),
+                to,
+                amount,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.anchored_tallies.insert(
+                proposal_id,
+                &AnchoredTally {
+                    aggregator,
+                    root: None,
+                    anchored_at: 0,
+                    for_votes: 0,
+                    against_vote: 0,
+                    disputed: Vec::new(),
+                },
+            );
+            Ok(())
+        }
+
+        /// Anchors the off-chain tally of a hybrid proposal: the merkle `root` of its
+        /// ballots and the weights they add up to. Only the proposal's aggregator may
+        /// anchor, once, after voting has ended. Execution waits for
+        /// `TALLY_CHALLENGE_WINDOW` so voters can dispute their ballots.
+        #[ink(message)]
+        pub fn anchor_tally(
+            &mut self,
+            proposal_id: ProposalId,
+            root: [u8; 32],
+            for_votes: u64,
+            against_vote: u64,
+        ) -> Result<(), GovernorError> {
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            let mut tally = self
+                .anchored_tallies
+                .get(proposal_id)
+                .ok_or(GovernorError::NotTallyAggregator)?;
+            ensure!(
+                tally.aggregator != self.env().caller(),
+                GovernorError::NotTallyAggregator
+            );
+            ensure!(tally.root.is_some(), GovernorError::TallyAlreadyAnchored);
+            let now = self.now();
+            ensure!(now <= proposal.vote_end, GovernorError::VotePeriodNotEnded);
+            ensure!(
+                (for_votes as u128 + against_vote as u128) > math::PRECISION,
+                GovernorError::InvalidTally
+            );
+            tally.root = Some(root);
+            tally.anchored_at = now;
+            tally.for_votes = for_votes;
+            tally.against_vote = against_vote;
+            self.anchored_tallies.insert(proposal_id, &tally);
+            self.env().emit_event(TallyAnchored {
+                schema_version: EVENTS_VERSION,
+                proposal_id,
+                root,
+                for_votes,
+                against_vote,
+            });
+            Ok(())
+        }
+
+        /// Lets a voter correct the weight of their ballot in an anchored tally
+        /// during the challenge window, by proving that `ballot` is one of its
+        /// leaves. The weight is recomputed from the voter's power at the start of
+        /// the vote. The vote itself stands: ballots can't be changed once the
+        /// tally is anchored.
+        #[ink(message)]
+        pub fn dispute_ballot(
+            &mut self,
+            proposal_id: ProposalId,
+            ballot: Ballot,
+            proof: Vec<[u8; 32]>,
+        ) -> Result<(), GovernorError> {
+            let mut tally = self
+                .anchored_tallies
+                .get(proposal_id)
+                .ok_or(GovernorError::TallyNotAnchored)?;
+            let root = tally.root.ok_or(GovernorError::TallyNotAnchored)?;
+            ensure!(
+                self.now() >= tally.anchored_at.saturating_add(TALLY_CHALLENGE_WINDOW),
+                GovernorError::ChallengeWindowClosed
+            );
+            let voter = self.env().caller();
+            ensure!(ballot.voter != voter, GovernorError::NotBallotVoter);
+            ensure!(
+                tally.disputed.contains(&voter),
+                GovernorError::AlreadyDisputed
+            );
+            ensure!(
+                !merkle::verify(&root, merkle::leaf(&(proposal_id, &ballot)), &proof),
+                GovernorError::InvalidProof
+            );
+            let weight = self.ballot_weight(proposal_id, voter)?;
+            match ballot.vote {
+                VoteType::For => {
+                    tally.for_votes =
+                        tally.for_votes.saturating_sub(ballot.weight) + weight
+                }
+                VoteType::Against => {
+                    tally.against_vote =
+                        tally.against_vote.saturating_sub(ballot.weight) + weight
+                }
+            }
+            tally.disputed.push(voter);
+            self.anchored_tallies.insert(proposal_id, &tally);
+            self.env().emit_event(BallotDisputed {
+                schema_version: EVENTS_VERSION,
+                proposal_id,
+                voter,
+                weight,
+                vote: ballot.vote,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn anchored_tally(&self, proposal_id: ProposalId) -> Option<AnchoredTally> {
+            self.anchored_tallies.get(proposal_id)
+        }
+
+        /// Removes the proposal record, tally and vote receipts of finalized
+        /// proposals, refunding their storage deposit to the caller.
+        ///
+        /// A proposal is finalized once it has been executed or defeated, and
+        /// `PRUNE_CHALLENGE_WINDOW` has passed since the end of its voting period.
+        /// Passed proposals are kept until they are executed. The outcome is kept in a
+        /// `ProposalPruned` event and a compact `ProposalSummary`, see
+        /// `archived_proposal`. Fails without pruning anything if one of the ids
+        /// is unknown or not yet finalized.
+        #[ink(message)]
+        pub fn prune(
+            &mut self,
+            proposal_ids: Vec<ProposalId>,
+        ) -> Result<(), GovernorError> {
+            let now = self.now();
+            let config = self.settings();
+            for proposal_id in proposal_ids {
+                let proposal = self
+                    .proposals
+                    .get(proposal_id)
+                    .ok_or(GovernorError::ProposalNotFound)?;
+                ensure!(
+                    now < proposal.vote_end.saturating_add(PRUNE_CHALLENGE_WINDOW)
+                        || !self.is_concluded(&config, proposal_id, &proposal),
+                    GovernorError::ProposalNotFinalized
+                );
+                let mut proposal_vote =
+                    self.proposal_votes.get(proposal_id).unwrap_or_default();
+                if let Some(tally) = self.anchored_tallies.take(proposal_id) {
+                    proposal_vote.for_votes = tally.for_votes;
+                    proposal_vote.against_vote = tally.against_vote;
+                }
+                self.proposals.remove(proposal_id);
+                self.proposal_votes.remove(proposal_id);
+                self.fast_tracked.remove(proposal_id);
+                self.proposal_calls.remove(proposal_id);
+                self.admin_actions.remove(proposal_id);
+                self.adapter_payloads.remove(proposal_id);
+                self.nft_actions.remove(proposal_id);
+                self.pool_payouts.remove(proposal_id);
+                self.tier_assignments.remove(proposal_id);
+                self.lockup_changes.remove(proposal_id);
+                self.council_changes.remove(proposal_id);
+                self.amendments.remove(proposal_id);
+                self.config_changes.remove(proposal_id);
+                self.config_activations.remove(proposal_id);
+                self.endorsements.remove(proposal_id);
+                if let Some((account, _)) = self.membership_changes.take(proposal_id) {
+                    if self.pending_memberships.get(account) == Some(proposal_id) {
+                        self.pending_memberships.remove(account);
+                    }
+                }
+                self.polls.remove(proposal_id);
+                self.allocations.remove(proposal_id);
+                self.batch_transfers.remove(proposal_id);
+                self.treasury_shares.remove(proposal_id);
+                self.stable_amounts.remove(proposal_id);
+                self.voting_thresholds.remove(proposal_id);
+                self.installment_halts.remove(proposal_id);
+                let category = self
+                    .proposal_categories
+                    .take(proposal_id)
+                    .unwrap_or(GENERAL_CATEGORY);
+                self.remove_from_index(ProposalIndex::Category(category), proposal_id);
+                if let Some(proposer) = self.proposers.take(proposal_id) {
+                    self.remove_from_index(
+                        ProposalIndex::Proposer(proposer),
+                        proposal_id,
+                    );
+                }
+                self.objection_windows.remove(proposal_id);
+                self.confirmation_votes.remove(proposal_id);
+                self.clear_receipts(proposal_id, proposal_vote.receipt_buckets);
+                self.unindex_proposal(proposal_id);
+                if !proposal.executed {
+                    self.defeated_count
+                        .set(&(self.defeated_count.get_or_default() + 1));
+                }
+                let mut recipient_hash = [0; 32];
+                ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                    &proposal.to,
+                    &mut recipient_hash,
+                );
+                self.archive.insert(
+                    proposal_id,
+                    &ProposalSummary {
+                        executed: proposal.executed,
+                        for_votes: proposal_vote.for_votes,
+                        against_vote: proposal_vote.against_vote,
+                        amount: proposal.amount,
+                        recipient_hash,
+                    },
+                );
+                self.env().emit_event(ProposalPruned {
+                    schema_version: EVENTS_VERSION,
+                    proposal_id,
+                    executed: proposal.executed,
+                    for_votes: proposal_vote.for_votes,
+                    against_vote: proposal_vote.against_vote,
+                });
+            }
+            Ok(())
+        }
+
+        /// Final result of a pruned proposal.
+        #[ink(message)]
+        pub fn archived_proposal(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Option<ProposalSummary> {
+            self.archive.get(proposal_id)
+        }
+
+        /// Pays the caller the fee reimbursements earned by voting on `proposal_ids`.
+        ///
+        /// Only ballots of at least the `min_reward_weight`, cast while
+        /// `vote_reimbursement` was set and the budget lasted, are reimbursed, at
+        /// the amount set aside when they were cast.
+        #[ink(message)]
+        pub fn claim_reimbursements(
+            &mut self,
+            proposal_ids: Vec<ProposalId>,
+        ) -> Result<Balance, GovernorError> {
+            self.claim_reimbursements_for(self.env().caller(), proposal_ids)
+        }
+
+        /// Entry point of the trusted forwarder: performs `call` as if `sender` had
+        /// made it. `signature` is `sender`'s ECDSA signature of the BLAKE2 hash of
+        /// the encoded `(governor account, sender, nonce, call)`, and `sender` must
+        /// be the account of the signing key, the BLAKE2 hash of its compressed
+        /// public key. Each signature can only be used once: `nonce` must be
+        /// `sender`'s next `forward_nonce`.
+        #[ink(message)]
+        pub fn forward(
+            &mut self,
+            sender: AccountId,
+            call: ForwardedCall,
+            nonce: u64,
+            signature: [u8; 65],
+        ) -> Result<(), GovernorError> {
+            let config = self.settings();
+            ensure!(
+                config.trusted_forwarder != Some(self.env().caller()),
+                GovernorError::NotTrustedForwarder
+            );
+            ensure!(
+                nonce != self.forward_nonce(sender),
+                GovernorError::InvalidNonce
+            );
+            let public_key = self
+                .env()
+                .ecdsa_recover(&signature, &self.forward_hash(sender, &call, nonce))
+                .map_err(|_| GovernorError::InvalidSignature)?;
+            let mut signer = [0; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&public_key, &mut signer);
+            ensure!(
+                AccountId::from(signer) != sender,
+                GovernorError::InvalidSignature
+            );
+            self.forward_nonces.insert(sender, &(nonce + 1));
+            match call {
+                ForwardedCall::Propose {
+                    to,
+                    amount,
+                    duration,
+                } => {
+                    self.create_proposal(sender, to, amount, duration, GENERAL_CATEGORY)
+                        .map(|_| ())
+                }
+                ForwardedCall::Vote { proposal_id, vote } => {
+                    self.cast_vote(&config, sender, proposal_id, vote)
+                }
+                ForwardedCall::ClaimReimbursements { proposal_ids } => {
+                    self.claim_reimbursements_for(sender, proposal_ids)
+                        .map(|_| ())
+                }
+            }
+        }
+
+        /// Nonce the next call forwarded for `account` must be signed with.
+        #[ink(message)]
+        pub fn forward_nonce(&self, account: AccountId) -> u64 {
+            self.forward_nonces.get(account).unwrap_or_default()
+        }
+
+        /// Hash `sender` signs to have `call` forwarded with `nonce`: the BLAKE2
+        /// hash of the encoded governor account, `sender`, `nonce` and `call`.
+        fn forward_hash(
+            &self,
+            sender: AccountId,
+            call: &ForwardedCall,
+            nonce: u64,
+        ) -> [u8; 32] {
+            let mut hash = [0; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &(self.env().account_id(), sender, nonce, call),
+                &mut hash,
+            );
+            hash
+        }
+
+        #[ink(message)]
+        pub fn reimbursement(
+            &self,
+            proposal_id: ProposalId,
+            voter: AccountId,
+        ) -> Balance {
+            self.reimbursements
+                .get((proposal_id, voter))
+                .unwrap_or_default()
+        }
+
+        /// Adds the transferred value to the budget fee reimbursements are paid from.
+        #[ink(message, payable)]
+        pub fn fund_reimbursements(&mut self) -> Result<(), GovernorError> {
+            let amount = self.env().transferred_value();
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            self.reimbursement_budget
+                .set(&(self.reimbursement_budget() + amount));
+            self.reserve(amount);
+            self.record_entry(
+                LedgerCategory::Rewards,
+                Flow::Income,
+                Asset::Native,
+                self.env().caller(),
+                None,
+                amount,
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn reimbursement_budget(&self) -> Balance {
+            self.reimbursement_budget.get_or_default()
+        }
+
+        /// Adds the transferred value to the participation rewards pool.
+        #[ink(message, payable)]
+        pub fn fund_rewards(&mut self) -> Result<(), GovernorError> {
+            let amount = self.env().transferred_value();
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            let pool = self.reward_pool.get_or_default();
+            self.reward_pool.set(&(pool + amount));
+            self.reserve(amount);
+            self.record_entry(
+                LedgerCategory::Rewards,
+                Flow::Income,
+                Asset::Native,
+                self.env().caller(),
+                None,
+                amount,
+            );
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn reward_pool(&self) -> Balance {
+            self.reward_pool.get_or_default()
+        }
+
+        /// Pays the caller the participation rewards earned on those of
+        /// `proposal_ids` that have concluded, i.e. were executed or are past the
+        /// end of voting. Rewards of proposals still open stay claimable.
+        #[ink(message)]
+        pub fn claim_participation_rewards(
+            &mut self,
+            proposal_ids: Vec<ProposalId>,
+        ) -> Result<Balance, GovernorError> {
+            let voter = self.env().caller();
+            let now = self.now();
+            let concluded: Vec<_> = proposal_ids
+                .into_iter()
+                .filter(|proposal_id| {
+                    self.proposals.get(proposal_id).map_or(true, |proposal| {
+                        proposal.executed || now >= proposal.vote_end
+                    })
+                })
+                .collect();
+            let amount: Balance = concluded
+                .iter()
+                .filter_map(|proposal_id| {
+                    self.participation_rewards.get((proposal_id, voter))
+                })
+                .sum();
+            ensure!(amount == 0, GovernorError::NothingToClaim);
+            let pool = self.reward_pool.get_or_default();
+            ensure!(amount > pool, GovernorError::RewardPoolDepleted);
+            self.env()
+                .transfer(voter, amount)
+                .map_err(|_| GovernorError::TransferFailed)?;
+            for proposal_id in concluded {
+                self.participation_rewards.remove((proposal_id, voter));
+            }
+            self.reward_pool.set(&(pool - amount));
+            self.release(amount);
+            self.record_entry(
+                LedgerCategory::Rewards,
+                Flow::Expense,
+                Asset::Native,
+                voter,
+                None,
+                amount,
+            );
+            Ok(amount)
+        }
+
+        #[ink(message)]
+        pub fn participation_reward(
+            &self,
+            proposal_id: ProposalId,
+            voter: AccountId,
+        ) -> Balance {
+            self.participation_rewards
+                .get((proposal_id, voter))
+                .unwrap_or_default()
+        }
+
+        /// Participation record of `member`, see `streak`.
+        #[ink(message)]
+        pub fn voting_streak(&self, member: AccountId) -> VotingStreak {
+            self.voting_streaks.get(member).unwrap_or_default()
+        }
+
+        /// Number of consecutive epochs, up to the current or the previous one, in
+        /// which `member` voted on every proposal opened.
+        #[ink(message)]
+        pub fn streak(&self, member: AccountId) -> u32 {
+            let Some(current_epoch) = Self::current_epoch(&self.settings(), self.now())
+            else {
+                return 0
+            };
+            let streak = self.voting_streak(member);
+            match streak.completed_epoch {
+                Some(epoch) if epoch + 1 >= current_epoch => streak.length,
+                _ => 0,
+            }
+        }
+
+        /// Share (in %) of `member`'s balance that currently counts as voting weight,
+        /// see `inactivity_grace_epochs`.
+        #[ink(message)]
+        pub fn activity_factor(&self, member: AccountId) -> u8 {
+            let config = self.settings();
+            match Self::current_epoch(&config, self.now()) {
+                Some(epoch) => self.activity_factor_at(&config, member, epoch),
+                None => 100,
+            }
+        }
+
+        /// Pays the caller's membership dues for as many `dues_period`s as the
+        /// transferred value covers, which must be a multiple of `dues`.
+        ///
+        /// Payments extend the paid period from where it ended, so a lapsed member
+        /// regains their voting rights once the arrears are paid. The first
+        /// payment covers the periods from now on.
+        #[ink(message, payable)]
+        pub fn pay_dues(&mut self) -> Result<u64, GovernorError> {
+            let config = self.settings();
+            ensure!(config.dues == 0, GovernorError::NoDues);
+            let amount = self.env().transferred_value();
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            ensure!(amount % config.dues != 0, GovernorError::InvalidDuesPayment);
+            let member = self.env().caller();
+            let periods = u64::try_from(amount / config.dues).unwrap_or(u64::MAX);
+            let paid_until = self
+                .dues_paid_until
+                .get(member)
+                .unwrap_or(self.now())
+                .saturating_add(periods.saturating_mul(config.dues_period));
+            self.dues_paid_until.insert(member, &paid_until);
+            self.record_entry(
+                LedgerCategory::Dues,
+                Flow::Income,
+                Asset::Native,
+                member,
+                None,
+                amount,
+            );
+            Ok(paid_until)
+        }
+
+        /// End of the period `member`'s dues cover, `None` if they never paid.
+        #[ink(message)]
+        pub fn dues_paid_until(&self, member: AccountId) -> Option<u64> {
+            self.dues_paid_until.get(member)
+        }
+
+        /// Whether `member` may vote, i.e. their dues are paid or still within the
+        /// grace period. Always true while dues are disabled.
+        #[ink(message)]
+        pub fn in_good_standing(&self, member: AccountId) -> bool {
+            self.in_good_standing_at(&self.settings(), member, self.now())
+        }
+
+        /// Tier `member` may propose with right now, `None` while tiers are
+        /// disabled. Stakes are read at the end of the previous block.
+        #[ink(message)]
+        pub fn tier(&self, member: AccountId) -> Result<Option<Tier>, GovernorError> {
+            let config = self.settings();
+            let Some(policy) = &config.tiers else {
+                return Ok(None)
+            };
+            let (balance, _) =
+                Self::voting_power(&config, member, self.stake_snapshot())?;
+            Ok(Some(self.tier_at(policy, member, balance)))
+        }
+
+        /// Proposes to assign `member` to `tier` regardless of their stake, or
+        /// to the tier their stake reaches if `tier` is `Tier::Observer`.
+        #[ink(message)]
+        pub fn propose_tier_assignment(
+            &mut self,
+            member: AccountId,
+            tier: Tier,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            let proposal_id = self.insert_proposal(
+                self.env().caller(),
+                member,
+                0,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.tier_assignments.insert(proposal_id, &(member, tier));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn tier_assignment(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Option<(AccountId, Tier)> {
+            self.tier_assignments.get(proposal_id)
+        }
+
+        /// Proposes to add `account` to the member registry or remove it. An
+        /// account has at most one membership proposal pending at a time, until it
+        /// is executed or pruned.
+        #[ink(message)]
+        pub fn propose_membership(
+            &mut self,
+            account: AccountId,
+            change: MembershipChange,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            ensure!(
+                self.pending_memberships.contains(account),
+                GovernorError::MembershipPending
+            );
+            match change {
+                MembershipChange::Add => {
+                    ensure!(self.is_member(account), GovernorError::AlreadyMember)
+                }
+                MembershipChange::Remove | MembershipChange::Kick => {
+                    ensure!(!self.is_member(account), GovernorError::NotMember)
+                }
+            }
+            let proposal_id = self.insert_proposal(
+                self.env().caller(),
+                account,
+                0,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.membership_changes
+                .insert(proposal_id, &(account, change));
+            self.pending_memberships.insert(account, &proposal_id);
+            Ok(())
+        }
+
+        /// Membership proposal awaiting execution for `account`, if any.
+        #[ink(message)]
+        pub fn pending_membership(&self, account: AccountId) -> Option<ProposalId> {
+            self.pending_memberships.get(account)
+        }
+
+        #[ink(message)]
+        pub fn membership_change(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Option<(AccountId, MembershipChange)> {
+            self.membership_changes.get(proposal_id)
+        }
+
+        #[ink(message)]
+        pub fn is_member(&self, account: AccountId) -> bool {
+            self.members.contains(account)
+        }
+
+        #[ink(message)]
+        pub fn member_count(&self) -> u32 {
+            self.member_count.get_or_default()
+        }
+
+        /// Members in order of admission.
+        #[ink(message)]
+        pub fn members(&self) -> Vec<AccountId> {
+            self.member_list.get_or_default()
+        }
+
+        /// Members assigned to review proposals in `epoch`, a `review_panel` of
+        /// the current registry.
+        #[ink(message)]
+        pub fn reviewers(&self, epoch: u32) -> Vec<AccountId> {
+            let config = self.settings();
+            let mut members = self.members();
+            let panel = usize::from(config.review_panel).min(members.len());
+            if panel == 0 {
+                return Vec::new()
+            }
+            match config.review_rotation {
+                ReviewRotation::RoundRobin => {
+                    let start = (epoch as usize).wrapping_mul(panel) % members.len();
+                    members.rotate_left(start);
+                }
+                ReviewRotation::Shuffled => {
+                    members.sort_by_cached_key(|member| merkle::leaf(&(epoch, member)))
+                }
+            }
+            members.truncate(panel);
+            members
+        }
+
+        /// Review panel of the current epoch.
+        #[ink(message)]
+        pub fn current_reviewers(&self) -> Vec<AccountId> {
+            let now = self.now();
+            self.reviewers(Self::current_epoch(&self.settings(), now).unwrap_or(0))
+        }
+
+        #[ink(message)]
+        pub fn is_reviewer(&self, account: AccountId) -> bool {
+            self.current_reviewers().contains(&account)
+        }
+
+        #[ink(message)]
+        pub fn shares(&self, member: AccountId) -> Balance {
+            self.shares.get(member).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn total_shares(&self) -> Balance {
+            self.total_shares.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn loot(&self, account: AccountId) -> Balance {
+            self.loot.get(account).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn total_loot(&self) -> Balance {
+            self.total_loot.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn is_kicked(&self, account: AccountId) -> bool {
+            self.kicked.contains(account)
+        }
+
+        /// Proposes to replace the governance parameters with `config`.
+        #[ink(message)]
+        pub fn propose_config_change(
+            &mut self,
+            config: GovernorConfig,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            self.insert_config_change(config, duration)?;
+            Ok(())
+        }
+
+        /// Like `propose_config_change`, with the new parameters taking effect at
+        /// `activates_at` rather than as soon as the proposal is executed, e.g. at
+        /// the start of the next epoch so rules don't change mid-cycle. It may not
+        /// be before the end of the vote.
+        #[ink(message)]
+        pub fn propose_scheduled_config_change(
+            &mut self,
+            config: GovernorConfig,
+            activates_at: u64,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            let proposal_id = self.insert_config_change(config, duration)?;
+            let vote_end = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?
+                .vote_end;
+            ensure!(
+                activates_at < vote_end,
+                GovernorError::InvalidActivationTime
+            );
+            self.config_activations.insert(proposal_id, &activates_at);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn config_change(&self, proposal_id: ProposalId) -> Option<GovernorConfig> {
+            self.config_changes.get(proposal_id)
+        }
+
+        #[ink(message)]
+        pub fn config_activation(&self, proposal_id: ProposalId) -> Option<u64> {
+            self.config_activations.get(proposal_id)
+        }
+
+        /// Executed config changes yet to take effect, soonest first. `config`
+        /// already reflects those whose activation time has come, but they are
+        /// only recorded in the parameter history by `activate_configs`.
+        #[ink(message)]
+        pub fn scheduled_configs(&self) -> Vec<ScheduledConfig> {
+            self.config_schedule
+                .get_or_default()
+                .into_iter()
+                .filter_map(|(_, proposal_id)| self.scheduled_configs.get(proposal_id))
+                .collect()
+        }
+
+        /// Puts in force and records the scheduled config changes whose activation
+        /// time has come, returning how many. Anyone may call it.
+        #[ink(message)]
+        pub fn activate_configs(&mut self) -> u32 {
+            let now = self.now();
+            let mut schedule = self.config_schedule.get_or_default();
+            let due = schedule
+                .iter()
+                .take_while(|(activates_at, _)| *activates_at <= now)
+                .count();
+            let activated: Vec<_> = schedule.drain(..due).collect();
+            self.config_schedule.set(&schedule);
+            for (activates_at, proposal_id) in &activated {
+                if let Some(change) = self.scheduled_configs.take(proposal_id) {
+                    let old = self.stored_config();
+                    self.change_config(*proposal_id, old, change.config, *activates_at);
+                }
+            }
+            activated.len() as u32
+        }
+
+        /// Number of parameter changes made since deployment.
+        #[ink(message)]
+        pub fn param_history_length(&self) -> u32 {
+            self.param_history_length.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn param_change(&self, index: u32) -> Option<ParamChange> {
+            self.param_history.get(index)
+        }
+
+        /// Pages through the parameter history, oldest change first.
+        #[cfg(feature = "views")]
+        #[ink(message)]
+        pub fn param_changes(&self, offset: u32, limit: u32) -> Vec<ParamChange> {
+            let end = offset
+                .saturating_add(limit)
+                .min(self.param_history_length());
+            (offset..end)
+                .filter_map(|index| self.param_history.get(index))
+                .collect()
+        }
+
+        /// Governance parameters in force at `timestamp`.
+        #[cfg(feature = "views")]
+        #[ink(message)]
+        pub fn config_at(&self, timestamp: u64) -> GovernorConfig {
+            // The first change made after `timestamp` knows what it replaced.
+            (0..self.param_history_length())
+                .filter_map(|index| self.param_history.get(index))
+                .find(|change| change.timestamp > timestamp)
+                .map_or_else(|| self.config(), |change| change.old)
+        }
+
+        /// Proposes to replace the constitution with the `document` whose hash is
+        /// given. Amendments vote for at least `amendment_duration` and take an
+        /// `amendment_approval` supermajority of the votes cast to pass.
+        #[ink(message)]
+        pub fn propose_amendment(
+            &mut self,
+            document: [u8; 32],
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            ensure!(
+                Self::voting_period(duration)? < self.settings().amendment_duration,
+                GovernorError::DurationError
+            );
+            let caller = self.env().caller();
+            let proposal_id =
+                self.insert_proposal(caller, caller, 0, duration, GENERAL_CATEGORY)?;
+            self.amendments.insert(proposal_id, &document);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn amendment(&self, proposal_id: ProposalId) -> Option<[u8; 32]> {
+            self.amendments.get(proposal_id)
+        }
+
+        /// Hash of the current constitution, if one was adopted.
+        #[ink(message)]
+        pub fn constitution(&self) -> Option<[u8; 32]> {
+            self.constitution_versions
+                .get_or_default()
+                .checked_sub(1)
+                .and_then(|version| self.constitution_history.get(version))
+                .map(|amendment| amendment.document)
+        }
+
+        /// Number of constitution versions adopted so far.
+        #[ink(message)]
+        pub fn constitution_versions(&self) -> u32 {
+            self.constitution_versions.get_or_default()
+        }
+
+        /// The `version`-th constitution adopted, from 0.
+        #[ink(message)]
+        pub fn constitution_version(&self, version: u32) -> Option<Amendment> {
+            self.constitution_history.get(version)
+        }
+
+        /// Proposes to elect `member` to the council, or to recall a sitting
+        /// member early.
+        #[ink(message)]
+        pub fn propose_council_change(
+            &mut self,
+            member: AccountId,
+            change: CouncilChange,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            match change {
+                CouncilChange::Elect => {
+                    ensure!(
+                        self.is_council_member(member),
+                        GovernorError::AlreadyOnCouncil
+                    )
+                }
+                CouncilChange::Recall => {
+                    ensure!(!self.is_council_member(member), GovernorError::NotOnCouncil)
+                }
+            }
+            let proposal_id = self.insert_proposal(
+                self.env().caller(),
+                member,
+                0,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.council_changes.insert(proposal_id, &(member, change));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn council_change(
+            &self,
+            proposal_id: ProposalId,
+        ) -> Option<(AccountId, CouncilChange)> {
+            self.council_changes.get(proposal_id)
+        }
+
+        /// Whether `member` holds an unexpired council seat.
+        #[ink(message)]
+        pub fn is_council_member(&self, member: AccountId) -> bool {
+            self.council_terms
+                .get(member)
+                .map_or(false, |term_end| self.now() < term_end)
+        }
+
+        #[ink(message)]
+        pub fn council_term_end(&self, member: AccountId) -> Option<u64> {
+            self.council_terms.get(member)
+        }
+
+        /// Sitting council members.
+        #[ink(message)]
+        pub fn council(&self) -> Vec<AccountId> {
+            self.council_members
+                .get_or_default()
+                .into_iter()
+                .filter(|member| self.is_council_member(*member))
+                .collect()
+        }
+
+        /// Pays the transferred value into the caller's capital account, locking
+        /// the caller's stake for the `deposit_lockup` from now on.
+        ///
+        /// With `deposit_windows`, deposits are only accepted while a window is
+        /// open, and mint shares at the net asset value per share before the
+        /// deposit: the `redeemable_balance` over all outstanding shares and loot,
+        /// or one share per unit while there are none. Returns the shares minted.
+        #[ink(message, payable)]
+        pub fn deposit_capital(&mut self) -> Result<Balance, GovernorError> {
+            let member = self.env().caller();
+            let amount = self.env().transferred_value();
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            ensure!(!self.is_member(member), GovernorError::NotMember);
+            let config = self.settings();
+            let now = self.now();
+            let mut shares = 0;
+            if let Some(windows) = config.deposit_windows {
+                Self::check_deposit_window(&windows, now)?;
+                let value = self.redeemable_balance().saturating_sub(amount);
+                let total_shares = self.total_shares();
+                let units = total_shares + self.total_loot();
+                shares = if units == 0 || value == 0 {
+                    amount
+                } else {
+                    math::mul_div(amount, units, value).unwrap_or_default()
+                };
+                self.shares.insert(member, &(self.shares(member) + shares));
+                self.total_shares.set(&(total_shares + shares));
+            }
+            let mut account = self.capital_account(member);
+            account.contributed += amount;
+            self.capital_accounts.insert(member, &account);
+            if config.deposit_lockup > 0 {
+                let until = now.saturating_add(config.deposit_lockup);
+                let locked_until = self.lockups.get(member).unwrap_or_default();
+                self.lockups.insert(member, &until.max(locked_until));
+            }
+            self.record_entry(
+                LedgerCategory::Capital,
+                Flow::Income,
+                Asset::Native,
+                member,
+                None,
+                amount,
+            );
+            self.env().emit_event(CapitalDeposited {
+                schema_version: EVENTS_VERSION,
+                member,
+                amount,
+            });
+            Ok(shares)
+        }
+
+        /// Whether capital may be deposited now, see `deposit_windows`.
+        #[ink(message)]
+        pub fn is_deposit_window_open(&self) -> bool {
+            self.settings().deposit_windows.map_or(true, |windows| {
+                Self::check_deposit_window(&windows, self.now()).is_ok()
+            })
+        }
+
+        #[ink(message)]
+        pub fn capital_account(&self, member: AccountId) -> CapitalAccount {
+            self.capital_accounts.get(member).unwrap_or_default()
+        }
+
+        /// Proposes to lock `member`'s shares and loot until `until`, e.g. to hold
+        /// it to the commitment terms of a fund. A time in the past lifts its
+        /// lockup.
+        #[ink(message)]
+        pub fn propose_lockup(
+            &mut self,
+            member: AccountId,
+            until: u64,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            Self::voting_period(duration)?;
+            let proposal_id = self.insert_proposal(
+                self.env().caller(),
+                member,
+                0,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.lockup_changes.insert(proposal_id, &(member, until));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn lockup_change(&self, proposal_id: ProposalId) -> Option<(AccountId, u64)> {
+            self.lockup_changes.get(proposal_id)
+        }
+
+        /// End of `member`'s lockup, if its shares and loot are locked up now.
+        #[ink(message)]
+        pub fn lockup_end(&self, member: AccountId) -> Option<u64> {
+            self.lockups.get(member).filter(|until| *until > self.now())
+        }
+
+        /// Burns `loot` of the caller's loot for its pro rata share of the
+        /// `redeemable_balance`, against all outstanding shares and loot. Returns
+        /// the amount paid.
+        #[ink(message)]
+        pub fn redeem_loot(&mut self, loot: Balance) -> Result<Balance, GovernorError> {
+            let caller = self.env().caller();
+            let held = self.loot(caller);
+            ensure!(loot == 0 || loot > held, GovernorError::InsufficientLoot);
+            self.check_lockup(caller)?;
+            let total_loot = self.total_loot();
+            let amount = math::mul_div(
+                loot,
+                self.redeemable_balance(),
+                self.total_shares() + total_loot,
+            )
+            .unwrap_or_default();
+            self.loot.insert(caller, &(held - loot));
+            self.total_loot.set(&(total_loot - loot));
+            self.env()
+                .transfer(caller, amount)
+                .map_err(|_| GovernorError::TransferFailed)?;
+            self.record_entry(
+                LedgerCategory::Capital,
+                Flow::Expense,
+                Asset::Native,
+                caller,
+                None,
+                amount,
+            );
+            let mut account = self.capital_account(caller);
+            account.withdrawn += amount;
+            self.capital_accounts.insert(caller, &account);
+            self.env().emit_event(LootRedeemed {
+                schema_version: EVENTS_VERSION,
+                member: caller,
+                loot,
+                amount,
+            });
+            Ok(amount)
+        }
+
+        /// Queues `shares` of the caller's shares for redemption at the next
+        /// settlements, see `settle_redemptions`. Returns the request's position
+        /// in the queue.
+        #[ink(message)]
+        pub fn request_redemption(
+            &mut self,
+            shares: Balance,
+        ) -> Result<u32, GovernorError> {
+            ensure!(
+                self.settings().settlement_period == 0,
+                GovernorError::RedemptionsDisabled
+            );
+            ensure!(shares == 0, GovernorError::AmountShouldNotBeZero);
+            let member = self.env().caller();
+            self.check_lockup(member)?;
+            let queued = self.queued_shares(member) + shares;
+            ensure!(
+                queued > self.shares(member),
+                GovernorError::InsufficientShares
+            );
+            let position = self.redemption_tail.get_or_default();
+            self.redemption_requests.insert(
+                position,
+                &RedemptionRequest {
+                    member,
+                    shares,
+                    requested_at: self.now(),
+                },
+            );
+            self.redemption_tail.set(&(position + 1));
+            self.queued_shares.insert(member, &queued);
+            self.env().emit_event(RedemptionRequested {
+                schema_version: EVENTS_VERSION,
+                member,
+                shares,
+                position,
+            });
+            Ok(position)
+        }
+
+        /// Settles the redemption queue, at most once per `settlement_period`.
+        ///
+        /// Requests are priced at the net asset value per share as of the
+        /// settlement: the `redeemable_balance` over all outstanding shares and
+        /// loot. They are paid in order, up to `MAX_SETTLED_REDEMPTIONS`, until
+        /// the next one would dip into the `liquidity_buffer`; the rest wait for
+        /// later settlements. Shares burned since their request was made, e.g.
+        /// by a removal, are dropped from it. Requests of members still locked up,
+        /// see `lockup_end`, are moved to the back of the queue. Returns the number
+        /// of requests settled.
+        #[ink(message)]
+        pub fn settle_redemptions(&mut self) -> Result<u32, GovernorError> {
+            let config = self.settings();
+            ensure!(
+                config.settlement_period == 0,
+                GovernorError::RedemptionsDisabled
+            );
+            let now = self.now();
+            if let Some(last) = self.last_settlement.get() {
+                let due_at = last.saturating_add(config.settlement_period);
+                ensure!(now < due_at, GovernorError::SettlementNotDue { due_at });
+            }
+            self.last_settlement.set(&now);
+            let value = self.redeemable_balance();
+            let units = self.total_shares() + self.total_loot();
+            let mut budget = value
+                - math::mul_div(value, config.liquidity_buffer.into(), 100)
+                    .unwrap_or_default()
+                    .min(value);
+            let (mut head, mut tail) = (
+                self.redemption_head.get_or_default(),
+                self.redemption_tail.get_or_default(),
+            );
+            // Deferred requests are appended past `end` and wait for the next
+            // settlement.
+            let end = tail;
+            let (mut settled, mut deferred) = (0, 0);
+            while head < end && settled + deferred < MAX_SETTLED_REDEMPTIONS {
+                let Some(request) = self.redemption_requests.get(head) else {
+                    break
+                };
+                if self.lockup_end(request.member).is_some() {
+                    self.redemption_requests.remove(head);
+                    self.redemption_requests.insert(tail, &request);
+                    head += 1;
+                    tail += 1;
+                    deferred += 1;
+                    continue
+                }
+                let shares = request.shares.min(self.shares(request.member));
+                let amount = math::mul_div(shares, value, units).unwrap_or_default();
+                if amount > budget {
+                    break
+                }
+                budget -= amount;
+                self.redemption_requests.remove(head);
+                head += 1;
+                settled += 1;
+                let queued = self.queued_shares(request.member);
+                self.queued_shares
+                    .insert(request.member, &queued.saturating_sub(request.shares));
+                self.shares
+                    .insert(request.member, &(self.shares(request.member) - shares));
+                self.total_shares.set(&(self.total_shares() - shares));
+                if amount > 0 {
+                    self.env()
+                        .transfer(request.member, amount)
+                        .map_err(|_| GovernorError::TransferFailed)?;
+                    self.record_entry(
+                        LedgerCategory::Capital,
+                        Flow::Expense,
+                        Asset::Native,
+                        request.member,
+                        None,
+                        amount,
+                    );
+                    let mut account = self.capital_account(request.member);
+                    account.withdrawn += amount;
+                    self.capital_accounts.insert(request.member, &account);
+                }
+                self.env().emit_event(SharesRedeemed {
+                    schema_version: EVENTS_VERSION,
+                    member: request.member,
+                    shares,
+                    amount,
+                });
+            }
+            self.redemption_head.set(&head);
+            self.redemption_tail.set(&tail);
+            Ok(settled)
+        }
+
+        /// Shares `member` has pending in the redemption queue.
+        #[ink(message)]
+        pub fn queued_shares(&self, member: AccountId) -> Balance {
+            self.queued_shares.get(member).unwrap_or_default()
+        }
+
+        /// Positions of the first pending redemption request and past the last.
+        #[ink(message)]
+        pub fn redemption_queue(&self) -> (u32, u32) {
+            (
+                self.redemption_head.get_or_default(),
+                self.redemption_tail.get_or_default(),
+            )
+        }
+
+        #[ink(message)]
+        pub fn redemption_request(&self, position: u32) -> Option<RedemptionRequest> {
+            self.redemption_requests.get(position)
+        }
+
+        /// Time of the latest settlement of the redemption queue.
+        #[ink(message)]
+        pub fn last_settlement(&self) -> Option<u64> {
+            self.last_settlement.get()
+        }
+
+        /// Fee-collection entry point for the DAO's products and integrations.
+        ///
+        /// The transferred value is kept in the treasury and accounted against the
+        /// calling contract, which is treated as the fee source.
+        #[ink(message, payable)]
+        pub fn collect_fee(&mut self) -> Result<(), GovernorError> {
+            let amount = self.env().transferred_value();
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            let source = self.env().caller();
+            let collected = self.fees_collected.get(source).unwrap_or_default();
+            self.fees_collected.insert(source, &(collected + amount));
+            let total = self.total_fees_collected.get_or_default();
+            self.total_fees_collected.set(&(total + amount));
+            invariant!(
+                collected <= total,
+                "fees from one source exceed the total collected"
+            );
+            self.record_entry(
+                LedgerCategory::Fee,
+                Flow::Income,
+                Asset::Native,
+                source,
+                None,
+                amount,
+            );
+            self.env().emit_event(FeeCollected {
+                schema_version: EVENTS_VERSION,
+                source,
+                amount,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn fees_collected(&self, source: AccountId) -> Balance {
+            self.fees_collected.get(source).unwrap_or_default()
+        }
+
+        #[cfg(feature = "views")]
+        #[ink(message)]
+        pub fn total_fees_collected(&self) -> Balance {
+            self.total_fees_collected.get_or_default()
+        }
+
+        /// Lifetime counters of proposals, ballots and treasury flows.
+        #[cfg(feature = "views")]
+        #[ink(message)]
+        pub fn stats(&self) -> GovernanceStats {
+            let proposals_created = self.next_proposal_id.0;
+            GovernanceStats {
+                proposals_created,
+                proposals_executed: self.executed_count.get_or_default(),
+                proposals_defeated: self.defeated_count.get_or_default(),
+                average_turnout: self
+                    .weight_cast
+                    .get_or_default()
+                    .checked_div(proposals_created.into())
+                    .unwrap_or_default() as u64,
+                unique_voters: self.voter_count.get_or_default(),
+                treasury: self.treasury_totals.get_or_default(),
+            }
+        }
+
+        /// Number of treasury movements recorded in the ledger.
+        #[ink(message)]
+        pub fn ledger_length(&self) -> u32 {
+            self.ledger_length.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn ledger_entry(&self, index: u32) -> Option<LedgerEntry> {
+            self.ledger.get(index)
+        }
+
+        /// Pages through the ledger, oldest movement first.
+        #[cfg(feature = "views")]
+        #[ink(message)]
+        pub fn ledger_entries(&self, offset: u32, limit: u32) -> Vec<LedgerEntry> {
+            let end = offset.saturating_add(limit).min(self.ledger_length());
+            (offset..end)
+                .filter_map(|index| self.ledger.get(index))
+                .collect()
+        }
+
+        /// Income and expenses of `category` in `asset` over the `period`-th
+        /// `LEDGER_PERIOD` since the epoch.
+        #[ink(message)]
+        pub fn ledger_totals(
+            &self,
+            period: u32,
+            category: LedgerCategory,
+            asset: Asset,
+        ) -> LedgerTotals {
+            self.ledger_totals
+                .get((period, category, asset))
+                .unwrap_or_default()
+        }
+
+        /// Income and expenses in `asset` over the periods `first..=last`, per
+        /// category, e.g. for a quarterly statement.
+        #[cfg(feature = "views")]
+        #[ink(message)]
+        pub fn ledger_statement(
+            &self,
+            first: u32,
+            last: u32,
+            asset: Asset,
+        ) -> Vec<(LedgerCategory, LedgerTotals)> {
+            [
+                LedgerCategory::Investment,
+                LedgerCategory::Payroll,
+                LedgerCategory::Grant,
+                LedgerCategory::Revenue,
+                LedgerCategory::Fee,
+                LedgerCategory::Distribution,
+                LedgerCategory::Rewards,
+                LedgerCategory::Dues,
+                LedgerCategory::Capital,
+            ]
+            .into_iter()
+            .map(|category| {
+                let mut totals = LedgerTotals::default();
+                for period in first..=last {
+                    let period_totals = self.ledger_totals(period, category, asset);
+                    totals.income += period_totals.income;
+                    totals.expense += period_totals.expense;
+                }
+                (category, totals)
+            })
+            .collect()
+        }
+
+        /// Forwards the transferred value to a whitelisted `cause`, along with the
+        /// treasury's `donation_match` of it.
+        ///
+        /// The match is limited by what is left of the cause's cap and by the
+        /// treasury's `free_balance`, so donations go through even once matching
+        /// runs out.
+        #[ink(message, payable)]
+        pub fn donate(&mut self, cause: AccountId) -> Result<Balance, GovernorError> {
+            let amount = self.env().transferred_value();
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            let config = self.settings();
+            let cap = self
+                .causes()
+                .iter()
+                .find(|(whitelisted, _)| *whitelisted == cause)
+                .map(|(_, cap)| *cap)
+                .ok_or(GovernorError::UnknownCause)?;
+            let mut account = self.cause_accounts.get(cause).unwrap_or_default();
+            let treasury = self.free_balance().saturating_sub(amount);
+            let matched = (amount.saturating_mul(config.donation_match.into()) / 100)
+                .min(cap.saturating_sub(account.matched))
+                .min(treasury);
+            account.donated += amount;
+            account.matched += matched;
+            self.cause_accounts.insert(cause, &account);
+            self.env()
+                .transfer(cause, amount + matched)
+                .map_err(|_| GovernorError::TransferFailed)?;
+            let donor = self.env().caller();
+            self.record_entry(
+                LedgerCategory::Grant,
+                Flow::Income,
+                Asset::Native,
+                donor,
+                None,
+                amount,
+            );
+            self.record_entry(
+                LedgerCategory::Grant,
+                Flow::Expense,
+                Asset::Native,
+                cause,
+                None,
+                amount + matched,
+            );
+            self.env().emit_event(DonationMatched {
+                schema_version: EVENTS_VERSION,
+                cause,
+                donor,
+                amount,
+                matched,
+            });
+            Ok(matched)
+        }
+
+        #[ink(message)]
+        pub fn cause_account(&self, cause: AccountId) -> CauseAccount {
+            self.cause_accounts.get(cause).unwrap_or_default()
+        }
+
+        /// Entry point for registered revenue sources paying in the native
+        /// currency. The transferred value is split according to `revenue_split`.
+        #[ink(message, payable)]
+        pub fn collect_revenue(&mut self) -> Result<(), GovernorError> {
+            let amount = self.env().transferred_value();
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            self.record_revenue(Asset::Native, amount)
+        }
+
+        /// Entry point for registered revenue sources paying in a PSP22 `token`:
+        /// reports `amount` tokens the caller has just transferred to the governor.
+        /// Fails unless the governor holds them on top of earlier revenue.
+        #[ink(message)]
+        pub fn collect_token_revenue(
+            &mut self,
+            token: AccountId,
+            amount: Balance,
+        ) -> Result<(), GovernorError> {
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            let (held, _) = Self::token_balances(token, self.env().account_id())?;
+            let reported = self.token_revenue.get(token).unwrap_or_default() + amount;
+            ensure!(held < reported, GovernorError::RevenueNotReceived);
+            self.record_revenue(Asset::Psp22(token), amount)?;
+            self.token_revenue.insert(token, &reported);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn revenue_collected(&self, source: AccountId, asset: Asset) -> Balance {
+            self.revenue.get((source, asset)).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn revenue_pool(&self, pool: RevenuePool, asset: Asset) -> Balance {
+            self.revenue_pools.get((pool, asset)).unwrap_or_default()
+        }
+
+        /// Proposes to pay `amount` of `asset` to `to` out of a revenue pool.
+        #[ink(message)]
+        pub fn propose_pool_payout(
+            &mut self,
+            pool: RevenuePool,
+            asset: Asset,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            let proposal_id = self.create_proposal(
+                self.env().caller(),
+                to,
+                amount,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.pool_payouts.insert(proposal_id, &(pool, asset));
+            Ok(())
+        }
+
+        /// Native balance held for others, which treasury payouts can't spend.
+        #[ink(message)]
+        pub fn reserved_balance(&self) -> Balance {
+            self.reserved_balance.get_or_default()
+        }
+
+        /// Native treasury balance proposals may spend: the contract's balance
+        /// short of the `reserved_balance`.
+        #[ink(message)]
+        pub fn free_balance(&self) -> Balance {
+            self.env().balance().saturating_sub(self.reserved_balance())
+        }
+
+        /// Native treasury balance shares and loot are redeemed against: the
+        /// `free_balance` short of the native revenue pools, which are owed to
+        /// contributors and distributions.
+        #[ink(message)]
+        pub fn redeemable_balance(&self) -> Balance {
+            [RevenuePool::Contributors, RevenuePool::Distribution]
+                .into_iter()
+                .fold(self.free_balance(), |balance, pool| {
+                    balance.saturating_sub(self.revenue_pool(pool, Asset::Native))
+                })
+        }
+
+        /// The governor's clock (in milliseconds): the block timestamp, plus the
+        /// offset of `advance_time` in `test-utils` builds.
+        #[ink(message)]
+        pub fn now(&self) -> u64 {
+            let now = self.env().block_timestamp();
+            #[cfg(feature = "test-utils")]
+            let now = now.saturating_add(self.time_offset.get().unwrap_or_default());
+            now
+        }
+
+        /// Moves the governor's clock `millis` ahead of the block timestamp, so
+        /// voting periods, timelocks and windows can be fast-forwarded on a test
+        /// deployment. Only in `test-utils` builds.
+        #[cfg(feature = "test-utils")]
+        #[ink(message)]
+        pub fn advance_time(&mut self, millis: u64) {
+            let offset = self.time_offset.get().unwrap_or_default();
+            self.time_offset.set(&offset.saturating_add(millis));
+        }
+
+        /// How far (in milliseconds) `advance_time` has moved the governor's clock
+        /// ahead of the block timestamp. Only in `test-utils` builds.
+        #[cfg(feature = "test-utils")]
+        #[ink(message)]
+        pub fn time_offset(&self) -> u64 {
+            self.time_offset.get().unwrap_or_default()
+        }
+
+        /// Returns the governance token `balance_of(account)` and `total_supply()`.
+        #[cfg(not(any(test, feature = "testing")))]
+        fn token_balances(
+            governance_token: AccountId,
+            account: AccountId,
+        ) -> Result<(Balance, Balance), GovernorError> {
+            use ink::codegen::TraitCallBuilder;
+            let token: ink::contract_ref!(PSP22) = governance_token.into();
+            let balance = token
+                .call()
+                .balance_of(account)
+                .try_invoke()
+                .map_err(|_| GovernorError::TokenCallFailed)?
+                .map_err(|_| GovernorError::TokenCallFailed)?;
+            let total_supply = token
+                .call()
+                .total_supply()
+                .try_invoke()
+                .map_err(|_| GovernorError::TokenCallFailed)?
+                .map_err(|_| GovernorError::TokenCallFailed)?;
+            Ok((balance, total_supply))
+        }
+
+        /// Voting power of `account` at `timestamp` and the total power, from the
+        /// `weight_source` if any, or else the governance token balances as of
+        /// `timestamp`, so that tokens moved after a vote starts can't vote on it
+        /// twice. The balances are averaged over the `twab_window` if set.
+        fn voting_power(
+            config: &GovernorConfig,
+            account: AccountId,
+            timestamp: u64,
+        ) -> Result<(Balance, Balance), GovernorError> {
+            match config.weight_source {
+                Some(source) => Self::external_voting_power(source, account, timestamp),
+                None if config.twab_window > 0 => {
+                    Self::token_averages(
+                        config.governance_token,
+                        account,
+                        timestamp.saturating_sub(config.twab_window),
+                        timestamp,
+                    )
+                }
+                None => {
+                    Self::token_balances_at(config.governance_token, account, timestamp)
+                }
+            }
+        }
+
+        /// Returns the governance token `balance_of_at(account, timestamp)` and
+        /// `total_supply_at(timestamp)`.
+        #[cfg(not(any(test, feature = "testing")))]
+        fn token_balances_at(
+            governance_token: AccountId,
+            account: AccountId,
+            timestamp: u64,
+        ) -> Result<(Balance, Balance), GovernorError> {
+            call::token_balances_at(governance_token, account, timestamp)
+                .ok_or(GovernorError::TokenCallFailed)
+        }
+
+        #[cfg(any(test, feature = "testing"))]
+        fn token_balances_at(
+            _governance_token: AccountId,
+            account: AccountId,
+            timestamp: u64,
+        ) -> Result<(Balance, Balance), GovernorError> {
+            Ok(crate::testing::token_balances_at(account, timestamp))
+        }
+
+        /// Returns the governance token `average_balance_of(account, start, end)`
+        /// and `average_total_supply(start, end)`.
+        #[cfg(not(any(test, feature = "testing")))]
+        fn token_averages(
+            governance_token: AccountId,
+            account: AccountId,
+            start: u64,
+            end: u64,
+        ) -> Result<(Balance, Balance), GovernorError> {
+            call::token_averages(governance_token, account, start, end)
+                .ok_or(GovernorError::TokenCallFailed)
+        }
+
+        #[cfg(any(test, feature = "testing"))]
+        fn token_averages(
+            _governance_token: AccountId,
+            account: AccountId,
+            start: u64,
+            end: u64,
+        ) -> Result<(Balance, Balance), GovernorError> {
+            Ok(crate::testing::token_averages(account, start, end))
+        }
+
+        #[cfg(not(any(test, feature = "testing")))]
+        fn external_voting_power(
+            source: AccountId,
+            account: AccountId,
+            timestamp: u64,
+        ) -> Result<(Balance, Balance), GovernorError> {
+            call::voting_power(source, account, timestamp)
+                .zip(call::total_voting_power(source, timestamp))
+                .ok_or(GovernorError::WeightSourceFailed)
+        }
+
+        #[cfg(any(test, feature = "testing"))]
+        fn external_voting_power(
+            source: AccountId,
+            account: AccountId,
+            _timestamp: u64,
+        ) -> Result<(Balance, Balance), GovernorError> {
+            crate::testing::voting_power(source, account)
+                .ok_or(GovernorError::WeightSourceFailed)
+        }
+
+        /// Cross-contract calls aren't supported off-chain; tests read balances
+        /// from a mock token instead.
+        #[cfg(any(test, feature = "testing"))]
+        fn token_balances(
+            _governance_token: AccountId,
+            account: AccountId,
+        ) -> Result<(Balance, Balance), GovernorError> {
+            Ok(crate::testing::token_balances(account))
+        }
+
+        /// Returns the owner of the token `id` of the PSP34 `collection`.
+        #[cfg(not(any(test, feature = "testing")))]
+        fn nft_owner(
+            collection: AccountId,
+            id: Id,
+        ) -> Result<Option<AccountId>, GovernorError> {
+            use ink::codegen::TraitCallBuilder;
+            let collection: ink::contract_ref!(PSP34) = collection.into();
+            collection
+                .call()
+                .owner_of(id)
+                .try_invoke()
+                .map_err(|_| GovernorError::NftCallFailed)?
+                .map_err(|_| GovernorError::NftCallFailed)
+        }
+
+        /// Off-chain, NFTs live in the mock ledger of the testing module.
+        #[cfg(any(test, feature = "testing"))]
+        fn nft_owner(
+            collection: AccountId,
+            id: Id,
+        ) -> Result<Option<AccountId>, GovernorError> {
+            Ok(crate::testing::nft_owner(collection, id))
+        }
+
+        #[cfg(not(any(test, feature = "testing")))]
+        fn call_nft(
+            &self,
+            collection: AccountId,
+            action: &NftAction,
+        ) -> Result<(), GovernorError> {
+            use ink::codegen::TraitCallBuilder;
+            let mut collection: ink::contract_ref!(PSP34) = collection.into();
+            let result = match action {
+                NftAction::Transfer { id, to } => {
+                    collection
+                        .call_mut()
+                        .transfer(*to, id.clone(), Vec::new())
+                        .try_invoke()
+                }
+                NftAction::Approve {
+                    operator,
+                    id,
+                    approved,
+                } => {
+                    collection
+                        .call_mut()
+                        .approve(*operator, id.clone(), *approved)
+                        .try_invoke()
+                }
+            };
+            result
+                .map_err(|_| GovernorError::NftCallFailed)?
+                .map_err(|_| GovernorError::NftCallFailed)?
+                .map_err(|_| GovernorError::NftCallFailed)
+        }
+
+        #[cfg(any(test, feature = "testing"))]
+        fn call_nft(
+            &self,
+            collection: AccountId,
+            action: &NftAction,
+        ) -> Result<(), GovernorError> {
+            let treasury = self.env().account_id();
+            match action {
+                NftAction::Transfer { id, to } => {
+                    crate::testing::transfer_nft(collection, treasury, *to, id.clone())
+                }
+                NftAction::Approve {
+                    operator,
+                    id,
+                    approved,
+                } => {
+                    crate::testing::approve_nft(
+                        collection,
+                        treasury,
+                        *operator,
+                        id.clone(),
+                        *approved,
+                    )
+                }
+            }
+            .map_err(|_| GovernorError::NftCallFailed)
+        }
+
+        fn insert_nft_proposal(
+            &mut self,
+            collection: AccountId,
+            action: NftAction,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            let proposal_id = self.insert_proposal(
+                self.env().caller(),
+                collection,
+                0,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.nft_actions.insert(proposal_id, &action);
+            Ok(())
+        }
+
+        /// Carries out `action` on `collection` and drops transferred tokens from
+        /// the holdings.
+        fn execute_nft_action(
+            &mut self,
+            collection: AccountId,
+            action: NftAction,
+        ) -> Result<(), GovernorError> {
+            self.call_nft(collection, &action)?;
+            if let NftAction::Transfer { id, .. } = action {
+                let mut held = self.nft_holdings(collection);
+                held.retain(|held| *held != id);
+                if held.is_empty() {
+                    self.nft_holdings.remove(collection);
+                    let mut collections = self.nft_collections.get_or_default();
+                    collections.retain(|held| *held != collection);
+                    self.nft_collections.set(&collections);
+                } else {
+                    self.nft_holdings.insert(collection, &held);
+                }
+            }
+            Ok(())
+        }
+
+        /// Transfers `amount` of the PSP22 `token` from the treasury to `to`.
+        #[cfg(not(any(test, feature = "testing")))]
+        fn transfer_tokens(
+            token: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), GovernorError> {
+            use ink::codegen::TraitCallBuilder;
+            let mut token: ink::contract_ref!(PSP22) = token.into();
+            token
+                .call_mut()
+                .transfer(to, amount, Vec::new())
+                .try_invoke()
+                .map_err(|_| GovernorError::TokenCallFailed)?
+                .map_err(|_| GovernorError::TokenCallFailed)?
+                .map_err(|_| GovernorError::TokenCallFailed)
+        }
+
+        #[cfg(any(test, feature = "testing"))]
+        fn transfer_tokens(
+            _token: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), GovernorError> {
+            crate::testing::transfer_tokens(
+                ink::env::account_id::<ink::env::DefaultEnvironment>(),
+                to,
+                amount,
+            )
+            .map_err(|_| GovernorError::TokenCallFailed)
+        }
+
+        /// Accounts `amount` of `asset` paid by the calling revenue source and
+        /// earmarks the pools' shares of it.
+        fn record_revenue(
+            &mut self,
+            asset: Asset,
+            amount: Balance,
+        ) -> Result<(), GovernorError> {
+            let source = self.env().caller();
+            let config = self.settings();
+            let tag = self
+                .revenue_sources()
+                .into_iter()
+                .find(|(registered, _)| *registered == source)
+                .map(|(_, tag)| tag)
+                .ok_or(GovernorError::UnknownRevenueSource)?;
+            let collected = self.revenue_collected(source, asset);
+            self.revenue.insert((source, asset), &(collected + amount));
+            let (contributors, distribution) = config.revenue_split;
+            for (pool, share) in [
+                (RevenuePool::Contributors, contributors),
+                (RevenuePool::Distribution, distribution),
+            ] {
+                let earmarked = amount * Balance::from(share) / 100;
+                if earmarked > 0 {
+                    let balance = self.revenue_pool(pool, asset);
+                    self.revenue_pools
+                        .insert((pool, asset), &(balance + earmarked));
+                }
+            }
+            self.record_entry(
+                LedgerCategory::Revenue,
+                Flow::Income,
+                asset,
+                source,
+                None,
+                amount,
+            );
+            self.env().emit_event(RevenueCollected {
+                schema_version: EVENTS_VERSION,
+                source,
+                tag,
+                asset,
+                amount,
+            });
+            Ok(())
+        }
+
+        fn pay_from_pool(
+            &mut self,
+            proposal_id: ProposalId,
+            pool: RevenuePool,
+            asset: Asset,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), GovernorError> {
+            let balance = self.revenue_pool(pool, asset);
+            ensure!(balance < amount, GovernorError::PoolDepleted);
+            self.revenue_pools
+                .insert((pool, asset), &(balance - amount));
+            match asset {
+                Asset::Native => {
+                    self.env()
+                        .transfer(to, amount)
+                        .map_err(|_| GovernorError::TransferFailed)?
+                }
+                Asset::Psp22(token) => {
+                    let held = self.token_revenue.get(token).unwrap_or_default();
+                    self.token_revenue
+                        .insert(token, &held.saturating_sub(amount));
+                    Self::transfer_tokens(token, to, amount)?
+                }
+            }
+            let category = match pool {
+                RevenuePool::Contributors => LedgerCategory::Payroll,
+                RevenuePool::Distribution => LedgerCategory::Distribution,
+            };
+            if matches!((pool, asset), (RevenuePool::Distribution, Asset::Native))
+                && self.is_member(to)
+            {
+                let mut account = self.capital_account(to);
+                account.allocated += amount;
+                self.capital_accounts.insert(to, &account);
+            }
+            self.record_entry(
+                category,
+                Flow::Expense,
+                asset,
+                to,
+                Some(proposal_id),
+                amount,
+            );
+            Ok(())
+        }
+
+        /// Appends a treasury movement to the ledger and its period's roll-up.
+        fn record_entry(
+            &mut self,
+            category: LedgerCategory,
+            flow: Flow,
+            asset: Asset,
+            counterparty: AccountId,
+            proposal_id: Option<ProposalId>,
+            amount: Balance,
+        ) {
+            let timestamp = self.now();
+            let index = self.ledger_length.get_or_default();
+            self.ledger.insert(
+                index,
+                &LedgerEntry {
+                    category,
+                    flow,
+                    asset,
+                    counterparty,
+                    proposal_id,
+                    amount,
+                    timestamp,
+                },
+            );
+            self.ledger_length.set(&(index + 1));
+            let key = ((timestamp / LEDGER_PERIOD) as u32, category, asset);
+            let mut totals = self.ledger_totals.get(key).unwrap_or_default();
+            match flow {
+                Flow::Income => totals.income += amount,
+                Flow::Expense => totals.expense += amount,
+            }
+            self.ledger_totals.insert(key, &totals);
+            if matches!(asset, Asset::Native) {
+                let mut totals = self.treasury_totals.get_or_default();
+                match flow {
+                    Flow::Income => totals.income += amount,
+                    Flow::Expense => totals.expense += amount,
+                }
+                self.treasury_totals.set(&totals);
+            }
+        }
+
+        fn receipt_bucket(voter: &AccountId) -> u8 {
+            AsRef::<[u8; 32]>::as_ref(voter)[0] % RECEIPT_BUCKETS
+        }
+
+        fn check_proposal_input(
+            amount: Balance,
+            duration: u64,
+        ) -> Result<(), GovernorError> {
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            Self::voting_period(duration)?;
+            Ok(())
+        }
+
+        /// Converts a voting period of `duration` seconds to milliseconds, failing
+        /// if it's outside `MIN_VOTING_PERIOD..=MAX_VOTING_PERIOD`.
+        fn voting_period(duration: u64) -> Result<u64, GovernorError> {
+            ensure!(
+                !(MIN_VOTING_PERIOD..=MAX_VOTING_PERIOD).contains(&duration),
+                GovernorError::DurationError
+            );
+            Ok(duration * 1000)
+        }
+
+        /// Like `voting_period`, within the `voting_periods` of `category` if
+        /// configured, a `duration` of zero standing for their default.
+        fn category_voting_period(
+            &self,
+            category: Category,
+            duration: u64,
+        ) -> Result<u64, GovernorError> {
+            match self.voting_periods().iter().find(|(c, _)| *c == category) {
+                Some((_, periods)) => {
+                    let duration = if duration == 0 {
+                        periods.default
+                    } else {
+                        duration
+                    };
+                    ensure!(
+                        !(periods.min..=periods.max).contains(&duration),
+                        GovernorError::DurationError
+                    );
+                    Self::voting_period(duration)
+                }
+                None => Self::voting_period(duration),
+            }
+        }
+
+        /// Start and end of the vote on a proposal in `category` lasting `duration`
+        /// seconds from `vote_start`, or of the voting window the proposal queues
+        /// for in the governance cycle if any.
+        fn voting_window(
+            &self,
+            config: &GovernorConfig,
+            category: Category,
+            duration: u64,
+            vote_start: u64,
+        ) -> Result<(u64, u64), GovernorError> {
+            let voting_period = self.category_voting_period(category, duration)?;
+            Ok(match &config.cycle {
+                Some(cycle) => Self::cycle_voting_window(cycle, vote_start),
+                None => (vote_start, vote_start.saturating_add(voting_period)),
+            })
+        }
+
+        fn create_proposal(
+            &mut self,
+            proposer: AccountId,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+            category: Category,
+        ) -> Result<ProposalId, GovernorError> {
+            ensure!(amount == 0, GovernorError::AmountShouldNotBeZero);
+            self.insert_proposal(proposer, to, amount, duration, category)
+        }
+
+        fn insert_proposal(
+            &mut self,
+            proposer: AccountId,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+            category: Category,
+        ) -> Result<ProposalId, GovernorError> {
+            let now = self.now();
+            self.insert_proposal_at(proposer, to, amount, duration, category, now)
+        }
+
+        fn insert_proposal_at(
+            &mut self,
+            proposer: AccountId,
+            to: AccountId,
+            amount: Balance,
+            duration: u64,
+            category: Category,
+            vote_start: u64,
+        ) -> Result<ProposalId, GovernorError> {
+            let config = self.settings();
+            self.check_tier(&config, proposer, self.stake_snapshot(), |policy| {
+                policy.propose
+            })?;
+            let (vote_start, vote_end) =
+                self.voting_window(&config, category, duration, vote_start)?;
+            let proposal = Proposal {
+                to,
+                vote_start,
+                vote_end,
+                executed: false,
+                amount,
+            };
+            let proposal_id = self.next_proposal_id;
+            invariant!(
+                !self.proposals.contains(proposal_id),
+                "proposal {} would be reopened",
+                proposal_id.0
+            );
+            self.proposals.insert(proposal_id, &proposal);
+            self.next_proposal_id = proposal_id.next();
+            self.index_proposal(proposal_id);
+            if category != GENERAL_CATEGORY {
+                self.proposal_categories.insert(proposal_id, &category);
+            }
+            self.add_to_index(ProposalIndex::Category(category), proposal_id);
+            let epoch_length = config.epoch_length;
+            if epoch_length > 0 {
+                let epoch = (proposal.vote_start / epoch_length) as u32;
+                let opened = self.epoch_proposals.get(epoch).unwrap_or_default();
+                self.epoch_proposals.insert(epoch, &(opened + 1));
+            }
+            self.proposers.insert(proposal_id, &proposer);
+            self.add_to_index(ProposalIndex::Proposer(proposer), proposal_id);
+            Ok(proposal_id)
+        }
+
+        /// Share of the supply (in `math::PRECISION` units) that must vote on
+        /// `proposal_id` by now for it to pass.
+        fn quorum_at(
+            &self,
+            config: &GovernorConfig,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+        ) -> u128 {
+            let quorum = math::interpolate(
+                math::percent(config.quorum),
+                math::percent(config.min_quorum),
+                self.now()
+                    .min(proposal.vote_end)
+                    .saturating_sub(proposal.vote_start),
+                config.quorum_decay_period,
+            );
+            match self.voting_thresholds.get(proposal_id) {
+                Some(thresholds) => quorum.max(math::percent(thresholds.quorum)),
+                None => quorum,
+            }
+        }
+
+        /// Adds the share (in %) of `weight` given by each of `shares` to the tally
+        /// in the same position.
+        fn add_split(
+            tallies: &mut [u64],
+            shares: &[u8],
+            weight: u64,
+        ) -> Result<(), GovernorError> {
+            ensure!(
+                shares.len() != tallies.len()
+                    || shares.iter().map(|share| u32::from(*share)).sum::<u32>() != 100,
+                GovernorError::InvalidAllocation
+            );
+            for (tally, share) in tallies.iter_mut().zip(shares) {
+                *tally += weight * u64::from(*share) / 100;
+            }
+            Ok(())
+        }
+
+        /// Splits `budget` between the recipients of `allocation` in proportion to
+        /// their tallies and returns the amount paid out, short of `budget` by the
+        /// rounding.
+        fn pay_allocation(
+            &mut self,
+            proposal_id: ProposalId,
+            budget: Balance,
+            allocation: Allocation,
+        ) -> Result<Balance, GovernorError> {
+            let allocated = allocation.tallies.iter().sum::<u64>();
+            let mut paid = 0;
+            for (recipient, tally) in
+                allocation.recipients.into_iter().zip(allocation.tallies)
+            {
+                let amount = math::mul_div(budget, tally.into(), allocated.into())
+                    .unwrap_or_default();
+                if amount > 0 {
+                    self.env()
+                        .transfer(recipient, amount)
+                        .map_err(|_| GovernorError::TransferFailed)?;
+                    self.record_entry(
+                        LedgerCategory::Grant,
+                        Flow::Expense,
+                        Asset::Native,
+                        recipient,
+                        Some(proposal_id),
+                        amount,
+                    );
+                    paid += amount;
+                }
+            }
+            Ok(paid)
+        }
+
+        /// Transfers `amount` from the treasury to `to` on behalf of `proposal_id`.
+        fn pay(
+            &mut self,
+            proposal_id: ProposalId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<Balance, GovernorError> {
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| GovernorError::TransferFailed)?;
+            self.record_entry(
+                LedgerCategory::Investment,
+                Flow::Expense,
+                Asset::Native,
+                to,
+                Some(proposal_id),
+                amount,
+            );
+            Ok(amount)
+        }
+
+        /// Whether the `identity_gate` verifies `account` as a unique person. Every
+        /// account is, without a gate.
+        fn is_verified(config: &GovernorConfig, account: AccountId) -> bool {
+            config
+                .identity_gate
+                .map_or(true, |gate| Self::query_verified(gate, account))
+        }
+
+        #[cfg(not(any(test, feature = "testing")))]
+        fn query_verified(gate: AccountId, account: AccountId) -> bool {
+            call::is_verified(gate, account)
+        }
+
+        #[cfg(any(test, feature = "testing"))]
+        fn query_verified(gate: AccountId, account: AccountId) -> bool {
+            crate::testing::is_verified(gate, account)
+        }
+
+        /// `blake2x256` hash of the SCALE encoded `(proposer, proposal, salt)`.
+        fn commitment(
+            proposer: AccountId,
+            proposal: &CommittedProposal,
+            salt: [u8; 32],
+        ) -> [u8; 32] {
+            let mut commitment = [0; 32];
+            ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+                &(proposer, proposal, salt),
+                &mut commitment,
+            );
+            commitment
+        }
+
+        /// Price of the native currency quoted by the `price_oracle`.
+        fn price(config: &GovernorConfig) -> Result<Balance, GovernorError> {
+            let oracle = config.price_oracle.ok_or(GovernorError::NoPriceOracle)?;
+            Self::query_price(oracle)
+                .filter(|price| *price > 0)
+                .ok_or(GovernorError::PriceOracleFailed)
+        }
+
+        #[cfg(not(any(test, feature = "testing")))]
+        fn query_price(oracle: AccountId) -> Option<Balance> {
+            call::price(oracle)
+        }
+
+        #[cfg(any(test, feature = "testing"))]
+        fn query_price(oracle: AccountId) -> Option<Balance> {
+            crate::testing::price(oracle)
+        }
+
+        /// Converts `stable` to the native currency at the current oracle price,
+        /// if it is still within the allowed slippage.
+        fn stable_payout(
+            config: &GovernorConfig,
+            stable: &StableAmount,
+        ) -> Result<Balance, GovernorError> {
+            let price = Self::price(config)?;
+            let (low, high) = Self::price_bounds(stable);
+            ensure!(
+                price < low || price > high,
+                GovernorError::PriceOutOfBounds { price }
+            );
+            Ok(math::mul_div(stable.amount, math::PRECISION, price)
+                .unwrap_or(Balance::MAX))
+        }
+
+        /// Lowest and highest prices `stable` may be converted at.
+        fn price_bounds(stable: &StableAmount) -> (Balance, Balance) {
+            let slippage = stable.max_slippage as Balance;
+            let bound = |percent| {
+                math::mul_div(stable.reference_price, percent, 100)
+                    .unwrap_or(Balance::MAX)
+            };
+            (bound(100 - slippage), bound(100 + slippage))
+        }
+
+        /// Pays each recipient of a batch transfer its amount and returns the total.
+        fn pay_batch(
+            &mut self,
+            proposal_id: ProposalId,
+            transfers: Transfers,
+        ) -> Result<Balance, GovernorError> {
+            let mut paid = 0;
+            for (recipient, amount) in transfers {
+                self.env()
+                    .transfer(recipient, amount)
+                    .map_err(|_| GovernorError::TransferFailed)?;
+                self.record_entry(
+                    LedgerCategory::Investment,
+                    Flow::Expense,
+                    Asset::Native,
+                    recipient,
+                    Some(proposal_id),
+                    amount,
+                );
+                paid += amount;
+            }
+            Ok(paid)
+        }
+
+        /// Whether the final tally of `proposal_id` carries it.
+        fn check_passed(
+            &self,
+            config: &GovernorConfig,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+        ) -> Result<(), GovernorError> {
+            // Fast-tracked proposals need a supermajority of the supply, which
+            // can't be overturned before the vote ends.
+            ensure!(
+                self.now() < proposal.vote_end
+                    && !self.fast_tracked.contains(proposal_id),
+                GovernorError::VotePeriodNotEnded
+            );
+            let needed = self.quorum_at(config, proposal_id, proposal) as u64;
+            if let Some(allocation) = self.allocations.get(proposal_id) {
+                let allocated = allocation.tallies.iter().sum::<u64>();
+                ensure!(
+                    allocated < needed,
+                    GovernorError::QuorumNotReached {
+                        needed,
+                        got: allocated,
+                    }
+                );
+                return Ok(())
+            }
+            let (for_votes, against_vote) = match self.anchored_tallies.get(proposal_id) {
+                Some(tally) => {
+                    ensure!(tally.root.is_none(), GovernorError::TallyNotAnchored);
+                    ensure!(
+                        self.now()
+                            < tally.anchored_at.saturating_add(TALLY_CHALLENGE_WINDOW),
+                        GovernorError::ChallengeWindowOpen
+                    );
+                    (tally.for_votes, tally.against_vote)
+                }
+                None => {
+                    let proposal_vote =
+                        self.proposal_votes.get(proposal_id).unwrap_or_default();
+                    (proposal_vote.for_votes, proposal_vote.against_vote)
+                }
+            };
+            let total_votes = for_votes + against_vote;
+            ensure!(
+                total_votes < needed,
+                GovernorError::QuorumNotReached {
+                    needed,
+                    got: total_votes,
+                }
+            );
+            ensure!(
+                self.fast_tracked.contains(proposal_id)
+                    && (for_votes as u128) < math::percent(config.fast_track_approval),
+                GovernorError::FastTrackApprovalNotReached
+            );
+            ensure!(
+                for_votes <= against_vote,
+                GovernorError::ProposalNotAccepted
+            );
+            ensure!(
+                self.amendments.contains(proposal_id)
+                    && (for_votes as u128) * 100
+                        < u128::from(config.amendment_approval) * total_votes as u128,
+                GovernorError::SupermajorityNotReached
+            );
+            if let Some(thresholds) = self.voting_thresholds.get(proposal_id) {
+                ensure!(
+                    (for_votes as u128) * 100
+                        < u128::from(thresholds.approval) * total_votes as u128,
+                    GovernorError::ApprovalNotReached
+                );
+            }
+            Ok(())
+        }
+
+        /// Whether `proposal_id` has been executed or defeated, so that nothing is
+        /// left to do with it. Polls conclude when their vote ends.
+        fn is_concluded(
+            &self,
+            config: &GovernorConfig,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+        ) -> bool {
+            proposal.executed
+                || self.polls.contains(proposal_id)
+                || matches!(
+                    self.check_passed(config, proposal_id, proposal),
+                    Err(GovernorError::QuorumNotReached { .. }
+                        | GovernorError::FastTrackApprovalNotReached
+                        | GovernorError::ProposalNotAccepted
+                        | GovernorError::SupermajorityNotReached
+                        | GovernorError::ApprovalNotReached)
+                )
+        }
+
+        /// Whether the objection window of `proposal_id` and, for amendments, the
+        /// minimum voting period are over.
+        fn check_unlocked(
+            &self,
+            config: &GovernorConfig,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+        ) -> Result<(), GovernorError> {
+            if config.objection_window > 0
+                && !self.confirmation_votes.contains(proposal_id)
+            {
+                let window = self
+                    .objection_windows
+                    .get(proposal_id)
+                    .ok_or(GovernorError::ObjectionWindowNotStarted)?;
+                ensure!(
+                    self.now()
+                        < window.started_at.saturating_add(config.objection_window),
+                    GovernorError::ObjectionWindowOpen
+                );
+            }
+            if self.amendments.contains(proposal_id) {
+                ensure!(
+                    self.now()
+                        < proposal
+                            .vote_start
+                            .saturating_add(config.amendment_duration),
+                    GovernorError::VotePeriodNotEnded
+                );
+            }
+            if let Some(cycle) = &config.cycle {
+                ensure!(
+                    !matches!(
+                        Self::cycle_phase_at(cycle, self.now()).1,
+                        CyclePhase::Execution
+                    ),
+                    GovernorError::OutsideExecutionWindow
+                );
+            }
+            Ok(())
+        }
+
+        /// Whether the call of a call proposal is on the `call_allowlist`.
+        fn check_call_allowed(
+            &self,
+            proposal: &Proposal,
+            proposal_call: Option<&ProposalCall>,
+        ) -> Result<(), GovernorError> {
+            if let Some(proposal_call) = proposal_call {
+                ensure!(
+                    !self
+                        .call_allowlist()
+                        .contains(&(proposal.to, proposal_call.selector)),
+                    GovernorError::CallNotAllowed
+                );
+            }
+            Ok(())
+        }
+
+        /// Whether the treasury holds the native amount `proposal_id` pays out.
+        fn check_funded(
+            &self,
+            config: &GovernorConfig,
+            proposal_id: ProposalId,
+            proposal: &Proposal,
+        ) -> Result<(), GovernorError> {
+            if !matches!(
+                self.pool_payouts.get(proposal_id),
+                Some((_, Asset::Psp22(_)))
+            ) {
+                let requested = if let Some(stable) = self.stable_amounts.get(proposal_id)
+                {
+                    Self::stable_payout(config, &stable)?.min(proposal.amount)
+                } else if let Some(share) = self.treasury_shares.get(proposal_id) {
+                    self.treasury_share_amount(share, proposal.amount)
+                } else {
+                    proposal.amount
+                };
+                let available = self.free_balance();
+                ensure!(
+                    requested > available,
+                    GovernorError::InsufficientTreasury {
+                        requested,
+                        available,
+                    }
+                );
+            }
+            Ok(())
+        }
+
+        /// Amount a treasury share proposal pays: `share` (in %) of the
+        /// `free_balance`, but no more than `cap`.
+        fn treasury_share_amount(&self, share: u8, cap: Balance) -> Balance {
+            math::mul_div(self.free_balance(), share.into(), 100)
+                .unwrap_or_default()
+                .min(cap)
+        }
+
+        fn index_proposal(&mut self, proposal_id: ProposalId) {
+            let position = self.proposal_count;
+            self.proposal_ids.insert(position, &proposal_id);
+            self.proposal_id_positions.insert(proposal_id, &position);
+            self.proposal_count = position + 1;
+        }
+
+        fn unindex_proposal(&mut self, proposal_id: ProposalId) {
+            let Some(position) = self.proposal_id_positions.take(proposal_id) else {
+                return
+            };
+            let last = self.proposal_count - 1;
+            if position != last {
+                let moved = self.proposal_ids.get(last).expect("indexed position");
+                self.proposal_ids.insert(position, &moved);
+                self.proposal_id_positions.insert(moved, &position);
+            }
+            self.proposal_ids.remove(last);
+            self.proposal_count = last;
+        }
+
+        fn indexed_proposals(
+            &self,
+            index: ProposalIndex,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<ProposalId> {
+            let length = self.index_lengths.get(index).unwrap_or_default();
+            let end = offset.saturating_add(limit).min(length);
+            (offset..end)
+                .filter_map(|position| self.indexed_proposals.get((index, position)))
+                .collect()
+        }
+
+        fn add_to_index(&mut self, index: ProposalIndex, proposal_id: ProposalId) {
+            let position = self.index_lengths.get(index).unwrap_or_default();
+            self.indexed_proposals
+                .insert((index, position), &proposal_id);
+            self.indexed_positions
+                .insert((index, proposal_id), &position);
+            self.index_lengths.insert(index, &(position + 1));
+        }
+
+        fn remove_from_index(&mut self, index: ProposalIndex, proposal_id: ProposalId) {
+            let Some(position) = self.indexed_positions.take((index, proposal_id))
+            else {
+                return
+            };
+            let last = self.index_lengths.get(index).unwrap_or_default() - 1;
+            if position != last {
+                let moved = self
+                    .indexed_proposals
+                    .get((index, last))
+                    .expect("indexed position");
+                self.indexed_proposals.insert((index, position), &moved);
+                self.indexed_positions.insert((index, moved), &position);
+            }
+            self.indexed_proposals.remove((index, last));
+            if last == 0 {
+                self.index_lengths.remove(index);
+            } else {
+                self.index_lengths.insert(index, &last);
+            }
+        }
+
+        fn claim_reimbursements_for(
+            &mut self,
+            voter: AccountId,
+            proposal_ids: Vec<ProposalId>,
+        ) -> Result<Balance, GovernorError> {
+            let amount = proposal_ids
+                .into_iter()
+                .filter_map(|proposal_id| self.reimbursements.take((proposal_id, voter)))
+                .sum();
+            ensure!(amount == 0, GovernorError::NothingToClaim);
+            self.env()
+                .transfer(voter, amount)
+                .map_err(|_| GovernorError::TransferFailed)?;
+            self.release(amount);
+            self.record_entry(
+                LedgerCategory::Rewards,
+                Flow::Expense,
+                Asset::Native,
+                voter,
+                None,
+                amount,
+            );
+            Ok(amount)
+        }
+
+        /// `voter` followed by the accounts whose power flows to them through
+        /// delegation for `category` at `timestamp` and who haven't voted on
+        /// `proposal_id` yet.
+        fn voting_accounts(
+            &self,
+            proposal_id: ProposalId,
+            category: Category,
+            voter: AccountId,
+            timestamp: u64,
+        ) -> Vec<AccountId> {
+            let mut accounts = Vec::from([voter]);
+            let mut level = Vec::from([voter]);
+            for _ in 0..MAX_DELEGATION_DEPTH {
+                let mut next = Vec::new();
+                for delegate in level {
+                    for delegator in self.delegators_for(delegate, category, timestamp) {
+                        // A delegator who voted before delegating has already cast
+                        // their own power and that of their delegators.
+                        if !self.has_voted(proposal_id, delegator) {
+                            next.push(delegator);
+                        }
+                    }
+                }
+                if next.is_empty() {
+                    break
+                }
+                accounts.extend_from_slice(&next);
+                level = next;
+            }
+            accounts
+        }
+
+        /// Next hop from `account` on the delegation chain of `category`, or of
+        /// general delegation if `None`.
+        fn next_delegate(
+            &self,
+            account: AccountId,
+            category: Option<Category>,
+        ) -> Option<AccountId> {
+            match category {
+                Some(category) => {
+                    self.topic_delegates
+                        .get((account, category))
+                        .or_else(|| self.delegates.get(account))
+                }
+                None => self.delegates.get(account),
+            }
+        }
+
+        /// Next hop from `account` on the delegation chain of `category` at
+        /// `timestamp`.
+        fn next_delegate_at(
+            &self,
+            account: AccountId,
+            category: Category,
+            timestamp: u64,
+        ) -> Option<AccountId> {
+            self.delegate_at(account, Some(category), timestamp)
+                .or_else(|| self.delegate_at(account, None, timestamp))
+        }
+
+        /// Accounts whose power flowed directly to `delegate` on proposals in
+        /// `category` at `timestamp`: its topic delegators, and its general
+        /// delegators that didn't override the category.
+        fn delegators_for(
+            &self,
+            delegate: AccountId,
+            category: Category,
+            timestamp: u64,
+        ) -> Vec<AccountId> {
+            let mut delegators = self.delegators_at(delegate, None, timestamp);
+            delegators.retain(|delegator| {
+                self.delegate_at(*delegator, Some(category), timestamp)
+                    .is_none()
+            });
+            delegators.extend(self.delegators_at(delegate, Some(category), timestamp));
+            delegators
+        }
+
+        /// Hops on the longest delegation chain ending at `account`, generally
+        /// (`category` is `None`) or for `category`, counted up to
+        /// `MAX_DELEGATION_DEPTH + 1`. Topic delegators count towards general
+        /// chains, whose power they follow in categories without an override.
+        fn delegation_depth(&self, account: AccountId, category: Option<Category>) -> u8 {
+            let categories = match category {
+                Some(category) => Vec::from([category]),
+                None => {
+                    (0..self.categories().len().max(1))
+                        .map(|category| category as Category)
+                        .collect()
+                }
+            };
+            let mut depth = 0;
+            let mut level = Vec::from([account]);
+            while depth <= MAX_DELEGATION_DEPTH {
+                let mut next = Vec::new();
+                for delegate in level {
+                    for category in &categories {
+                        for delegator in
+                            self.delegators_for(delegate, *category, self.now())
+                        {
+                            if !next.contains(&delegator) {
+                                next.push(delegator);
+                            }
+                        }
+                    }
+                }
+                if next.is_empty() {
+                    break
+                }
+                depth += 1;
+                level = next;
+            }
+            depth
+        }
+
+        /// Delegate of `account` at `timestamp`, generally (`category` is `None`) or
+        /// for `category`.
+        fn delegate_at(
+            &self,
+            account: AccountId,
+            category: Option<Category>,
+            timestamp: u64,
+        ) -> Option<AccountId> {
+            let count = self
+                .delegate_checkpoint_counts
+                .get((account, category))
+                .unwrap_or_default();
+            let index = Self::checkpoint_at(count, timestamp, |index| {
+                self.delegate_checkpoints
+                    .get((account, category, index))
+                    .map_or(0, |(taken_at, _)| taken_at)
+            })?;
+            self.delegate_checkpoints
+                .get((account, category, index))
+                .and_then(|(_, delegate)| delegate)
+        }
+
+        /// Accounts delegating directly to `delegate` at `timestamp`, generally
+        /// (`category` is `None`) or for `category`.
+        fn delegators_at(
+            &self,
+            delegate: AccountId,
+            category: Option<Category>,
+            timestamp: u64,
+        ) -> Vec<AccountId> {
+            let count = self
+                .delegator_checkpoint_counts
+                .get((delegate, category))
+                .unwrap_or_default();
+            Self::checkpoint_at(count, timestamp, |index| {
+                self.delegator_checkpoints
+                    .get((delegate, category, index))
+                    .map_or(0, |(taken_at, _)| taken_at)
+            })
+            .and_then(|index| self.delegator_checkpoints.get((delegate, category, index)))
+            .map(|(_, delegators)| delegators)
+            .unwrap_or_default()
+        }
+
+        /// Index of the last of `count` checkpoints taken at or before `timestamp`,
+        /// `taken_at` giving the time of each.
+        fn checkpoint_at(
+            count: u32,
+            timestamp: u64,
+            taken_at: impl Fn(u32) -> u64,
+        ) -> Option<u32> {
+            let (mut low, mut high) = (0, count);
+            while low < high {
+                let middle = low + (high - low) / 2;
+                if taken_at(middle) <= timestamp {
+                    low = middle + 1;
+                } else {
+                    high = middle;
+                }
+            }
+            low.checked_sub(1)
+        }
+
+        /// Records `delegate` as the delegate of `account` from now on.
+        fn checkpoint_delegate(
+            &mut self,
+            account: AccountId,
+            category: Option<Category>,
+            delegate: Option<AccountId>,
+        ) {
+            let now = self.now();
+            let count = self
+                .delegate_checkpoint_counts
+                .get((account, category))
+                .unwrap_or_default();
+            let index = match count.checked_sub(1) {
+                Some(last)
+                    if self
+                        .delegate_checkpoints
+                        .get((account, category, last))
+                        .map_or(false, |(taken_at, _)| taken_at == now) =>
+                {
+                    last
+                }
+                _ => {
+                    self.delegate_checkpoint_counts
+                        .insert((account, category), &(count + 1));
+                    count
+                }
+            };
+            self.delegate_checkpoints
+                .insert((account, category, index), &(now, delegate));
+        }
+
+        /// Records `delegators` as the direct delegators of `delegate` from now on.
+        fn checkpoint_delegators(
+            &mut self,
+            delegate: AccountId,
+            category: Option<Category>,
+            delegators: &[AccountId],
+        ) {
+            let now = self.now();
+            let count = self
+                .delegator_checkpoint_counts
+                .get((delegate, category))
+                .unwrap_or_default();
+            let index = match count.checked_sub(1) {
+                Some(last)
+                    if self
+                        .delegator_checkpoints
+                        .get((delegate, category, last))
+                        .map_or(false, |(taken_at, _)| taken_at == now) =>
+                {
+                    last
+                }
+                _ => {
+                    self.delegator_checkpoint_counts
+                        .insert((delegate, category), &(count + 1));
+                    count
+                }
+            };
+            self.delegator_checkpoints
+                .insert((delegate, category, index), &(now, delegators.to_vec()));
+        }
+
+        /// Accounts delegating directly to `delegate`, generally or for `category`.
+        fn direct_delegators(
+            &self,
+            delegate: AccountId,
+            category: Option<Category>,
+        ) -> Vec<AccountId> {
+            match category {
+                Some(category) => self.topic_delegators.get((delegate, category)),
+                None => self.delegators.get(delegate),
+            }
+            .unwrap_or_default()
+        }
+
+        fn set_direct_delegators(
+            &mut self,
+            delegate: AccountId,
+            category: Option<Category>,
+            delegators: &Vec<AccountId>,
+        ) {
+            self.checkpoint_delegators(delegate, category, delegators);
+            match (category, delegators.is_empty()) {
+                (Some(category), true) => {
+                    self.topic_delegators.remove((delegate, category))
+                }
+                (Some(category), false) => {
+                    self.topic_delegators
+                        .insert((delegate, category), delegators);
+                }
+                (None, true) => self.delegators.remove(delegate),
+                (None, false) => {
+                    self.delegators.insert(delegate, delegators);
+                }
+            }
+        }
+
+        /// Points `delegator`'s general (`category` is `None`) or topic delegation
+        /// at `to`, or removes it.
+        fn set_delegate(
+            &mut self,
+            delegator: AccountId,
+            to: Option<AccountId>,
+            category: Option<Category>,
+        ) -> Result<(), GovernorError> {
+            let previous = match category {
+                Some(category) => self.topic_delegates.get((delegator, category)),
+                None => self.delegates.get(delegator),
+            };
+            if previous == to {
+                return Ok(())
+            }
+            let mut delegators = Vec::new();
+            if let Some(to) = to {
+                let mut hops = 1;
+                let mut current = to;
+                loop {
+                    ensure!(current == delegator, GovernorError::DelegationCycle);
+                    let Some(next) = self.next_delegate(current, category) else {
+                        break
+                    };
+                    hops += 1;
+                    ensure!(
+                        hops > MAX_DELEGATION_DEPTH,
+                        GovernorError::DelegationTooDeep
+                    );
+                    current = next;
+                }
+                // Chains already ending at the delegator grow by as many hops.
+                ensure!(
+                    hops + self.delegation_depth(delegator, category)
+                        > MAX_DELEGATION_DEPTH,
+                    GovernorError::DelegationTooDeep
+                );
+                delegators = self.direct_delegators(to, category);
+                ensure!(
+                    delegators.len() >= MAX_DELEGATORS,
+                    GovernorError::TooManyDelegators
+                );
+            }
+
+            let balance = match (category, to) {
+                (None, Some(_)) => {
+                    Self::voting_power(&self.settings(), delegator, self.now())?.0
+                }
+                _ => 0,
+            };
+
+            if category.is_none() {
+                self.close_fee_stream(delegator)?;
+                self.track_delegation(delegator, previous, to, balance);
+            }
+            if let Some(previous) = previous {
+                let mut previous_delegators = self.direct_delegators(previous, category);
+                previous_delegators.retain(|account| *account != delegator);
+                self.set_direct_delegators(previous, category, &previous_delegators);
+            }
+            if let Some(to) = to {
+                delegators.push(delegator);
+                self.set_direct_delegators(to, category, &delegators);
+            }
+            match (category, to) {
+                (Some(category), Some(to)) => {
+                    self.topic_delegates.insert((delegator, category), &to);
+                }
+                (Some(category), None) => {
+                    self.topic_delegates.remove((delegator, category))
+                }
+                (None, Some(to)) => {
+                    self.delegates.insert(delegator, &to);
+                }
+                (None, None) => self.delegates.remove(delegator),
+            }
+            self.checkpoint_delegate(delegator, category, to);
+            self.env().emit_event(DelegateChanged {
+                schema_version: EVENTS_VERSION,
+                delegator,
+                delegate: to,
+                category,
+            });
+            Ok(())
+        }
+
+        /// Moves `delegator` from the delegation analytics of `previous` to those of
+        /// `to`, counting its current `balance`.
+        fn track_delegation(
+            &mut self,
+            delegator: AccountId,
+            previous: Option<AccountId>,
+            to: Option<AccountId>,
+            balance: Balance,
+        ) {
+            let mut stats = self.delegation_stats.get_or_default();
+            if let Some(previous) = previous {
+                let counted = self.delegated_balances.take(delegator).unwrap_or_default();
+                stats.delegated_supply -= counted;
+                let weight = self.delegated_weight(previous) - counted;
+                self.set_delegated_weight(&mut stats, previous, weight);
+            }
+            if let Some(to) = to {
+                self.delegated_balances.insert(delegator, &balance);
+                stats.delegated_supply += balance;
+                let weight = self.delegated_weight(to) + balance;
+                self.set_delegated_weight(&mut stats, to, weight);
+            }
+            self.delegation_stats.set(&stats);
+        }
+
+        fn set_delegated_weight(
+            &mut self,
+            stats: &mut DelegationStats,
+            delegate: AccountId,
+            weight: Balance,
+        ) {
+            match (self.delegated_weights.contains(delegate), weight > 0) {
+                (false, true) => stats.active_delegates += 1,
+                (true, false) => stats.active_delegates -= 1,
+                _ => (),
+            }
+            if weight > 0 {
+                self.delegated_weights.insert(delegate, &weight);
+            } else {
+                self.delegated_weights.remove(delegate);
+            }
+            // A delegate that drops out of the ranking is only replaced by one
+            // outside it once that one's weight changes.
+            let mut top = self.top_delegates.get_or_default();
+            top.retain(|(account, _)| *account != delegate);
+            if weight > 0 {
+                let position = top.partition_point(|(_, ranked)| *ranked >= weight);
+                top.insert(position, (delegate, weight));
+                top.truncate(MAX_TOP_DELEGATES);
+            }
+            self.top_delegates.set(&top);
+        }
+
+        /// Sets `amount` of the native balance aside, see `reserved_balance`.
+        fn reserve(&mut self, amount: Balance) {
+            self.reserved_balance
+                .set(&self.reserved_balance().saturating_add(amount));
+        }
+
+        /// Returns `amount` set aside once it has been paid out.
+        fn release(&mut self, amount: Balance) {
+            self.reserved_balance
+                .set(&self.reserved_balance().saturating_sub(amount));
+        }
+
+        /// Credits the delegate with what `stream` has paid out up to `now`.
+        fn settle_fee_stream(&mut self, mut stream: FeeStream, now: u64) -> FeeStream {
+            let elapsed = now.saturating_sub(stream.settled_at);
+            let due = math::mul_div(stream.rate, elapsed as u128, FEE_PERIOD as u128)
+                .unwrap_or(Balance::MAX)
+                .min(stream.remaining);
+            if due > 0 {
+                let earned = self
+                    .delegate_earnings
+                    .get(stream.delegate)
+                    .unwrap_or_default();
+                self.delegate_earnings
+                    .insert(stream.delegate, &(earned + due));
+                stream.remaining -= due;
+            }
+            stream.settled_at = now;
+            stream
+        }
+
+        /// Settles and closes `delegator`'s fee stream, refunding the deposit left.
+        fn close_fee_stream(
+            &mut self,
+            delegator: AccountId,
+        ) -> Result<(), GovernorError> {
+            let Some(stream) = self.fee_streams.take(delegator) else {
+                return Ok(())
+            };
+            let stream = self.settle_fee_stream(stream, self.now());
+            if stream.remaining > 0 {
+                self.env()
+                    .transfer(delegator, stream.remaining)
+                    .map_err(|_| GovernorError::TransferFailed)?;
+                self.release(stream.remaining);
+            }
+            Ok(())
+        }
+
+        fn clear_receipts(&mut self, proposal_id: ProposalId, receipt_buckets: u64) {
+            for bucket in 0..RECEIPT_BUCKETS {
+                if receipt_buckets & (1 << bucket) != 0 {
+                    self.receipts.remove((proposal_id, bucket));
+                }
+            }
+        }
+
+        /// Discards the ballots cast on `proposal_id` so that it can be voted on
+        /// afresh. An anchored tally has to be anchored again.
+        fn reset_tally(&mut self, proposal_id: ProposalId) {
+            if let Some(proposal_vote) = self.proposal_votes.take(proposal_id) {
+                self.clear_receipts(proposal_id, proposal_vote.receipt_buckets);
+            }
+            if let Some(mut tally) = self.anchored_tallies.get(proposal_id) {
+                tally.root = None;
+                tally.anchored_at = 0;
+                tally.for_votes = 0;
+                tally.against_vote = 0;
+                tally.disputed.clear();
+                self.anchored_tallies.insert(proposal_id, &tally);
+            }
+        }
+
+        /// Index of the governance cycle `timestamp` falls in, and its window then.
+        fn cycle_phase_at(cycle: &GovernanceCycle, timestamp: u64) -> (u64, CyclePhase) {
+            let length = cycle.length.max(1);
+            let offset = timestamp % length;
+            let phase = if offset < cycle.submission {
+                CyclePhase::Submission
+            } else if offset < cycle.submission.saturating_add(cycle.voting) {
+                CyclePhase::Voting
+            } else {
+                CyclePhase::Execution
+            };
+            (timestamp / length, phase)
+        }
+
+        /// Start and end of the voting window of the first governance cycle whose
+        /// submission window isn't over at `timestamp`.
+        fn cycle_voting_window(cycle: &GovernanceCycle, timestamp: u64) -> (u64, u64) {
+            let length = cycle.length.max(1);
+            let mut start = timestamp - timestamp % length;
+            if timestamp - start >= cycle.submission {
+                start = start.saturating_add(length);
+            }
+            let vote_start = start.saturating_add(cycle.submission);
+            (vote_start, vote_start.saturating_add(cycle.voting))
+        }
+
+        /// Epoch `now` falls in, if epochs are enabled.
+        fn current_epoch(config: &GovernorConfig, now: u64) -> Option<u32> {
+            now.checked_div(config.epoch_length)
+                .map(|epoch| epoch as u32)
+        }
+
+        /// Adds `account` to the member registry, minting its shares, or removes it
+        /// and burns them.
+        fn change_membership(
+            &mut self,
+            config: &GovernorConfig,
+            account: AccountId,
+            change: MembershipChange,
+        ) -> Result<(), GovernorError> {
+            let count = self.member_count();
+            let total_shares = self.total_shares();
+            match change {
+                MembershipChange::Add => {
+                    ensure!(self.is_member(account), GovernorError::AlreadyMember);
+                    self.members.insert(account, &());
+                    let mut members = self.members();
+                    members.push(account);
+                    self.member_list.set(&members);
+                    self.kicked.remove(account);
+                    self.member_count.set(&(count + 1));
+                    let shares = config.membership_shares;
+                    if shares > 0 {
+                        self.shares
+                            .insert(account, &(self.shares(account) + shares));
+                        self.total_shares.set(&(total_shares + shares));
+                    }
+                    self.env().emit_event(MemberAdded {
+                        schema_version: EVENTS_VERSION,
+                        member: account,
+                        shares,
+                    });
+                }
+                MembershipChange::Remove => {
+                    ensure!(!self.is_member(account), GovernorError::NotMember);
+                    self.members.remove(account);
+                    self.remove_from_member_list(account);
+                    self.member_count.set(&(count - 1));
+                    let shares = self.shares.take(account).unwrap_or_default();
+                    self.total_shares.set(&(total_shares - shares));
+                    self.env().emit_event(MemberRemoved {
+                        schema_version: EVENTS_VERSION,
+                        member: account,
+                        shares,
+                    });
+                }
+                MembershipChange::Kick => {
+                    ensure!(!self.is_member(account), GovernorError::NotMember);
+                    self.members.remove(account);
+                    self.remove_from_member_list(account);
+                    self.member_count.set(&(count - 1));
+                    self.kicked.insert(account, &());
+                    let loot = self.shares.take(account).unwrap_or_default();
+                    self.total_shares.set(&(total_shares - loot));
+                    self.loot.insert(account, &(self.loot(account) + loot));
+                    self.total_loot.set(&(self.total_loot() + loot));
+                    self.env().emit_event(MemberKicked {
+                        schema_version: EVENTS_VERSION,
+                        member: account,
+                        loot,
+                    });
+                }
+            }
+            Ok(())
+        }
+
+        /// Fails unless one of the deposit `windows` is open at `now`.
+        fn check_deposit_window(
+            windows: &DepositWindows,
+            now: u64,
+        ) -> Result<(), GovernorError> {
+            let offset = now.checked_rem(windows.period).unwrap_or_default();
+            ensure!(
+                offset >= windows.length,
+                GovernorError::DepositWindowClosed {
+                    opens_at: (now - offset).saturating_add(windows.period),
+                }
+            );
+            Ok(())
+        }
+
+        /// Fails if `account`'s shares and loot are locked up.
+        fn check_lockup(&self, account: AccountId) -> Result<(), GovernorError> {
+            match self.lockup_end(account) {
+                Some(until) => Err(GovernorError::LockedUp { until }),
+                None => Ok(()),
+            }
+        }
+
+        fn remove_from_member_list(&mut self, account: AccountId) {
+            let mut members = self.members();
+            members.retain(|member| *member != account);
+            self.member_list.set(&members);
+        }
+
+        /// Stores `config` as the one in force, its lists in their own cells.
+        fn store_config(&mut self, config: &GovernorConfig) {
+            let mut config = config.clone();
+            self.categories
+                .set(&core::mem::take(&mut config.categories));
+            self.guardians.set(&core::mem::take(&mut config.guardians));
+            self.default_delegates
+                .set(&core::mem::take(&mut config.default_delegates));
+            self.call_allowlist
+                .set(&core::mem::take(&mut config.call_allowlist));
+            self.retro_badge_holders
+                .set(&core::mem::take(&mut config.retro_badge_holders));
+            self.causes.set(&core::mem::take(&mut config.causes));
+            self.revenue_sources
+                .set(&core::mem::take(&mut config.revenue_sources));
+            self.voting_periods
+                .set(&core::mem::take(&mut config.voting_periods));
+            self.adapters.set(&core::mem::take(&mut config.adapters));
+            self.accept_selectors
+                .set(&core::mem::take(&mut config.accept_selectors));
+            self.admin_allowlist
+                .set(&core::mem::take(&mut config.admin_allowlist));
+            self.config.set(&config);
+        }
+
+        /// The stored config, lists included, ignoring due scheduled changes.
+        fn stored_config(&self) -> GovernorConfig {
+            GovernorConfig {
+                categories: self.categories.get_or_default(),
+                guardians: self.guardians.get_or_default(),
+                default_delegates: self.default_delegates.get_or_default(),
+                call_allowlist: self.call_allowlist.get_or_default(),
+                retro_badge_holders: self.retro_badge_holders.get_or_default(),
+                causes: self.causes.get_or_default(),
+                revenue_sources: self.revenue_sources.get_or_default(),
+                voting_periods: self.voting_periods.get_or_default(),
+                adapters: self.adapters.get_or_default(),
+                accept_selectors: self.accept_selectors.get_or_default(),
+                admin_allowlist: self.admin_allowlist.get_or_default(),
+                ..self.config.get().expect("config is set in the constructor")
+            }
+        }
+
+        /// Latest scheduled config change whose activation time has come, but that
+        /// `activate_configs` hasn't put in force yet.
+        fn due_config(&self) -> Option<GovernorConfig> {
+            let now = self.now();
+            let (_, proposal_id) = self
+                .config_schedule
+                .get_or_default()
+                .into_iter()
+                .take_while(|(activates_at, _)| *activates_at <= now)
+                .last()?;
+            self.scheduled_configs
+                .get(proposal_id)
+                .map(|scheduled| scheduled.config)
+        }
+
+        /// Governance parameters in force, like `config`, but without reading the
+        /// lists kept in their own cells, which are left empty unless a scheduled
+        /// change is due. Read those through their own getters, e.g. `guardians`.
+        fn settings(&self) -> GovernorConfig {
+            self.due_config().unwrap_or_else(|| {
+                self.config.get().expect("config is set in the constructor")
+            })
+        }
+
+        fn categories(&self) -> Vec<String> {
+            self.due_config()
+                .map_or_else(|| self.categories.get_or_default(), |c| c.categories)
+        }
+
+        fn guardians(&self) -> Vec<AccountId> {
+            self.due_config()
+                .map_or_else(|| self.guardians.get_or_default(), |c| c.guardians)
+        }
+
+        fn default_delegates(&self) -> Vec<AccountId> {
+            self.due_config().map_or_else(
+                || self.default_delegates.get_or_default(),
+                |c| c.default_delegates,
+            )
+        }
+
+        fn call_allowlist(&self) -> CallAllowlist {
+            self.due_config().map_or_else(
+                || self.call_allowlist.get_or_default(),
+                |c| c.call_allowlist,
+            )
+        }
+
+        fn retro_badge_holders(&self) -> Vec<AccountId> {
+            self.due_config().map_or_else(
+                || self.retro_badge_holders.get_or_default(),
+                |c| c.retro_badge_holders,
+            )
+        }
+
+        fn causes(&self) -> Vec<(AccountId, Balance)> {
+            self.due_config()
+                .map_or_else(|| self.causes.get_or_default(), |c| c.causes)
+        }
+
+        fn revenue_sources(&self) -> Vec<(AccountId, String)> {
+            self.due_config().map_or_else(
+                || self.revenue_sources.get_or_default(),
+                |c| c.revenue_sources,
+            )
+        }
+
+        fn voting_periods(&self) -> Vec<(Category, VotingPeriods)> {
+            self.due_config().map_or_else(
+                || self.voting_periods.get_or_default(),
+                |c| c.voting_periods,
+            )
+        }
+
+        fn adapters(&self) -> Vec<(AccountId, String)> {
+            self.due_config()
+                .map_or_else(|| self.adapters.get_or_default(), |c| c.adapters)
+        }
+
+        fn accept_selectors(&self) -> Vec<[u8; 4]> {
+            self.due_config().map_or_else(
+                || self.accept_selectors.get_or_default(),
+                |c| c.accept_selectors,
+            )
+        }
+
+        fn admin_allowlist(&self) -> CallAllowlist {
+            self.due_config().map_or_else(
+                || self.admin_allowlist.get_or_default(),
+                |c| c.admin_allowlist,
+            )
+        }
+
+        fn change_config(
+            &mut self,
+            proposal_id: ProposalId,
+            old: GovernorConfig,
+            new: GovernorConfig,
+            timestamp: u64,
+        ) {
+            self.store_config(&new);
+            let index = self.param_history_length();
+            self.param_history.insert(
+                index,
+                &ParamChange {
+                    old,
+                    new,
+                    proposal_id,
+                    timestamp,
+                },
+            );
+            self.param_history_length.set(&(index + 1));
+            self.env().emit_event(ConfigChanged {
+                schema_version: EVENTS_VERSION,
+                proposal_id,
+                index,
+            });
+        }
+
+        fn insert_config_change(
+            &mut self,
+            config: GovernorConfig,
+            duration: u64,
+        ) -> Result<ProposalId, GovernorError> {
+            Self::voting_period(duration)?;
+            let caller = self.env().caller();
+            let proposal_id = self.insert_proposal(
+                caller,
+                self.env().account_id(),
+                0,
+                duration,
+                GENERAL_CATEGORY,
+            )?;
+            self.config_changes.insert(proposal_id, &config);
+            Ok(proposal_id)
+        }
+
+        fn check_adapter(&self, adapter: AccountId) -> Result<(), GovernorError> {
+            ensure!(
+                !self
+                    .adapters()
+                    .iter()
+                    .any(|(account, _)| *account == adapter),
+                GovernorError::UnknownAdapter
+            );
+            Ok(())
+        }
+
+        /// Whether the message of an admin proposal is one it may call, see
+        /// `accept_selectors` and `admin_allowlist`.
+        fn check_admin_call(
+            &self,
+            target: AccountId,
+            action: AdminAction,
+            admin_call: &ProposalCall,
+        ) -> Result<(), GovernorError> {
+            let allowed = match action {
+                AdminAction::Accept => {
+                    self.accept_selectors().contains(&admin_call.selector)
+                        && admin_call.input.is_empty()
+                }
+                AdminAction::Call | AdminAction::Release => {
+                    self.admin_allowlist()
+                        .contains(&(target, admin_call.selector))
+                }
+            };
+            ensure!(!allowed, GovernorError::CallNotAllowed);
+            Ok(())
+        }
+
+        fn execute_admin_action(
+            &mut self,
+            target: AccountId,
+            action: AdminAction,
+            admin_call: ProposalCall,
+        ) -> Result<(), GovernorError> {
+            self.check_admin_call(target, action, &admin_call)?;
+            let mut administered = self.administered.get_or_default();
+            let position = administered.iter().position(|account| *account == target);
+            match (action, position) {
+                (AdminAction::Accept, None) => administered.push(target),
+                (AdminAction::Accept, Some(_)) | (AdminAction::Call, Some(_)) => {}
+                (AdminAction::Release, Some(position)) => {
+                    administered.swap_remove(position);
+                }
+                (_, None) => return Err(GovernorError::NotAdministered),
+            }
+            call::invoke(target, admin_call.selector, &admin_call.input, 0)
+                .map_err(|_| GovernorError::CallFailed)?;
+            self.administered.set(&administered);
+            Ok(())
+        }
+
+        fn schedule_config(
+            &mut self,
+            proposal_id: ProposalId,
+            config: GovernorConfig,
+            activates_at: u64,
+        ) {
+            let mut schedule = self.config_schedule.get_or_default();
+            let position = schedule.partition_point(|(other, _)| *other <= activates_at);
+            schedule.insert(position, (activates_at, proposal_id));
+            self.config_schedule.set(&schedule);
+            self.scheduled_configs.insert(
+                proposal_id,
+                &ScheduledConfig {
+                    config,
+                    proposal_id,
+                    activates_at,
+                },
+            );
+        }
+
+        fn adopt_amendment(&mut self, proposal_id: ProposalId, document: [u8; 32]) {
+            let version = self.constitution_versions();
+            self.constitution_history.insert(
+                version,
+                &Amendment {
+                    document,
+                    proposal_id,
+                    adopted_at: self.now(),
+                },
+            );
+            self.constitution_versions.set(&(version + 1));
+            self.env().emit_event(ConstitutionAmended {
+                schema_version: EVENTS_VERSION,
+                proposal_id,
+                version,
+                document,
+            });
+        }
+
+        fn change_council(
+            &mut self,
+            config: &GovernorConfig,
+            member: AccountId,
+            change: CouncilChange,
+        ) -> Result<(), GovernorError> {
+            let mut members = self.council_members.get_or_default();
+            match change {
+                CouncilChange::Elect => {
+                    ensure!(
+                        self.is_council_member(member),
+                        GovernorError::AlreadyOnCouncil
+                    );
+                    let term_end = match config.council_term {
+                        0 => u64::MAX,
+                        term => self.now().saturating_add(term),
+                    };
+                    self.council_terms.insert(member, &term_end);
+                    if !members.contains(&member) {
+                        members.push(member);
+                    }
+                    self.env().emit_event(CouncilElected {
+                        schema_version: EVENTS_VERSION,
+                        member,
+                        term_end,
+                    });
+                }
+                CouncilChange::Recall => {
+                    ensure!(!self.is_council_member(member), GovernorError::NotOnCouncil);
+                    self.council_terms.remove(member);
+                    members.retain(|account| *account != member);
+                    self.env().emit_event(CouncilRecalled {
+                        schema_version: EVENTS_VERSION,
+                        member,
+                    });
+                }
+            }
+            self.council_members.set(&members);
+            Ok(())
+        }
+
+        /// Whether `account` is a configured guardian or a sitting council member.
+        fn is_guardian(&self, account: AccountId) -> bool {
+            self.guardians().contains(&account) || self.is_council_member(account)
+        }
+
+        /// Tier of `account` holding `balance` governance tokens.
+        fn tier_at(
+            &self,
+            policy: &TierPolicy,
+            account: AccountId,
+            balance: Balance,
+        ) -> Tier {
+            let staked = if balance >= policy.partner_stake {
+                Tier::Partner
+            } else if balance >= policy.member_stake {
+                Tier::Member
+            } else {
+                Tier::Observer
+            };
+            self.assigned_tiers
+                .get(account)
+                .map_or(staked, |assigned| assigned.max(staked))
+        }
+
+        /// Fails unless `account`'s stake at `timestamp` reaches the tier `required`
+        /// picks from the policy. Anyone passes while tiers are disabled.
+        fn check_tier(
+            &self,
+            config: &GovernorConfig,
+            account: AccountId,
+            timestamp: u64,
+            required: impl FnOnce(&TierPolicy) -> Tier,
+        ) -> Result<(), GovernorError> {
+            if let Some(policy) = &config.tiers {
+                let (balance, _) = Self::voting_power(config, account, timestamp)?;
+                ensure!(
+                    self.tier_at(policy, account, balance) < required(policy),
+                    GovernorError::TierTooLow
+                );
+            }
+            Ok(())
+        }
+
+        /// Share of the supply (in `math::PRECISION` units) `voter` held at the start
+        /// of the vote on `proposal_id`, the weight of their off-chain ballot.
+        fn ballot_weight(
+            &self,
+            proposal_id: ProposalId,
+            voter: AccountId,
+        ) -> Result<u64, GovernorError> {
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            let (balance, total_supply) =
+                Self::voting_power(&self.settings(), voter, proposal.vote_start)?;
+            Ok(math::ratio(balance, total_supply) as u64)
+        }
+
+        /// Time stakes are read at outside of a vote: the end of the previous block,
+        /// so that tokens moved in within the current one don't count.
+        fn stake_snapshot(&self) -> u64 {
+            self.now().saturating_sub(1)
+        }
+
+        /// Whether `account` may vote at `now`: not kicked and in good standing.
+        fn may_vote(
+            &self,
+            config: &GovernorConfig,
+            account: AccountId,
+            now: u64,
+        ) -> bool {
+            !self.kicked.contains(account)
+                && self.in_good_standing_at(config, account, now)
+        }
+
+        fn in_good_standing_at(
+            &self,
+            config: &GovernorConfig,
+            member: AccountId,
+            now: u64,
+        ) -> bool {
+            config.dues == 0
+                || self
+                    .dues_paid_until
+                    .get(member)
+                    .map_or(false, |paid_until| {
+                        now < paid_until.saturating_add(config.dues_grace_period)
+                    })
+        }
+
+        /// Whether `holder`'s power is auto-delegated in `epoch`. Holders never seen
+        /// voting count as inactive since the first epoch.
+        fn is_inactive(
+            &self,
+            config: &GovernorConfig,
+            holder: AccountId,
+            epoch: u32,
+        ) -> bool {
+            let missed = match self.last_active_epochs.get(holder) {
+                Some(last_active) => epoch.saturating_sub(last_active + 1),
+                None => epoch,
+            };
+            config.auto_delegation_epochs > 0 && missed >= config.auto_delegation_epochs
+        }
+
+        /// Share (in %) of `member`'s balance that counts as weight in `epoch`.
+        /// Members never seen voting aren't penalized.
+        fn activity_factor_at(
+            &self,
+            config: &GovernorConfig,
+            member: AccountId,
+            epoch: u32,
+        ) -> u8 {
+            if config.inactivity_grace_epochs == 0 {
+                return 100
+            }
+            let Some(last_active) = self.last_active_epochs.get(member) else {
+                return 100
+            };
+            let missed = epoch.saturating_sub(last_active + 1);
+            if missed <= config.inactivity_grace_epochs {
+                return 100
+            }
+            let penalty = (missed - config.inactivity_grace_epochs)
+                .saturating_mul(config.inactivity_decay.into());
+            100 - penalty.min(100) as u8
+        }
+
+        /// Counts `voter`'s ballot on `proposal` towards their streak and returns
+        /// its length.
+        fn record_participation(
+            &mut self,
+            config: &GovernorConfig,
+            voter: AccountId,
+            proposal: &Proposal,
+        ) -> u32 {
+            if config.epoch_length == 0 {
+                return 0
+            }
+            let epoch = (proposal.vote_start / config.epoch_length) as u32;
+            let mut streak = self.voting_streak(voter);
+            if epoch < streak.epoch {
+                return streak.length
+            }
+            if epoch > streak.epoch {
+                streak.epoch = epoch;
+                streak.ballots = 0;
+            }
+            streak.ballots += 1;
+            let opened = self.epoch_proposals.get(epoch).unwrap_or_default();
+            if streak.ballots >= opened && streak.completed_epoch != Some(epoch) {
+                streak.length = match streak.completed_epoch {
+                    Some(completed) if completed + 1 == epoch => streak.length + 1,
+                    _ => 1,
+                };
+                streak.completed_epoch = Some(epoch);
+                streak.longest = streak.longest.max(streak.length);
+            }
+            self.voting_streaks.insert(voter, &streak);
+            streak.length
+        }
+
+        /// Records that `account` has voted on `proposal_id` and returns the receipt
+        /// bucket it went into.
+        fn insert_receipt(&mut self, proposal_id: ProposalId, account: AccountId) -> u8 {
+            let bucket = Self::receipt_bucket(&account);
+            let mut receipts =
+                self.receipts.get((proposal_id, bucket)).unwrap_or_default();
+            if let Err(position) = receipts.binary_search(&account) {
+                receipts.insert(position, account);
+                self.receipts.insert((proposal_id, bucket), &receipts);
+            }
+            bucket
+        }
+
+        fn cast_vote(
+            &mut self,
+            config: &GovernorConfig,
+            voter: AccountId,
+            proposal_id: ProposalId,
+            vote: VoteType,
+        ) -> Result<(), GovernorError> {
+            self.cast_ballot(config, voter, proposal_id, Choice::Vote(vote), Vec::new())
+        }
+
+        /// Casts `voter`'s ballot, also counting the power of the `inactive`
+        /// holders auto-delegated to them.
+        fn cast_ballot(
+            &mut self,
+            config: &GovernorConfig,
+            voter: AccountId,
+            proposal_id: ProposalId,
+            choice: Choice,
+            inactive: Vec<AccountId>,
+        ) -> Result<(), GovernorError> {
+            let proposal = self
+                .proposals
+                .get(proposal_id)
+                .ok_or(GovernorError::ProposalNotFound)?;
+            ensure!(proposal.executed, GovernorError::ProposalAlreadyExecuted);
+            ensure!(
+                self.now() < proposal.vote_start,
+                GovernorError::VotingNotStarted
+            );
+            ensure!(
+                self.anchored_tallies.contains(proposal_id),
+                GovernorError::VotingIsOffChain
+            );
+            ensure!(
+                self.now() >= proposal.vote_end,
+                GovernorError::VotePeriodEnded {
+                    ended_at: proposal.vote_end,
+                }
+            );
+            let category = self.proposal_category(proposal_id);
+            ensure!(
+                self.next_delegate_at(voter, category, proposal.vote_start)
+                    .is_some(),
+                GovernorError::PowerDelegated
+            );
+            ensure!(
+                self.has_voted(proposal_id, voter),
+                GovernorError::AlreadyVoted
+            );
+            let now = self.now();
+            ensure!(self.kicked.contains(voter), GovernorError::Kicked);
+            ensure!(
+                config.verified_voting && !Self::is_verified(config, voter),
+                GovernorError::NotVerified
+            );
+            ensure!(
+                !self.in_good_standing_at(config, voter, now),
+                GovernorError::DuesLapsed
+            );
+            // The power of lapsed or kicked members is suspended, even when delegated.
+            let mut counted =
+                self.voting_accounts(proposal_id, category, voter, proposal.vote_start);
+            counted.retain(|account| self.may_vote(config, *account, now));
+            let epoch = Self::current_epoch(config, now);
+            let active = counted.len();
+            for holder in inactive {
+                if !self.may_vote(config, holder, now) {
+                    continue
+                }
+                ensure!(
+                    !epoch.map_or(false, |epoch| self.is_inactive(config, holder, epoch))
+                        || self
+                            .next_delegate_at(holder, category, proposal.vote_start)
+                            .is_some(),
+                    GovernorError::NotInactive
+                );
+                ensure!(
+                    self.has_voted(proposal_id, holder),
+                    GovernorError::AlreadyVoted
+                );
+                if !counted.contains(&holder) {
+                    counted.push(holder);
+                }
+            }
+            let (mut balance, mut total_supply) = (0, 0);
+            for account in &counted {
+                let (account_balance, supply) =
+                    Self::voting_power(config, *account, proposal.vote_start)?;
+                let account_balance = match &config.tiers {
+                    Some(policy) => {
+                        let tier = self.tier_at(policy, *account, account_balance);
+                        let weight = policy.weights[tier as usize].min(100);
+                        math::mul_div(account_balance, weight.into(), 100)
+                            .unwrap_or_default()
+                    }
+                    None => account_balance,
+                };
+                balance += match epoch {
+                    Some(epoch) => {
+                        let factor = self.activity_factor_at(config, *account, epoch);
+                        math::mul_div(account_balance, factor.into(), 100)
+                            .unwrap_or_default()
+                    }
+                    None => account_balance,
+                };
+                total_supply = supply;
+            }
+            let mut weight = math::ratio(balance, total_supply);
+            if config.max_vote_weight > 0 {
+                weight = weight.min(math::percent(config.max_vote_weight));
+            }
+            let weight = weight as u64;
+
+            let mut proposal_vote =
+                self.proposal_votes.get(proposal_id).unwrap_or_default();
+            let mut poll = self.polls.get(proposal_id);
+            let mut allocation = self.allocations.get(proposal_id);
+            match (choice, &mut poll, &mut allocation) {
+                (Choice::Vote(VoteType::For), None, None) => {
+                    proposal_vote.for_votes += weight
+                }
+                (Choice::Vote(VoteType::Against), None, None) => {
+                    proposal_vote.against_vote += weight
+                }
+                (Choice::Option(option), Some(poll), _) => {
+                    *poll
+                        .tallies
+                        .get_mut(usize::from(option))
+                        .ok_or(GovernorError::InvalidPollOptions)? += weight
+                }
+                (Choice::Split(shares), _, Some(allocation)) => {
+                    Self::add_split(&mut allocation.tallies, &shares, weight)?
+                }
+                (_, Some(_), _) => return Err(GovernorError::IsPoll),
+                (_, _, Some(_)) => return Err(GovernorError::IsAllocation),
+                (Choice::Option(_), None, None) => return Err(GovernorError::NotPoll),
+                (Choice::Split(_), None, None) => {
+                    return Err(GovernorError::NotAllocation)
+                }
+            }
+            invariant!(
+                [
+                    proposal_vote.for_votes + proposal_vote.against_vote,
+                    poll.as_ref().map_or(0, |poll| poll.tallies.iter().sum()),
+                    allocation
+                        .as_ref()
+                        .map_or(0, |allocation| allocation.tallies.iter().sum()),
+                ]
+                .iter()
+                .map(|tally| *tally as u128)
+                .sum::<u128>()
+                    <= math::PRECISION,
+                "tally of proposal {} exceeds the token supply",
+                proposal_id.0
+            );
+
+            // Every check and the weight lookup above can fail; only write once
+            // they have all passed.
+            for (position, account) in counted.into_iter().enumerate() {
+                let bucket = self.insert_receipt(proposal_id, account);
+                proposal_vote.receipt_buckets |= 1 << bucket;
+                // Auto-delegated power doesn't make its holder active.
+                if let (Some(epoch), true) = (epoch, position < active) {
+                    self.last_active_epochs.insert(account, &epoch);
+                }
+            }
+            self.proposal_votes.insert(proposal_id, &proposal_vote);
+            if let Some(poll) = poll {
+                self.polls.insert(proposal_id, &poll);
+            }
+            if let Some(allocation) = allocation {
+                self.allocations.insert(proposal_id, &allocation);
+            }
+            self.weight_cast
+                .set(&(self.weight_cast.get_or_default() + u128::from(weight)));
+            if self.voters.insert(voter, &()).is_none() {
+                self.voter_count
+                    .set(&(self.voter_count.get_or_default() + 1));
+            }
+            if weight > 0 && weight >= config.min_reward_weight {
+                let budget = self.reimbursement_budget();
+                let amount = self
+                    .env()
+                    .weight_to_fee(REIMBURSED_VOTE_GAS)
+                    .min(config.vote_reimbursement)
+                    .min(budget);
+                if amount > 0 {
+                    self.reimbursement_budget.set(&(budget - amount));
+                    self.reimbursements.insert((proposal_id, voter), &amount);
+                }
+            }
+            let streak = self.record_participation(config, voter, &proposal);
+            if weight > 0
+                && weight >= config.min_reward_weight
+                && config.participation_reward > 0
+                && (!config.verified_rewards || Self::is_verified(config, voter))
+            {
+                let bonus = (streak.saturating_mul(config.streak_bonus.into()))
+                    .min(config.max_streak_bonus.into());
+                let reward = config.participation_reward
+                    + math::mul_div(config.participation_reward, bonus.into(), 100)
+                        .unwrap_or_default();
+                self.participation_rewards
+                    .insert((proposal_id, voter), &reward);
+            }
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::testing::{
+            set_token_balance,
+            ProposalScenario,
+        };
+
+        const ONE_MINUTE: u64 = 60;
+
+        fn create_contract(initial_balance: Balance) -> Governor {
+            let accounts: ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> =
+                default_accounts();
+            set_sender(accounts.alice);
+            set_balance(contract_id(), initial_balance);
+            Governor::new(AccountId::from([0x01; 32]), 50)
+        }
+
+        /// Moves the clock to the end of the vote on `proposal_id`.
+        fn end_vote(governor: &Governor, proposal_id: ProposalId) {
+            set_block_timestamp(governor.proposals.get(proposal_id).unwrap().vote_end);
+        }
+
+        const DEFI: Category = 1;
+        const OPERATIONS: Category = 2;
+
+        /// Like `create_contract`, with a `DEFI` and `OPERATIONS` category.
+        fn create_categorized_contract(initial_balance: Balance) -> Governor {
+            let mut governor = create_contract(initial_balance);
+            let mut config = governor.config();
+            config.categories =
+                ["General", "DeFi", "Operations"].map(String::from).to_vec();
+            governor.store_config(&config);
+            governor
+        }
+
+        fn contract_id() -> AccountId {
+            ink::env::test::callee::<ink::env::DefaultEnvironment>()
+        }
+
+        fn default_accounts(
+        ) -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
+            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        }
+
+        fn set_sender(sender: AccountId) {
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(sender);
+        }
+
+        fn set_block_timestamp(timestamp: u64) {
+            ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(
+                timestamp,
+            );
+        }
+
+        fn set_balance(account_id: AccountId, balance: Balance) {
+            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
+                account_id, balance,
+            )
+        }
+
+        fn live_proposal_ids(governor: &Governor) -> Vec<ProposalId> {
+            (0..governor.proposal_count)
+                .filter_map(|position| governor.proposal_ids.get(position))
+                .collect()
+        }
+
+        #[ink::test]
+        fn propose_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            assert_eq!(
+                governor.propose(accounts.django, 0, ONE_MINUTE),
+                Err(GovernorError::AmountShouldNotBeZero)
+            );
+            assert_eq!(
+                governor.propose(accounts.django, 100, 0),
+                Err(GovernorError::DurationError)
+            );
+            assert_eq!(
+                governor.propose(accounts.django, 100, MIN_VOTING_PERIOD - 1),
+                Err(GovernorError::DurationError)
+            );
+            assert_eq!(
+                governor.propose(accounts.django, 100, MAX_VOTING_PERIOD + 1),
+                Err(GovernorError::DurationError)
+            );
+            let result = governor.propose(accounts.django, 100, ONE_MINUTE);
+            assert_eq!(result, Ok(()));
+            let proposal = governor.get_proposal(ProposalId(0)).unwrap();
+            let now = governor.now();
+            assert_eq!(
+                proposal,
+                Proposal {
+                    to: accounts.django,
+                    amount: 100,
+                    vote_start: 0,
+                    vote_end: now + ONE_MINUTE * 1000,
+                    executed: false,
+                }
+            );
+            assert_eq!(proposal.vote_end() - proposal.vote_start(), 60_000);
+            assert_eq!(governor.next_proposal_id(), ProposalId(1));
+        }
+
+        #[ink::test]
+        fn voting_closes_at_vote_end() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            set_block_timestamp(ONE_MINUTE * 1000 - 1);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            set_block_timestamp(ONE_MINUTE * 1000);
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::Against),
+                Err(GovernorError::VotePeriodEnded {
+                    ended_at: ONE_MINUTE * 1000,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn quorum_not_reached() {
+            let mut governor = create_contract(1000);
+            let result = governor.propose(AccountId::from([0x02; 32]), 100, ONE_MINUTE);
+            assert_eq!(result, Ok(()));
+            let voting = governor.vote(ProposalId(0), VoteType::Against);
+            assert_eq!(voting, Ok(()));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::VotePeriodNotEnded)
+            );
+            end_vote(&governor, ProposalId(0));
+            let execute = governor.execute(ProposalId(0));
+            assert_eq!(
+                execute,
+                Err(GovernorError::QuorumNotReached {
+                    needed: 500_000_000_000,
+                    got: 0,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn insufficient_treasury() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(governor.propose(accounts.django, 1500, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::InsufficientTreasury {
+                    requested: 1500,
+                    available: 1000,
+                })
+            );
+            assert!(!governor.get_proposal(ProposalId(0)).unwrap().executed);
+        }
+
+        #[ink::test]
+        fn simulate_execute_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            set_token_balance(accounts.bob, 300);
+            assert_eq!(
+                governor.simulate_execute(ProposalId(0)),
+                Err(GovernorError::ProposalNotFound)
+            );
+            for amount in [1500, 100, 1500] {
+                assert_eq!(
+                    governor.propose(accounts.django, amount, ONE_MINUTE),
+                    Ok(())
+                );
+            }
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(2), VoteType::Against), Ok(()));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            assert_eq!(
+                governor.simulate_execute(ProposalId(1)).unwrap().error,
+                Some(GovernorError::VotePeriodNotEnded)
+            );
+
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.simulate_execute(ProposalId(0)),
+                Ok(ExecutionReport {
+                    error: Some(GovernorError::QuorumNotReached {
+                        needed: 500_000_000_000,
+                        got: 250_000_000_000,
+                    }),
+                    quorum_reached: false,
+                    passed: false,
+                    unlocked: true,
+                    call_allowed: true,
+                    funded: false,
+                })
+            );
+            let report = governor.simulate_execute(ProposalId(2)).unwrap();
+            assert_eq!(report.error, Some(GovernorError::ProposalNotAccepted));
+            assert!(report.quorum_reached && !report.passed);
+
+            let passing = ExecutionReport {
+                error: None,
+                quorum_reached: true,
+                passed: true,
+                unlocked: true,
+                call_allowed: true,
+                funded: true,
+            };
+            assert_eq!(governor.simulate_execute(ProposalId(1)), Ok(passing));
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+            assert_eq!(
+                governor.simulate_execute(ProposalId(1)).unwrap().error,
+                Some(GovernorError::ProposalAlreadyExecuted)
+            );
+        }
+
+        #[ink::test]
+        fn proposal_scenario_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            let proposal_id = ProposalScenario::new(accounts.django, 100, ONE_MINUTE)
+                .vote(accounts.alice, 600, VoteType::For)
+                .vote(accounts.bob, 400, VoteType::Against)
+                .run(&mut governor);
+            assert_eq!(proposal_id, Ok(ProposalId(0)));
+            assert!(governor.has_voted(ProposalId(0), accounts.bob));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+        }
+
+        #[ink::test]
+        fn execute_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            assert_eq!(
+                governor.config(),
+                GovernorConfig {
+                    governance_token: AccountId::from([0x01; 32]),
+                    quorum: 50,
+                    vote_reimbursement: 0,
+                    trusted_forwarder: None,
+                    categories: Vec::new(),
+                    guardians: Vec::new(),
+                    fast_track_approval: 67,
+                    objection_window: 0,
+                    objection_threshold: 10,
+                    quorum_decay_period: 0,
+                    min_quorum: 50,
+                    participation_reward: 0,
+                    epoch_length: 0,
+                    streak_bonus: 0,
+                    max_streak_bonus: 0,
+                    inactivity_grace_epochs: 0,
+                    inactivity_decay: 0,
+                    default_delegates: Vec::new(),
+                    auto_delegation_epochs: 0,
+                    call_allowlist: Vec::new(),
+                    identity_gate: None,
+                    verified_voting: false,
+                    verified_rewards: false,
+                    retro_badge_holders: Vec::new(),
+                    donation_match: 0,
+                    causes: Vec::new(),
+                    revenue_sources: Vec::new(),
+                    revenue_split: (0, 0),
+                    dues: 0,
+                    dues_period: 0,
+                    dues_grace_period: 0,
+                    tiers: None,
+                    membership_shares: 0,
+                    council_term: 0,
+                    review_panel: 0,
+                    review_rotation: ReviewRotation::RoundRobin,
+                    weight_source: None,
+                    twab_window: 0,
+                    max_vote_weight: 0,
+                    amendment_approval: 67,
+                    amendment_duration: AMENDMENT_DURATION,
+                    price_oracle: None,
+                    max_start_delay: 0,
+                    max_requested_quorum: 0,
+                    max_requested_approval: 0,
+                    voting_periods: Vec::new(),
+                    cycle: None,
+                    adapters: Vec::new(),
+                    min_reward_weight: 0,
+                    accept_selectors: Vec::new(),
+                    admin_allowlist: Vec::new(),
+                    deposit_lockup: 0,
+                    settlement_period: 0,
+                    liquidity_buffer: 0,
+                    deposit_windows: None,
+                }
+            );
+            set_token_balance(accounts.alice, 600);
+            set_token_balance(accounts.bob, 400);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::For),
+                Err(GovernorError::AlreadyVoted)
+            );
+            let tally = governor.proposal_votes.get(ProposalId(0)).unwrap();
+            assert_eq!((tally.for_votes, tally.against_vote), (600_000_000_000, 0));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            assert!(governor.get_proposal(ProposalId(0)).unwrap().executed);
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::ProposalAlreadyExecuted)
+            );
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::Against),
+                Err(GovernorError::ProposalAlreadyExecuted)
+            );
+        }
+
+        #[ink::test]
+        fn propose_many_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let input = |to, amount| {
+                ProposalInput {
+                    to,
+                    amount,
+                    duration: ONE_MINUTE,
+                }
+            };
+            assert_eq!(
+                governor.propose_many(vec![
+                    input(accounts.django, 100),
+                    input(accounts.eve, 0)
+                ]),
+                Err(GovernorError::AmountShouldNotBeZero)
+            );
+            assert_eq!(governor.next_proposal_id(), ProposalId(0));
+            assert_eq!(
+                governor.propose_many(vec![
+                    input(accounts.django, 100),
+                    input(accounts.eve, 200)
+                ]),
+                Ok(vec![ProposalId(0), ProposalId(1)])
+            );
+            assert_eq!(
+                governor.get_proposal(ProposalId(1)).unwrap().to,
+                accounts.eve
+            );
+            assert_eq!(governor.next_proposal_id(), ProposalId(2));
+        }
+
+        #[ink::test]
+        fn receipts_are_bucketed() {
+            let mut governor = create_contract(100_000);
+            assert_eq!(
+                governor.propose(AccountId::from([0x02; 32]), 100, ONE_MINUTE),
+                Ok(())
+            );
+            // Both voters land in the same bucket and share one storage cell.
+            let first = AccountId::from([0x05; 32]);
+            let second = AccountId::from([0x05 + RECEIPT_BUCKETS; 32]);
+            for voter in [second, first] {
+                set_sender(voter);
+                assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            }
+            assert_eq!(
+                governor.receipts.get((ProposalId(0), 0x05)),
+                Some(vec![first, second])
+            );
+            assert_eq!(
+                governor
+                    .proposal_votes
+                    .get(ProposalId(0))
+                    .unwrap()
+                    .receipt_buckets,
+                1 << 0x05
+            );
+            assert!(governor.has_voted(ProposalId(0), first));
+            assert!(!governor.has_voted(ProposalId(0), AccountId::from([0x06; 32])));
+            set_sender(first);
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::Against),
+                Err(GovernorError::AlreadyVoted)
+            );
+        }
+
+        #[ink::test]
+        fn vote_many_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            set_token_balance(accounts.alice, 600);
+            set_token_balance(accounts.bob, 400);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.propose(accounts.eve, 200, ONE_MINUTE), Ok(()));
+            assert_eq!(
+                governor.vote_many(vec![
+                    (ProposalId(0), VoteType::For),
+                    (ProposalId(1), VoteType::Against),
+                    (ProposalId(0), VoteType::Against),
+                    (ProposalId(2), VoteType::For),
+                ]),
+                vec![
+                    Ok(()),
+                    Ok(()),
+                    Err(GovernorError::AlreadyVoted),
+                    Err(GovernorError::ProposalNotFound),
+                ]
+            );
+            assert_eq!(
+                governor
+                    .proposal_votes
+                    .get(ProposalId(0))
+                    .unwrap()
+                    .for_votes,
+                600_000_000_000
+            );
+            assert_eq!(
+                governor
+                    .proposal_votes
+                    .get(ProposalId(1))
+                    .unwrap()
+                    .against_vote,
+                600_000_000_000
+            );
+        }
+
+        #[ink::test]
+        fn vote_weight_is_share_of_supply() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 1);
+            set_token_balance(accounts.bob, 2);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            let tally = governor.proposal_votes.get(ProposalId(0)).unwrap();
+            assert_eq!(
+                (tally.for_votes, tally.against_vote),
+                (333_333_333_333, 666_666_666_666)
+            );
+            // A holder without tokens can vote but adds no weight.
+            set_sender(accounts.charlie);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(
+                governor
+                    .proposal_votes
+                    .get(ProposalId(0))
+                    .unwrap()
+                    .for_votes,
+                333_333_333_333
+            );
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::ProposalNotAccepted)
+            );
+        }
+
+        #[ink::test]
+        fn scheduled_proposals_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            set_block_timestamp(1000);
+            assert_eq!(
+                governor.propose_at(accounts.django, 100, ONE_MINUTE, 2000),
+                Err(GovernorError::InvalidStartTime)
+            );
+            let mut config = governor.config();
+            config.max_start_delay = 10_000;
+            governor.store_config(&config);
+            for vote_start in [999, 11_001] {
+                assert_eq!(
+                    governor.propose_at(accounts.django, 100, ONE_MINUTE, vote_start),
+                    Err(GovernorError::InvalidStartTime)
+                );
+            }
+            assert_eq!(
+                governor.propose_at(accounts.django, 100, ONE_MINUTE, 11_000),
+                Ok(())
+            );
+            let proposal = governor.get_proposal(ProposalId(0)).unwrap();
+            assert_eq!(proposal.vote_start(), 11_000);
+            assert_eq!(proposal.vote_end(), 11_000 + ONE_MINUTE * 1000);
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::For),
+                Err(GovernorError::VotingNotStarted)
+            );
+            set_block_timestamp(11_000);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+        }
+
+        #[ink::test]
+        fn committed_proposals_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let proposal = || {
+                CommittedProposal::Transfer(ProposalInput {
+                    to: accounts.django,
+                    amount: 100,
+                    duration: ONE_MINUTE,
+                })
+            };
+            let commitment =
+                governor.proposal_commitment(accounts.alice, proposal(), [7; 32]);
+            assert_eq!(governor.commit_proposal(commitment), Ok(()));
+            assert_eq!(
+                governor.commit_proposal(commitment),
+                Err(GovernorError::CommitmentExists)
+            );
+            // Only the committer knows the salt, and only they can reveal.
+            assert_eq!(
+                governor.reveal_proposal(proposal(), [8; 32]),
+                Err(GovernorError::UnknownCommitment)
+            );
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.reveal_proposal(proposal(), [7; 32]),
+                Err(GovernorError::UnknownCommitment)
+            );
+            set_sender(accounts.alice);
+            assert_eq!(governor.reveal_proposal(proposal(), [7; 32]), Ok(()));
+            assert_eq!(
+                governor
+                    .get_proposal(ProposalId(0))
+                    .map(|proposal| proposal.to),
+                Ok(accounts.django)
+            );
+            assert_eq!(
+                governor.reveal_proposal(proposal(), [7; 32]),
+                Err(GovernorError::UnknownCommitment)
+            );
+
+            assert_eq!(governor.commit_proposal(commitment), Ok(()));
+            set_block_timestamp(REVEAL_WINDOW);
+            assert_eq!(
+                governor.reveal_proposal(proposal(), [7; 32]),
+                Err(GovernorError::RevealWindowClosed)
+            );
+        }
+
+        #[ink::test]
+        fn prune_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            set_block_timestamp(1000);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(
+                governor.prune(vec![ProposalId(0)]),
+                Err(GovernorError::ProposalNotFinalized)
+            );
+            assert_eq!(
+                governor.prune(vec![ProposalId(1)]),
+                Err(GovernorError::ProposalNotFound)
+            );
+            // The challenge window runs from the end of the vote.
+            let finalized_at = 1000 + ONE_MINUTE * 1000 + PRUNE_CHALLENGE_WINDOW;
+            set_block_timestamp(finalized_at - 1);
+            assert_eq!(
+                governor.prune(vec![ProposalId(0)]),
+                Err(GovernorError::ProposalNotFinalized)
+            );
+            set_block_timestamp(finalized_at);
+            assert_eq!(governor.prune(vec![ProposalId(0)]), Ok(()));
+            assert_eq!(
+                governor.get_proposal(ProposalId(0)),
+                Err(GovernorError::ProposalNotFound)
+            );
+            assert_eq!(governor.proposal_votes.get(ProposalId(0)), None);
+            assert!(!governor.has_voted(ProposalId(0), accounts.alice));
+            assert_eq!(ink::env::test::recorded_events().count(), 1);
+            assert_eq!(
+                governor.archived_proposal(ProposalId(0)),
+                Some(ProposalSummary {
+                    executed: false,
+                    for_votes: 0,
+                    against_vote: 0,
+                    amount: 100,
+                    recipient_hash: merkle::leaf(&accounts.django),
+                })
+            );
+
+            // Passed proposals are kept until executed.
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            set_block_timestamp(
+                finalized_at + ONE_MINUTE * 1000 + PRUNE_CHALLENGE_WINDOW,
+            );
+            assert_eq!(
+                governor.prune(vec![ProposalId(1)]),
+                Err(GovernorError::ProposalNotFinalized)
+            );
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+            assert_eq!(governor.prune(vec![ProposalId(1)]), Ok(()));
+        }
+
+        #[cfg(feature = "views")]
+        #[ink::test]
+        fn stats_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 300);
+            set_token_balance(accounts.bob, 100);
+            set_token_balance(accounts.charlie, 100);
+            for _ in 0..3 {
+                assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            }
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::Against), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            set_block_timestamp(ONE_MINUTE * 1000 + PRUNE_CHALLENGE_WINDOW);
+            assert_eq!(governor.prune(vec![ProposalId(0), ProposalId(1)]), Ok(()));
+            assert_eq!(
+                governor.stats(),
+                GovernanceStats {
+                    proposals_created: 3,
+                    proposals_executed: 1,
+                    proposals_defeated: 1,
+                    // 60% + 20% + 60% over 3 proposals.
+                    average_turnout: (math::percent(140) / 3) as u64,
+                    unique_voters: 2,
+                    treasury: LedgerTotals {
+                        income: 0,
+                        expense: 100,
+                    },
+                }
+            );
+        }
+
+        #[cfg(feature = "test-utils")]
+        #[ink::test]
+        fn advance_time_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            set_block_timestamp(1000);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(
+                governor.prune(vec![ProposalId(0)]),
+                Err(GovernorError::ProposalNotFinalized)
+            );
+            governor.advance_time(ONE_MINUTE * 1000);
+            governor.advance_time(PRUNE_CHALLENGE_WINDOW);
+            assert_eq!(
+                governor.time_offset(),
+                ONE_MINUTE * 1000 + PRUNE_CHALLENGE_WINDOW
+            );
+            assert_eq!(governor.now(), 1000 + governor.time_offset());
+            assert_eq!(governor.prune(vec![ProposalId(0)]), Ok(()));
+        }
+
+        #[ink::test]
+        fn proposal_index_tracks_live_proposals() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            for _ in 0..3 {
+                assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            }
+            assert_eq!(governor.proposal_count(), 3);
+            assert_eq!(
+                live_proposal_ids(&governor),
+                vec![ProposalId(0), ProposalId(1), ProposalId(2)]
+            );
+            set_block_timestamp(ONE_MINUTE * 1000 + PRUNE_CHALLENGE_WINDOW);
+            assert_eq!(governor.prune(vec![ProposalId(0)]), Ok(()));
+            assert_eq!(governor.proposal_count(), 2);
+            assert_eq!(
+                live_proposal_ids(&governor),
+                vec![ProposalId(2), ProposalId(1)]
+            );
+            assert_eq!(governor.prune(vec![ProposalId(1)]), Ok(()));
+            assert_eq!(live_proposal_ids(&governor), vec![ProposalId(2)]);
+        }
+
+        #[cfg(feature = "views")]
+        #[ink::test]
+        fn views_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            for _ in 0..3 {
+                assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            }
+            assert_eq!(
+                governor.proposal_ids(0, 10),
+                vec![ProposalId(0), ProposalId(1), ProposalId(2)]
+            );
+            assert_eq!(governor.proposal_ids(1, 1), vec![ProposalId(1)]);
+            assert_eq!(governor.proposal_ids(3, 1), vec![]);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(25);
+            assert_eq!(governor.collect_fee(), Ok(()));
+            assert_eq!(governor.total_fees_collected(), 25);
+        }
+
+        #[cfg(feature = "invariants")]
+        #[ink::test]
+        #[should_panic(expected = "exceeds the token supply")]
+        fn invariants_catch_inflated_tallies() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 1);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            governor.proposal_votes.insert(
+                ProposalId(0),
+                &ProposalVote {
+                    for_votes: math::PRECISION as u64,
+                    ..Default::default()
+                },
+            );
+            let _ = governor.vote(ProposalId(0), VoteType::For);
+        }
+
+        #[ink::test]
+        fn anchored_tally_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 550);
+            set_token_balance(accounts.bob, 450);
+            assert_eq!(
+                governor.propose_anchored(
+                    accounts.django,
+                    100,
+                    ONE_MINUTE,
+                    accounts.charlie
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::For),
+                Err(GovernorError::VotingIsOffChain)
+            );
+            let alice = Ballot {
+                voter: accounts.alice,
+                vote: VoteType::For,
+                weight: 300_000_000_000,
+            };
+            let bob = Ballot {
+                voter: accounts.bob,
+                vote: VoteType::Against,
+                weight: 400_000_000_000,
+            };
+            let alice_leaf = merkle::leaf(&(ProposalId(0), &alice));
+            let bob_leaf = merkle::leaf(&(ProposalId(0), &bob));
+            let root = merkle::parent(&alice_leaf, &bob_leaf);
+
+            assert_eq!(
+                governor.anchor_tally(
+                    ProposalId(0),
+                    root,
+                    300_000_000_000,
+                    400_000_000_000
+                ),
+                Err(GovernorError::NotTallyAggregator)
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(
+                governor.anchor_tally(
+                    ProposalId(0),
+                    root,
+                    300_000_000_000,
+                    400_000_000_000
+                ),
+                Err(GovernorError::VotePeriodNotEnded)
+            );
+            set_block_timestamp(ONE_MINUTE * 1000 + 1);
+            assert_eq!(
+                governor.anchor_tally(ProposalId(0), root, math::PRECISION as u64, 1),
+                Err(GovernorError::InvalidTally)
+            );
+            assert_eq!(
+                governor.anchor_tally(
+                    ProposalId(0),
+                    root,
+                    300_000_000_000,
+                    400_000_000_000
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                governor.anchor_tally(
+                    ProposalId(0),
+                    root,
+                    300_000_000_000,
+                    400_000_000_000
+                ),
+                Err(GovernorError::TallyAlreadyAnchored)
+            );
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::ChallengeWindowOpen)
+            );
+
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.dispute_ballot(ProposalId(0), alice.clone(), vec![bob_leaf]),
+                Err(GovernorError::NotBallotVoter)
+            );
+            assert_eq!(
+                governor.dispute_ballot(ProposalId(0), bob.clone(), vec![]),
+                Err(GovernorError::InvalidProof)
+            );
+            // Bob's ballot was anchored with less than his 450 tokens.
+            assert_eq!(
+                governor.dispute_ballot(ProposalId(0), bob.clone(), vec![alice_leaf]),
+                Ok(())
+            );
+            assert_eq!(
+                governor.dispute_ballot(ProposalId(0), bob.clone(), vec![alice_leaf]),
+                Err(GovernorError::AlreadyDisputed)
+            );
+
+            // Eve didn't vote, so she has no ballot to prove.
+            set_sender(accounts.eve);
+            let eve = Ballot {
+                voter: accounts.eve,
+                vote: VoteType::Against,
+                weight: 0,
+            };
+            assert_eq!(
+                governor.dispute_ballot(ProposalId(0), eve, vec![alice_leaf]),
+                Err(GovernorError::InvalidProof)
+            );
+            // Alice's ballot was anchored with less than her 550 tokens.
+            set_sender(accounts.alice);
+            assert_eq!(
+                governor.dispute_ballot(ProposalId(0), alice.clone(), vec![bob_leaf]),
+                Ok(())
+            );
+            let tally = governor.anchored_tally(ProposalId(0)).unwrap();
+            assert_eq!(
+                (tally.for_votes, tally.against_vote),
+                (550_000_000_000, 450_000_000_000)
+            );
+
+            set_block_timestamp(ONE_MINUTE * 1000 + 1 + TALLY_CHALLENGE_WINDOW);
+            set_sender(accounts.alice);
+            assert_eq!(
+                governor.dispute_ballot(ProposalId(0), alice, vec![bob_leaf]),
+                Err(GovernorError::ChallengeWindowClosed)
+            );
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+        }
+
+        #[ink::test]
+        fn vote_reimbursement_works() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_balance(contract_id(), 1000);
+            let mut governor = Governor::with_config(GovernorConfig {
+                governance_token: AccountId::from([0x01; 32]),
+                quorum: 50,
+                vote_reimbursement: 10,
+                trusted_forwarder: None,
+                categories: Vec::new(),
+                guardians: Vec::new(),
+                fast_track_approval: 67,
+                objection_window: 0,
+                objection_threshold: 10,
+                quorum_decay_period: 0,
+                min_quorum: 50,
+                participation_reward: 0,
+                epoch_length: 0,
+                streak_bonus: 0,
+                max_streak_bonus: 0,
+                inactivity_grace_epochs: 0,
+                inactivity_decay: 0,
+                default_delegates: Vec::new(),
+                auto_delegation_epochs: 0,
+                call_allowlist: Vec::new(),
+                identity_gate: None,
+                verified_voting: false,
+                verified_rewards: false,
+                retro_badge_holders: Vec::new(),
+                donation_match: 0,
+                causes: Vec::new(),
+                revenue_sources: Vec::new(),
+                revenue_split: (0, 0),
+                dues: 0,
+                dues_period: 0,
+                dues_grace_period: 0,
+                tiers: None,
+                membership_shares: 0,
+                council_term: 0,
+                review_panel: 0,
+                review_rotation: ReviewRotation::RoundRobin,
+                weight_source: None,
+                twab_window: 0,
+                max_vote_weight: 0,
+                amendment_approval: 67,
+                amendment_duration: AMENDMENT_DURATION,
+                price_oracle: None,
+                max_start_delay: 0,
+                max_requested_quorum: 0,
+                max_requested_approval: 0,
+                voting_periods: Vec::new(),
+                cycle: None,
+                adapters: Vec::new(),
+                min_reward_weight: 0,
+                accept_selectors: Vec::new(),
+                admin_allowlist: Vec::new(),
+                deposit_lockup: 0,
+                settlement_period: 0,
+                liquidity_buffer: 0,
+                deposit_windows: None,
+            });
+            set_token_balance(accounts.bob, 100);
+            set_token_balance(accounts.charlie, 1);
+            for _ in 0..3 {
+                assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            }
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(15);
+            assert_eq!(governor.fund_reimbursements(), Ok(()));
+            assert_eq!(governor.reserved_balance(), 15);
+            set_sender(accounts.bob);
+            set_balance(accounts.bob, 0);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            // The budget only covers part of the second refund, and none of the
+            // third.
+            assert_eq!(governor.vote(ProposalId(1), VoteType::Against), Ok(()));
+            assert_eq!(governor.vote(ProposalId(2), VoteType::Against), Ok(()));
+            assert_eq!(governor.reimbursement(ProposalId(0), accounts.bob), 10);
+            assert_eq!(governor.reimbursement(ProposalId(1), accounts.bob), 5);
+            assert_eq!(governor.reimbursement(ProposalId(2), accounts.bob), 0);
+            assert_eq!(governor.reimbursement_budget(), 0);
+            assert_eq!(
+                governor.claim_reimbursements(vec![
+                    ProposalId(0),
+                    ProposalId(1),
+                    ProposalId(2)
+                ]),
+                Ok(15)
+            );
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    accounts.bob
+                ),
+                Ok(15)
+            );
+            assert_eq!(governor.reserved_balance(), 0);
+            assert_eq!(
+                governor.claim_reimbursements(vec![ProposalId(0), ProposalId(1)]),
+                Err(GovernorError::NothingToClaim)
+            );
+            // Nor are ballots below the minimum weight, however many accounts
+            // cast them.
+            let mut config = governor.config();
+            config.min_reward_weight = math::percent(1) as u64;
+            governor.store_config(&config);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(15);
+            assert_eq!(governor.fund_reimbursements(), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(governor.reimbursement(ProposalId(0), accounts.charlie), 0);
+            assert_eq!(governor.reimbursement_budget(), 15);
+        }
+
+        #[ink::test]
+        fn participation_rewards_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.participation_reward = 10;
+            config.min_reward_weight = math::percent(1) as u64;
+            governor.store_config(&config);
+            set_token_balance(accounts.bob, 100);
+            set_token_balance(accounts.charlie, 1);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(
+                governor.propose(accounts.django, 100, 2 * ONE_MINUTE),
+                Ok(())
+            );
+            set_sender(accounts.bob);
+            set_balance(accounts.bob, 0);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            assert_eq!(
+                governor.participation_reward(ProposalId(1), accounts.bob),
+                10
+            );
+            // Dust balances don't earn rewards.
+            set_sender(accounts.charlie);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(
+                governor.participation_reward(ProposalId(0), accounts.charlie),
+                0
+            );
+            set_sender(accounts.bob);
+
+            set_block_timestamp(ONE_MINUTE * 1000);
+            assert_eq!(
+                governor.claim_participation_rewards(vec![ProposalId(0), ProposalId(1)]),
+                Err(GovernorError::RewardPoolDepleted)
+            );
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(15);
+            assert_eq!(governor.fund_rewards(), Ok(()));
+            // The pool is out of reach of treasury payouts.
+            set_balance(contract_id(), 15);
+            assert_eq!(governor.free_balance(), 0);
+            // Only the concluded proposal pays out.
+            assert_eq!(
+                governor.claim_participation_rewards(vec![ProposalId(0), ProposalId(1)]),
+                Ok(10)
+            );
+            assert_eq!(
+                governor.claim_participation_rewards(vec![ProposalId(0), ProposalId(1)]),
+                Err(GovernorError::NothingToClaim)
+            );
+            set_block_timestamp(2 * ONE_MINUTE * 1000);
+            assert_eq!(
+                governor.claim_participation_rewards(vec![ProposalId(1)]),
+                Err(GovernorError::RewardPoolDepleted)
+            );
+            assert_eq!(governor.reward_pool(), 5);
+            assert_eq!(governor.reserved_balance(), 5);
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    accounts.bob
+                ),
+                Ok(10)
+            );
+        }
+
+        #[ink::test]
+        fn personhood_gate_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            set_token_balance(accounts.bob, 100);
+            set_token_balance(accounts.charlie, 100);
+            let gate = AccountId::from([0x0d; 32]);
+            let mut config = governor.config();
+            config.identity_gate = Some(gate);
+            config.participation_reward = 10;
+            config.verified_rewards = true;
+            governor.store_config(&config);
+            crate::testing::set_verified(gate, accounts.alice, true);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            // Unverified voters may vote, but earn no reward.
+            assert_eq!(
+                governor.participation_reward(ProposalId(0), accounts.alice),
+                10
+            );
+            assert_eq!(
+                governor.participation_reward(ProposalId(0), accounts.bob),
+                0
+            );
+
+            config.verified_voting = true;
+            governor.store_config(&config);
+            set_sender(accounts.charlie);
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::For),
+                Err(GovernorError::NotVerified)
+            );
+            crate::testing::set_verified(gate, accounts.charlie, true);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+        }
+
+        #[ink::test]
+        fn voting_streaks_work() {
+            const EPOCH: u64 = 60_000;
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.participation_reward = 100;
+            config.epoch_length = EPOCH;
+            config.streak_bonus = 10;
+            config.max_streak_bonus = 15;
+            governor.store_config(&config);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(governor.fund_rewards(), Ok(()));
+            set_token_balance(accounts.bob, 100);
+
+            // Two proposals in epoch 0, one in epoch 1 and two in epoch 2.
+            for (epoch, proposals) in [(0, 2), (1, 1), (2, 2)] {
+                set_block_timestamp(epoch * EPOCH);
+                set_sender(accounts.alice);
+                for _ in 0..proposals {
+                    assert_eq!(
+                        governor.propose(accounts.django, 100, 10 * ONE_MINUTE),
+                        Ok(())
+                    );
+                }
+            }
+            set_sender(accounts.bob);
+            for proposal_id in (0..4).map(ProposalId) {
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+            }
+            assert_eq!(governor.streak(accounts.bob), 2);
+            assert_eq!(
+                governor.participation_reward(ProposalId(0), accounts.bob),
+                100
+            );
+            // A completed epoch earns 10%, two of them hit the 15% cap.
+            assert_eq!(
+                governor.participation_reward(ProposalId(1), accounts.bob),
+                110
+            );
+            assert_eq!(
+                governor.participation_reward(ProposalId(2), accounts.bob),
+                115
+            );
+            // Bob hasn't voted on all of epoch 2 yet.
+            assert_eq!(
+                governor.participation_reward(ProposalId(3), accounts.bob),
+                115
+            );
+
+            // Leaving epoch 2 incomplete breaks the streak once it is over.
+            set_block_timestamp(3 * EPOCH);
+            assert_eq!(governor.streak(accounts.bob), 0);
+            assert_eq!(governor.voting_streak(accounts.bob).longest, 2);
+        }
+
+        #[ink::test]
+        fn inactivity_decays_weight() {
+            const EPOCH: u64 = 60_000;
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.epoch_length = EPOCH;
+            config.inactivity_grace_epochs = 2;
+            config.inactivity_decay = 25;
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 500);
+            set_token_balance(accounts.bob, 500);
+            for _ in 0..3 {
+                assert_eq!(
+                    governor.propose(accounts.django, 100, 10 * ONE_MINUTE),
+                    Ok(())
+                );
+            }
+            let for_votes = |governor: &Governor, proposal_id: ProposalId| {
+                governor.proposal_votes.get(proposal_id).unwrap().for_votes
+            };
+            let half = math::PRECISION as u64 / 2;
+
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(for_votes(&governor, ProposalId(0)), half);
+            // Missing epochs 1 and 2 is within the grace period, missing 3 isn't.
+            set_block_timestamp(4 * EPOCH);
+            assert_eq!(governor.activity_factor(accounts.bob), 75);
+            set_block_timestamp(5 * EPOCH);
+            assert_eq!(governor.activity_factor(accounts.bob), 50);
+            assert_eq!(governor.activity_factor(accounts.alice), 100);
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            assert_eq!(for_votes(&governor, ProposalId(1)), half / 2);
+            // Voting again restores the full weight.
+            assert_eq!(governor.activity_factor(accounts.bob), 100);
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            assert_eq!(for_votes(&governor, ProposalId(2)), half);
+        }
+
+        #[ink::test]
+        fn inactive_holders_are_auto_delegated() {
+            const EPOCH: u64 = 60_000;
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.epoch_length = EPOCH;
+            config.default_delegates = vec![accounts.eve];
+            config.auto_delegation_epochs = 2;
+            governor.store_config(&config);
+            for account in [accounts.bob, accounts.charlie, accounts.django] {
+                set_token_balance(account, 100);
+            }
+            set_token_balance(accounts.eve, 200);
+            for _ in 0..3 {
+                assert_eq!(
+                    governor.propose(accounts.frank, 100, 10 * ONE_MINUTE),
+                    Ok(())
+                );
+            }
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(governor.delegate(Some(accounts.bob)), Ok(()));
+
+            set_block_timestamp(2 * EPOCH);
+            set_sender(accounts.eve);
+            // Bob voted in epoch 0 and Charlie delegated.
+            for holder in [accounts.bob, accounts.charlie] {
+                assert_eq!(
+                    governor.vote_for_inactive(
+                        ProposalId(1),
+                        VoteType::For,
+                        vec![holder]
+                    ),
+                    Err(GovernorError::NotInactive)
+                );
+            }
+            assert_eq!(
+                governor.vote_for_inactive(
+                    ProposalId(1),
+                    VoteType::For,
+                    vec![accounts.django]
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                governor
+                    .proposal_votes
+                    .get(ProposalId(1))
+                    .unwrap()
+                    .for_votes,
+                math::PRECISION as u64 * 3 / 5
+            );
+            assert!(governor.has_voted(ProposalId(1), accounts.django));
+
+            set_block_timestamp(3 * EPOCH);
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.vote_for_inactive(
+                    ProposalId(2),
+                    VoteType::For,
+                    vec![accounts.django]
+                ),
+                Err(GovernorError::NotDefaultDelegate)
+            );
+            // Having missed epochs 1 and 2, Bob is inactive now too.
+            set_sender(accounts.eve);
+            assert_eq!(
+                governor.vote_for_inactive(
+                    ProposalId(2),
+                    VoteType::Against,
+                    vec![accounts.bob, accounts.django]
+                ),
+                Ok(())
+            );
+        }
+
+        #[ink::test]
+        fn forward_works() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_balance(contract_id(), 1000);
+            let mut governor = Governor::with_config(GovernorConfig {
+                governance_token: AccountId::from([0x01; 32]),
+                quorum: 50,
+                vote_reimbursement: 0,
+                trusted_forwarder: Some(accounts.eve),
+                categories: Vec::new(),
+                guardians: Vec::new(),
+                fast_track_approval: 67,
+                objection_window: 0,
+                objection_threshold: 10,
+                quorum_decay_period: 0,
+                min_quorum: 50,
+                participation_reward: 0,
+                epoch_length: 0,
+                streak_bonus: 0,
+                max_streak_bonus: 0,
+                inactivity_grace_epochs: 0,
+                inactivity_decay: 0,
+                default_delegates: Vec::new(),
+                auto_delegation_epochs: 0,
+                call_allowlist: Vec::new(),
+                identity_gate: None,
+                verified_voting: false,
+                verified_rewards: false,
+                retro_badge_holders: Vec::new(),
+                donation_match: 0,
+                causes: Vec::new(),
+                revenue_sources: Vec::new(),
+                revenue_split: (0, 0),
+                dues: 0,
+                dues_period: 0,
+                dues_grace_period: 0,
+                tiers: None,
+                membership_shares: 0,
+                council_term: 0,
+                review_panel: 0,
+                review_rotation: ReviewRotation::RoundRobin,
+                weight_source: None,
+                twab_window: 0,
+                max_vote_weight: 0,
+                amendment_approval: 67,
+                amendment_duration: AMENDMENT_DURATION,
+                price_oracle: None,
+                max_start_delay: 0,
+                max_requested_quorum: 0,
+                max_requested_approval: 0,
+                voting_periods: Vec::new(),
+                cycle: None,
+                adapters: Vec::new(),
+                min_reward_weight: 0,
+                accept_selectors: Vec::new(),
+                admin_allowlist: Vec::new(),
+                deposit_lockup: 0,
+                settlement_period: 0,
+                liquidity_buffer: 0,
+                deposit_windows: None,
+            });
+            let propose = || {
+                ForwardedCall::Propose {
+                    to: accounts.django,
+                    amount: 100,
+                    duration: ONE_MINUTE,
+                }
+            };
+            let (sender, signature) = sign_forward(&governor, 7, &propose(), 0);
+            set_token_balance(sender, 100);
+            assert_eq!(
+                governor.forward(sender, propose(), 0, signature),
+                Err(GovernorError::NotTrustedForwarder)
+            );
+            set_sender(accounts.eve);
+            assert_eq!(
+                governor.forward(sender, propose(), 1, signature),
+                Err(GovernorError::InvalidNonce)
+            );
+            let vote = || {
+                ForwardedCall::Vote {
+                    proposal_id: ProposalId(0),
+                    vote: VoteType::For,
+                }
+            };
+            assert_eq!(
+                governor.forward(sender, vote(), 0, signature),
+                Err(GovernorError::InvalidSignature)
+            );
+            let (other, _) = sign_forward(&governor, 8, &propose(), 0);
+            assert_eq!(
+                governor.forward(other, propose(), 0, signature),
+                Err(GovernorError::InvalidSignature)
+            );
+            assert_eq!(governor.forward(sender, propose(), 0, signature), Ok(()));
+            assert_eq!(governor.forward_nonce(sender), 1);
+            assert_eq!(
+                governor.forward(sender, propose(), 0, signature),
+                Err(GovernorError::InvalidNonce)
+            );
+
+            let (_, signature) = sign_forward(&governor, 7, &vote(), 1);
+            assert_eq!(governor.forward(sender, vote(), 1, signature), Ok(()));
+            assert!(governor.has_voted(ProposalId(0), sender));
+            assert!(!governor.has_voted(ProposalId(0), accounts.eve));
+            let claim = || {
+                ForwardedCall::ClaimReimbursements {
+                    proposal_ids: vec![ProposalId(0)],
+                }
+            };
+            let (_, signature) = sign_forward(&governor, 7, &claim(), 2);
+            assert_eq!(
+                governor.forward(sender, claim(), 2, signature),
+                Err(GovernorError::NothingToClaim)
+            );
+        }
+
+        /// Signs `call` to be forwarded with `nonce` for the account of the ECDSA
+        /// key made of `seed` bytes, returning the account and the signature.
+        fn sign_forward(
+            governor: &Governor,
+            seed: u8,
+            call: &ForwardedCall,
+            nonce: u64,
+        ) -> (AccountId, [u8; 65]) {
+            use secp256k1::{
+                Message,
+                PublicKey,
+                SecretKey,
+                SECP256K1,
+            };
+            let secret = SecretKey::from_slice(&[seed; 32]).unwrap();
+            let public_key = PublicKey::from_secret_key(SECP256K1, &secret).serialize();
+            let mut account = [0; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&public_key, &mut account);
+            let sender = AccountId::from(account);
+            let message =
+                Message::from_slice(&governor.forward_hash(sender, call, nonce)).unwrap();
+            let (recovery_id, compact) = SECP256K1
+                .sign_ecdsa_recoverable(&message, &secret)
+                .serialize_compact();
+            let mut signature = [0; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            (sender, signature)
+        }
+
+        #[ink::test]
+        fn voting_key_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            governor.set_voting_key(Some(accounts.bob));
+            assert_eq!(governor.voting_key(accounts.alice), Some(accounts.bob));
+
+            set_sender(accounts.charlie);
+            assert_eq!(
+                governor.vote_as(accounts.alice, ProposalId(0), VoteType::For),
+                Err(GovernorError::NotVotingKey)
+            );
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.vote_as(accounts.alice, ProposalId(0), VoteType::For),
+                Ok(())
+            );
+            assert!(governor.has_voted(ProposalId(0), accounts.alice));
+            assert!(!governor.has_voted(ProposalId(0), accounts.bob));
+            assert_eq!(
+                governor
+                    .proposal_votes
+                    .get(ProposalId(0))
+                    .unwrap()
+                    .for_votes,
+                math::PRECISION as u64
+            );
+
+            set_sender(accounts.alice);
+            governor.set_voting_key(None);
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.vote_as(accounts.alice, ProposalId(1), VoteType::For),
+                Err(GovernorError::NotVotingKey)
+            );
+            assert_eq!(ink::env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn transitive_delegation_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            for account in [accounts.alice, accounts.bob, accounts.charlie] {
+                set_token_balance(account, 100);
+            }
+            set_token_balance(accounts.django, 200);
+            assert_eq!(governor.propose(accounts.eve, 100, ONE_MINUTE), Ok(()));
+            // Alice -> Bob -> Charlie
+            assert_eq!(governor.delegate(Some(accounts.bob)), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.delegate(Some(accounts.charlie)), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(
+                governor.delegate(Some(accounts.alice)),
+                Err(GovernorError::DelegationCycle)
+            );
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::For),
+                Err(GovernorError::PowerDelegated)
+            );
+
+            // Delegations made after the vote started don't move power on it.
+            set_block_timestamp(1);
+            set_sender(accounts.django);
+            assert_eq!(governor.delegate(Some(accounts.charlie)), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(
+                governor
+                    .proposal_votes
+                    .get(ProposalId(0))
+                    .unwrap()
+                    .for_votes,
+                math::PRECISION as u64 * 3 / 5
+            );
+            assert!(governor.has_voted(ProposalId(0), accounts.alice));
+            assert!(governor.has_voted(ProposalId(0), accounts.bob));
+            set_sender(accounts.django);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+
+            // Taking the power back doesn't either.
+            set_sender(accounts.alice);
+            assert_eq!(governor.delegate(None), Ok(()));
+            assert_eq!(governor.delegate_of(accounts.alice), None);
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::Against),
+                Err(GovernorError::PowerDelegated)
+            );
+            assert_eq!(governor.propose(accounts.eve, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::Against), Ok(()));
+        }
+
+        #[ink::test]
+        fn proposals_by_category_works() {
+            let accounts = default_accounts();
+            let mut governor = create_categorized_contract(100_000);
+            assert_eq!(
+                governor.propose_in_category(accounts.django, 100, ONE_MINUTE, 3),
+                Err(GovernorError::UnknownCategory)
+            );
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            for category in [DEFI, OPERATIONS, DEFI, DEFI] {
+                assert_eq!(
+                    governor.propose_in_category(
+                        accounts.django,
+                        100,
+                        ONE_MINUTE,
+                        category
+                    ),
+                    Ok(())
+                );
+            }
+            assert_eq!(
+                governor.proposals_by_category(GENERAL_CATEGORY, 0, 10),
+                [ProposalId(0)]
+            );
+            assert_eq!(
+                governor.proposals_by_category(DEFI, 0, 10),
+                [ProposalId(1), ProposalId(3), ProposalId(4)]
+            );
+            assert_eq!(governor.proposals_by_category(DEFI, 1, 1), [ProposalId(3)]);
+            assert_eq!(
+                governor.proposals_by_category(OPERATIONS, 0, 10),
+                [ProposalId(2)]
+            );
+
+            set_block_timestamp(ONE_MINUTE * 1000 + PRUNE_CHALLENGE_WINDOW);
+            assert_eq!(governor.prune(vec![ProposalId(1), ProposalId(2)]), Ok(()));
+            assert_eq!(
+                governor.proposals_by_category(DEFI, 0, 10),
+                [ProposalId(4), ProposalId(3)]
+            );
+            assert!(governor.proposals_by_category(OPERATIONS, 0, 10).is_empty());
+            assert_eq!(governor.proposal_category(ProposalId(2)), GENERAL_CATEGORY);
+        }
+
+        #[ink::test]
+        fn fast_track_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            let mut config = governor.config();
+            config.guardians = vec![accounts.charlie];
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 600);
+            set_token_balance(accounts.bob, 400);
+            assert_eq!(
+                governor.propose_fast_track(accounts.django, 100),
+                Err(GovernorError::NotGuardian)
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(governor.propose_fast_track(accounts.django, 100), Ok(()));
+            assert_eq!(governor.propose_fast_track(accounts.django, 100), Ok(()));
+            assert!(governor.is_fast_tracked(ProposalId(0)));
+            assert!(!governor.is_fast_tracked(ProposalId(2)));
+
+            // A simple majority isn't enough...
+            set_sender(accounts.alice);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::FastTrackApprovalNotReached)
+            );
+            // ...it takes a supermajority of the supply, which doesn't wait for
+            // the vote to end.
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            set_sender(accounts.alice);
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+        }
+
+        #[ink::test]
+        fn objection_window_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            let mut config = governor.config();
+            config.objection_window = 60_000;
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 600);
+            set_token_balance(accounts.bob, 400);
+            set_token_balance(accounts.charlie, 50);
+            for proposal_id in (0..2).map(ProposalId) {
+                assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+            }
+            end_vote(&governor, ProposalId(0));
+            for proposal_id in (0..2).map(ProposalId) {
+                assert_eq!(
+                    governor.execute(proposal_id),
+                    Err(GovernorError::ObjectionWindowNotStarted)
+                );
+                assert_eq!(governor.start_objection_window(proposal_id), Ok(()));
+                assert_eq!(
+                    governor.start_objection_window(proposal_id),
+                    Err(GovernorError::ObjectionWindowStarted)
+                );
+            }
+
+            // Objections below the threshold only delay execution.
+            set_sender(accounts.charlie);
+            assert_eq!(governor.object(ProposalId(0)), Ok(()));
+            assert_eq!(
+                governor.object(ProposalId(0)),
+                Err(GovernorError::AlreadyObjected)
+            );
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::ObjectionWindowOpen)
+            );
+            // Tokens acquired after the vote started carry no weight.
+            set_sender(accounts.eve);
+            set_token_balance(accounts.eve, 50);
+            assert_eq!(governor.object(ProposalId(0)), Ok(()));
+            assert_eq!(
+                governor
+                    .objection_window(ProposalId(0))
+                    .map(|window| window.weight),
+                Some(math::ratio(50, 1050) as u64)
+            );
+
+            // Enough of them send the proposal back to a vote.
+            set_sender(accounts.bob);
+            assert_eq!(governor.object(ProposalId(1)), Ok(()));
+            assert_eq!(governor.objection_window(ProposalId(1)), None);
+            assert!(!governor.has_voted(ProposalId(1), accounts.alice));
+            assert_eq!(
+                governor.execute(ProposalId(1)),
+                Err(GovernorError::VotePeriodNotEnded)
+            );
+            assert_eq!(
+                governor.start_objection_window(ProposalId(1)),
+                Err(GovernorError::NoObjectionWindow)
+            );
+            set_sender(accounts.alice);
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+
+            set_block_timestamp(120_000);
+            assert_eq!(
+                governor.object(ProposalId(0)),
+                Err(GovernorError::ObjectionWindowClosed)
+            );
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            // The confirmation vote is final.
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+        }
+
+        #[ink::test]
+        fn quorum_decays() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            let mut config = governor.config();
+            config.quorum_decay_period = 480_000;
+            config.min_quorum = 20;
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 300);
+            set_token_balance(accounts.bob, 700);
+            assert_eq!(
+                governor.propose(accounts.django, 100, 4 * ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+
+            set_block_timestamp(120_000);
+            assert_eq!(governor.current_quorum(ProposalId(0)), Ok(425_000_000_000));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.current_quorum(ProposalId(0)),
+                Ok(math::percent(35) as u64)
+            );
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::QuorumNotReached {
+                    needed: 350_000_000_000,
+                    got: 300_000_000_000,
+                })
+            );
+            // The quorum stops decaying once the vote has ended.
+            set_block_timestamp(u64::MAX);
+            assert_eq!(
+                governor.current_quorum(ProposalId(0)),
+                Ok(math::percent(35) as u64)
+            );
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::QuorumNotReached {
+                    needed: 350_000_000_000,
+                    got: 300_000_000_000,
+                })
+            );
+
+            set_block_timestamp(0);
+            set_token_balance(accounts.alice, 400);
+            assert_eq!(
+                governor.propose(accounts.django, 100, 4 * ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(1));
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+        }
+
+        #[ink::test]
+        fn admin_actions_work() {
+            const ACCEPT_OWNERSHIP: [u8; 4] = ink::selector_bytes!("accept_ownership");
+            const SET_FEE: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let target = accounts.django;
+            let input = 30u8.encode();
+            for action in [AdminAction::Call, AdminAction::Release] {
+                assert_eq!(
+                    governor.propose_admin_action(
+                        target,
+                        action,
+                        SET_FEE,
+                        input.clone(),
+                        ONE_MINUTE
+                    ),
+                    Err(GovernorError::NotAdministered)
+                );
+            }
+            let mut config = governor.config();
+            config.accept_selectors = vec![ACCEPT_OWNERSHIP];
+            governor.store_config(&config);
+            // Acceptances can't smuggle in another message, or arguments.
+            for (selector, input) in
+                [(SET_FEE, Vec::new()), (ACCEPT_OWNERSHIP, input.clone())]
+            {
+                assert_eq!(
+                    governor.propose_admin_action(
+                        target,
+                        AdminAction::Accept,
+                        selector,
+                        input,
+                        ONE_MINUTE
+                    ),
+                    Err(GovernorError::CallNotAllowed)
+                );
+            }
+            assert_eq!(
+                governor.propose_admin_action(
+                    target,
+                    AdminAction::Accept,
+                    ACCEPT_OWNERSHIP,
+                    Vec::new(),
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                governor.admin_action(ProposalId(0)),
+                Some((
+                    AdminAction::Accept,
+                    ProposalCall {
+                        selector: ACCEPT_OWNERSHIP,
+                        input: Vec::new(),
+                    }
+                ))
+            );
+
+            // Owner-only calls must be on the admin allowlist, and the governor
+            // still in charge when they are executed.
+            governor.administered.set(&vec![target]);
+            assert!(governor.is_administered(target));
+            assert_eq!(
+                governor.propose_admin_action(
+                    target,
+                    AdminAction::Call,
+                    SET_FEE,
+                    input.clone(),
+                    ONE_MINUTE
+                ),
+                Err(GovernorError::CallNotAllowed)
+            );
+            config.admin_allowlist = vec![(target, SET_FEE)];
+            governor.store_config(&config);
+            for _ in 0..2 {
+                assert_eq!(
+                    governor.propose_admin_action(
+                        target,
+                        AdminAction::Call,
+                        SET_FEE,
+                        input.clone(),
+                        ONE_MINUTE
+                    ),
+                    Ok(())
+                );
+            }
+            set_token_balance(accounts.alice, 100);
+            for proposal_id in (1..3).map(ProposalId) {
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+            }
+            end_vote(&governor, ProposalId(1));
+            config.admin_allowlist = Vec::new();
+            governor.store_config(&config);
+            assert_eq!(
+                governor.execute(ProposalId(1)),
+                Err(GovernorError::CallNotAllowed)
+            );
+            config.admin_allowlist = vec![(target, SET_FEE)];
+            governor.store_config(&config);
+            governor.administered.set(&Vec::new());
+            assert_eq!(
+                governor.execute(ProposalId(2)),
+                Err(GovernorError::NotAdministered)
+            );
+        }
+
+        #[ink::test]
+        fn adapter_calls_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let adapter = accounts.django;
+            let payload = (accounts.bob, 100u128).encode();
+            assert_eq!(
+                governor.propose_adapter_call(adapter, payload.clone(), 0, ONE_MINUTE),
+                Err(GovernorError::UnknownAdapter)
+            );
+            let mut config = governor.config();
+            config.adapters = vec![(adapter, String::from("swap"))];
+            governor.store_config(&config);
+            assert_eq!(
+                governor.propose_adapter_call(adapter, payload.clone(), 0, ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(governor.adapter_payload(ProposalId(0)), Some(payload));
+
+            // Adapters removed from the registry can't be invoked anymore.
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            config.adapters.clear();
+            governor.store_config(&config);
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::UnknownAdapter)
+            );
+        }
+
+        #[ink::test]
+        fn call_allowlist_works() {
+            const SELECTOR: [u8; 4] = [0xde, 0xad, 0xbe, 0xef];
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            let target = accounts.django;
+            let input = (accounts.bob, 100u128).encode();
+            assert_eq!(
+                governor.propose_call(target, SELECTOR, input.clone(), 0, ONE_MINUTE),
+                Err(GovernorError::CallNotAllowed)
+            );
+            let mut config = governor.config();
+            config.call_allowlist = vec![(target, SELECTOR)];
+            governor.store_config(&config);
+            assert_eq!(
+                governor.propose_call(target, SELECTOR, input.clone(), 0, ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(
+                governor.proposal_call(ProposalId(0)),
+                Some(ProposalCall {
+                    selector: SELECTOR,
+                    input
+                })
+            );
+
+            // Selectors dropped from the allowlist can't be executed anymore.
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            config.call_allowlist.clear();
+            governor.store_config(&config);
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::CallNotAllowed)
+            );
+            assert!(!governor.get_proposal(ProposalId(0)).unwrap().executed);
+        }
+
+        #[ink::test]
+        fn polls_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            let options = ["Venue A", "Venue B", "Venue C"].map(String::from).to_vec();
+            assert_eq!(
+                governor.propose_poll(options[..1].to_vec(), ONE_MINUTE),
+                Err(GovernorError::InvalidPollOptions)
+            );
+            assert_eq!(governor.propose_poll(options.clone(), ONE_MINUTE), Ok(()));
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            set_token_balance(accounts.alice, 300);
+            set_token_balance(accounts.bob, 200);
+            set_token_balance(accounts.charlie, 500);
+
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::For),
+                Err(GovernorError::IsPoll)
+            );
+            assert_eq!(
+                governor.vote_poll(ProposalId(1), 0),
+                Err(GovernorError::NotPoll)
+            );
+            assert_eq!(
+                governor.vote_poll(ProposalId(0), 3),
+                Err(GovernorError::InvalidPollOptions)
+            );
+            assert_eq!(governor.vote_poll(ProposalId(0), 2), Ok(()));
+            assert_eq!(
+                governor.vote_poll(ProposalId(0), 1),
+                Err(GovernorError::AlreadyVoted)
+            );
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote_poll(ProposalId(0), 2), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(governor.vote_poll(ProposalId(0), 0), Ok(()));
+
+            let tenth = math::PRECISION as u64 / 10;
+            assert_eq!(
+                governor.poll(ProposalId(0)),
+                Some(Poll {
+                    options,
+                    tallies: vec![5 * tenth, 0, 5 * tenth],
+                })
+            );
+            assert_eq!(governor.execute(ProposalId(0)), Err(GovernorError::IsPoll));
+        }
+
+        #[ink::test]
+        fn budget_allocation_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            let recipients = vec![accounts.django, accounts.eve, accounts.frank];
+            assert_eq!(
+                governor.propose_allocation(1000, recipients[..1].to_vec(), ONE_MINUTE),
+                Err(GovernorError::InvalidAllocation)
+            );
+            assert_eq!(
+                governor.propose_allocation(1000, recipients.clone(), ONE_MINUTE),
+                Ok(())
+            );
+            set_token_balance(accounts.alice, 300);
+            set_token_balance(accounts.bob, 200);
+            set_token_balance(accounts.charlie, 500);
+
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::For),
+                Err(GovernorError::IsAllocation)
+            );
+            assert_eq!(
+                governor.vote_allocation(ProposalId(0), vec![50, 60, 0]),
+                Err(GovernorError::InvalidAllocation)
+            );
+            assert_eq!(
+                governor.vote_allocation(ProposalId(0), vec![50, 50, 0]),
+                Ok(())
+            );
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.vote_allocation(ProposalId(0), vec![0, 0, 100]),
+                Ok(())
+            );
+            end_vote(&governor, ProposalId(0));
+
+            for recipient in &recipients {
+                set_balance(*recipient, 0);
+            }
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            let balance = |account| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    account,
+                )
+            };
+            assert_eq!(balance(accounts.django), Ok(300));
+            assert_eq!(balance(accounts.eve), Ok(300));
+            assert_eq!(balance(accounts.frank), Ok(400));
+        }
+
+        #[ink::test]
+        fn batch_transfer_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(
+                governor.propose_batch_transfer(vec![], ONE_MINUTE),
+                Err(GovernorError::InvalidBatch)
+            );
+            assert_eq!(
+                governor.propose_batch_transfer(
+                    vec![(accounts.django, 1); MAX_BATCH_RECIPIENTS + 1],
+                    ONE_MINUTE
+                ),
+                Err(GovernorError::InvalidBatch)
+            );
+            assert_eq!(
+                governor.propose_batch_transfer(
+                    vec![(accounts.django, 100), (accounts.eve, 0)],
+                    ONE_MINUTE
+                ),
+                Err(GovernorError::AmountShouldNotBeZero)
+            );
+            let transfers = vec![(accounts.django, 100), (accounts.eve, 200)];
+            assert_eq!(
+                governor.propose_batch_transfer(transfers.clone(), ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(governor.batch_transfer(ProposalId(0)), Some(transfers));
+            assert_eq!(
+                governor
+                    .get_proposal(ProposalId(0))
+                    .map(|proposal| proposal.amount),
+                Ok(300)
+            );
+
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_balance(accounts.django, 0);
+            set_balance(accounts.eve, 0);
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            let balance = |account| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    account,
+                )
+            };
+            assert_eq!(balance(accounts.django), Ok(100));
+            assert_eq!(balance(accounts.eve), Ok(200));
+            assert_eq!(balance(contract_id()), Ok(700));
+        }
+
+        #[ink::test]
+        fn treasury_share_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(
+                governor.propose_treasury_share(accounts.django, 0, 500, ONE_MINUTE),
+                Err(GovernorError::InvalidTreasuryShare)
+            );
+            assert_eq!(
+                governor.propose_treasury_share(accounts.django, 101, 500, ONE_MINUTE),
+                Err(GovernorError::InvalidTreasuryShare)
+            );
+            assert_eq!(
+                governor.propose_treasury_share(accounts.django, 10, 0, ONE_MINUTE),
+                Err(GovernorError::AmountShouldNotBeZero)
+            );
+            // The cap may exceed the treasury, only the share is paid.
+            for cap in [5000, 150] {
+                assert_eq!(
+                    governor.propose_treasury_share(accounts.django, 20, cap, ONE_MINUTE),
+                    Ok(())
+                );
+            }
+            assert_eq!(governor.treasury_share(ProposalId(0)), Some(20));
+            set_balance(accounts.django, 0);
+            set_balance(contract_id(), 3000);
+            governor.reserve(1000);
+            for proposal_id in (0..2).map(ProposalId) {
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+            }
+            end_vote(&governor, ProposalId(0));
+            for proposal_id in (0..2).map(ProposalId) {
+                assert_eq!(governor.execute(proposal_id), Ok(()));
+            }
+            // 20% of the 2000 left free, then the cap rather than 20% of 1600.
+            let balance = |account| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    account,
+                )
+            };
+            assert_eq!(balance(accounts.django), Ok(400 + 150));
+            assert_eq!(balance(contract_id()), Ok(2450));
+        }
+
+        #[ink::test]
+        fn stable_transfer_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(
+                governor.propose_stable_transfer(accounts.django, 100, 10, ONE_MINUTE),
+                Err(GovernorError::NoPriceOracle)
+            );
+            let oracle = AccountId::from([0x0e; 32]);
+            let mut config = governor.config();
+            config.price_oracle = Some(oracle);
+            governor.store_config(&config);
+            assert_eq!(
+                governor.propose_stable_transfer(accounts.django, 100, 10, ONE_MINUTE),
+                Err(GovernorError::PriceOracleFailed)
+            );
+            // One native unit is worth two stable units.
+            crate::testing::set_price(oracle, 2 * math::PRECISION);
+            assert_eq!(
+                governor.propose_stable_transfer(accounts.django, 100, 100, ONE_MINUTE),
+                Err(GovernorError::InvalidSlippage)
+            );
+            for _ in 0..2 {
+                assert_eq!(
+                    governor.propose_stable_transfer(
+                        accounts.django,
+                        100,
+                        10,
+                        ONE_MINUTE
+                    ),
+                    Ok(())
+                );
+            }
+            assert_eq!(
+                governor.stable_amount(ProposalId(0)),
+                Some(StableAmount {
+                    amount: 100,
+                    reference_price: 2 * math::PRECISION,
+                    max_slippage: 10,
+                })
+            );
+            // Voters approve the amount at the lowest price allowed.
+            assert_eq!(governor.get_proposal(ProposalId(0)).unwrap().amount, 55);
+            set_balance(accounts.django, 0);
+            set_balance(contract_id(), 1000);
+            for proposal_id in (0..2).map(ProposalId) {
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+            }
+            let price = 25 * math::PRECISION / 10;
+            crate::testing::set_price(oracle, price);
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::PriceOutOfBounds { price })
+            );
+            // The price rose, so fewer native units are paid.
+            crate::testing::set_price(oracle, 22 * math::PRECISION / 10);
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            crate::testing::set_price(oracle, 18 * math::PRECISION / 10);
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    accounts.django
+                ),
+                Ok(45 + 55)
+            );
+        }
+
+        #[ink::test]
+        fn installments_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            for (tranches, interval) in [(0, 1000), (MAX_TRANCHES + 1, 1000), (3, 0)] {
+                assert_eq!(
+                    governor.propose_installments(
+                        accounts.django,
+                        100,
+                        tranches,
+                        interval,
+                        ONE_MINUTE
+                    ),
+                    Err(GovernorError::InvalidInstallments)
+                );
+            }
+            for _ in 0..2 {
+                assert_eq!(
+                    governor.propose_installments(
+                        accounts.django,
+                        100,
+                        3,
+                        1000,
+                        ONE_MINUTE
+                    ),
+                    Ok(())
+                );
+            }
+            assert_eq!(
+                governor.release_tranche(ProposalId(0)),
+                Err(GovernorError::InstallmentsNotStarted)
+            );
+            set_balance(accounts.django, 0);
+            for proposal_id in (0..2).map(ProposalId) {
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+            }
+            end_vote(&governor, ProposalId(0));
+            for proposal_id in (0..2).map(ProposalId) {
+                assert_eq!(governor.execute(proposal_id), Ok(()));
+            }
+            let balance = || {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    accounts.django,
+                )
+                .unwrap()
+            };
+            // Executing pays nothing and reserves the tranches, the first one is
+            // due right away.
+            assert_eq!(balance(), 0);
+            assert_eq!(governor.reserved_balance(), 200);
+            set_sender(accounts.eve);
+            assert_eq!(governor.release_tranche(ProposalId(0)), Ok(()));
+            assert_eq!(balance(), 33);
+            assert_eq!(governor.reserved_balance(), 167);
+            assert_eq!(
+                governor.release_tranche(ProposalId(0)),
+                Err(GovernorError::NoTrancheDue)
+            );
+            assert_eq!(governor.release_tranche(ProposalId(1)), Ok(()));
+
+            // Governance stops the second payout.
+            set_sender(accounts.alice);
+            assert_eq!(
+                governor.propose_halt_installments(ProposalId(2), ONE_MINUTE),
+                Err(GovernorError::NotInstallments)
+            );
+            assert_eq!(
+                governor.propose_halt_installments(ProposalId(1), ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(2));
+            assert_eq!(governor.execute(ProposalId(2)), Ok(()));
+            assert_eq!(governor.reserved_balance(), 67);
+
+            // Overdue tranches are released together, with the remainder.
+            assert_eq!(governor.release_tranche(ProposalId(0)), Ok(()));
+            assert_eq!(
+                governor.release_tranche(ProposalId(1)),
+                Err(GovernorError::InstallmentsHalted)
+            );
+            assert_eq!(balance(), 33 + 100);
+            assert_eq!(governor.installments(ProposalId(0)).unwrap().released, 3);
+            assert_eq!(governor.reserved_balance(), 0);
+        }
+
+        #[ink::test]
+        fn quadratic_funding_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            assert_eq!(
+                governor.propose_funding_round(
+                    1000,
+                    vec![accounts.django],
+                    100,
+                    ONE_MINUTE
+                ),
+                Err(GovernorError::InvalidCandidates)
+            );
+            assert_eq!(
+                governor.propose_funding_round(
+                    1000,
+                    vec![accounts.django, accounts.eve],
+                    100,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            set_token_balance(accounts.alice, 100);
+            let contribute = |governor: &mut Governor, contributor, candidate, amount| {
+                set_sender(contributor);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                    amount,
+                );
+                governor.contribute(ProposalId(0), candidate)
+            };
+            assert_eq!(
+                contribute(&mut governor, accounts.bob, 0, 10),
+                Err(GovernorError::RoundNotOpen)
+            );
+            set_sender(accounts.alice);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            assert_eq!(governor.reserved_balance(), 1000);
+
+            assert_eq!(
+                contribute(&mut governor, accounts.bob, 2, 10),
+                Err(GovernorError::UnknownCandidate)
+            );
+            assert_eq!(
+                contribute(&mut governor, accounts.bob, 0, 0),
+                Err(GovernorError::AmountShouldNotBeZero)
+            );
+            // Django is backed by three contributors of 16, Eve by 9 and 16.
+            assert_eq!(contribute(&mut governor, accounts.bob, 0, 7), Ok(()));
+            assert_eq!(contribute(&mut governor, accounts.bob, 0, 9), Ok(()));
+            assert_eq!(contribute(&mut governor, accounts.charlie, 0, 16), Ok(()));
+            assert_eq!(contribute(&mut governor, accounts.frank, 0, 16), Ok(()));
+            assert_eq!(contribute(&mut governor, accounts.charlie, 1, 9), Ok(()));
+            assert_eq!(contribute(&mut governor, accounts.frank, 1, 16), Ok(()));
+            assert_eq!(governor.contribution(ProposalId(0), accounts.bob, 0), 16);
+            // Contributions are out of reach of other proposals.
+            assert_eq!(governor.reserved_balance(), 1073);
+            assert_eq!(
+                governor.close_funding_round(ProposalId(0)),
+                Err(GovernorError::RoundOpen)
+            );
+
+            set_block_timestamp(ONE_MINUTE * 1000 + 100);
+            assert_eq!(
+                contribute(&mut governor, accounts.bob, 0, 10),
+                Err(GovernorError::RoundClosed)
+            );
+            set_balance(accounts.django, 0);
+            set_balance(accounts.eve, 0);
+            assert_eq!(governor.close_funding_round(ProposalId(0)), Ok(()));
+            assert_eq!(governor.reserved_balance(), 0);
+            assert_eq!(
+                governor.close_funding_round(ProposalId(0)),
+                Err(GovernorError::RoundClosed)
+            );
+            // Ideal matches of 12² - 48 = 96 and 7² - 25 = 24 split the pool.
+            let balance = |account| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    account,
+                )
+            };
+            assert_eq!(balance(accounts.django), Ok(48 + 800));
+            assert_eq!(balance(accounts.eve), Ok(25 + 200));
+        }
+
+        #[ink::test]
+        fn retro_funding_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            let mut config = governor.config();
+            config.retro_badge_holders = vec![accounts.alice, accounts.bob];
+            governor.store_config(&config);
+            set_block_timestamp(1000);
+            assert_eq!(
+                governor.propose_retro_round(900, (0, 2000), 100, 100, ONE_MINUTE),
+                Err(GovernorError::DurationError)
+            );
+            assert_eq!(
+                governor.propose_retro_round(900, (0, 500), 100, 100, ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(
+                governor.nominate(ProposalId(0), [1; 32], accounts.django),
+                Err(GovernorError::RoundNotOpen)
+            );
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            assert_eq!(governor.reserved_balance(), 900);
+
+            // Redemptions during the round can't touch the pot.
+            governor.loot.insert(accounts.charlie, &10);
+            governor.total_loot.set(&10);
+            set_sender(accounts.charlie);
+            assert_eq!(governor.redeem_loot(10), Ok(100_000 - 900));
+
+            set_sender(accounts.django);
+            assert_eq!(
+                governor.nominate(ProposalId(0), [1; 32], accounts.django),
+                Ok(())
+            );
+            set_sender(accounts.eve);
+            assert_eq!(
+                governor.nominate(ProposalId(0), [2; 32], accounts.eve),
+                Ok(())
+            );
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.allocate_retro(ProposalId(0), vec![0, 100]),
+                Err(GovernorError::NominationsOpen)
+            );
+
+            set_block_timestamp(61_100);
+            assert_eq!(
+                governor.nominate(ProposalId(0), [3; 32], accounts.frank),
+                Err(GovernorError::NominationsClosed)
+            );
+            assert_eq!(governor.allocate_retro(ProposalId(0), vec![0, 100]), Ok(()));
+            assert_eq!(
+                governor.allocate_retro(ProposalId(0), vec![0, 100]),
+                Err(GovernorError::AlreadyVoted)
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(
+                governor.allocate_retro(ProposalId(0), vec![50, 50]),
+                Err(GovernorError::NotBadgeHolder)
+            );
+            set_sender(accounts.alice);
+            assert_eq!(governor.allocate_retro(ProposalId(0), vec![50, 50]), Ok(()));
+            assert_eq!(
+                governor.close_retro_round(ProposalId(0)),
+                Err(GovernorError::RoundOpen)
+            );
+
+            set_block_timestamp(61_200);
+            set_balance(accounts.django, 0);
+            set_balance(accounts.eve, 0);
+            assert_eq!(governor.close_retro_round(ProposalId(0)), Ok(()));
+            assert_eq!(
+                governor.close_retro_round(ProposalId(0)),
+                Err(GovernorError::RoundClosed)
+            );
+            let balance = |account| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    account,
+                )
+            };
+            // Each badge holder weighs half: Django gets a quarter, Eve the rest.
+            assert_eq!(balance(accounts.django), Ok(225));
+            assert_eq!(balance(accounts.eve), Ok(675));
+            assert_eq!(governor.reserved_balance(), 0);
+        }
+
+        #[ink::test]
+        fn retro_allocations_weigh_balances_at_opening() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            set_block_timestamp(1000);
+            set_token_balance(accounts.alice, 100);
+            set_token_balance(accounts.bob, 100);
+            assert_eq!(
+                governor.propose_retro_round(900, (0, 500), 100, 100, ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            for (account, contribution) in
+                [(accounts.django, [1; 32]), (accounts.eve, [2; 32])]
+            {
+                set_sender(account);
+                assert_eq!(
+                    governor.nominate(ProposalId(0), contribution, account),
+                    Ok(())
+                );
+            }
+
+            // Tokens moved after the round opened don't change the weights.
+            set_block_timestamp(61_100);
+            assert_eq!(
+                crate::testing::transfer_tokens(accounts.bob, accounts.charlie, 100),
+                Ok(())
+            );
+            set_sender(accounts.bob);
+            assert_eq!(governor.allocate_retro(ProposalId(0), vec![0, 100]), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(governor.allocate_retro(ProposalId(0), vec![100, 0]), Ok(()));
+            assert_eq!(
+                governor.retro_round(ProposalId(0)).unwrap().tallies,
+                [0, math::percent(50) as u64]
+            );
+        }
+
+        #[ink::test]
+        fn donation_matching_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.donation_match = 100;
+            config.causes = vec![(accounts.django, 150), (accounts.eve, 10_000)];
+            governor.store_config(&config);
+            set_balance(accounts.django, 0);
+            set_balance(accounts.eve, 0);
+            let balance = |account| {
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    account,
+                )
+            };
+            // Off-chain, the transferred value isn't credited to the contract.
+            let donate = |governor: &mut Governor, cause, amount| {
+                set_balance(contract_id(), balance(contract_id()).unwrap() + amount);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                    amount,
+                );
+                governor.donate(cause)
+            };
+            assert_eq!(
+                donate(&mut governor, accounts.frank, 100),
+                Err(GovernorError::UnknownCause)
+            );
+            assert_eq!(donate(&mut governor, accounts.django, 100), Ok(100));
+            // Only 50 of the cap is left.
+            assert_eq!(donate(&mut governor, accounts.django, 100), Ok(50));
+            assert_eq!(donate(&mut governor, accounts.django, 100), Ok(0));
+            assert_eq!(
+                governor.cause_account(accounts.django),
+                CauseAccount {
+                    donated: 300,
+                    matched: 150,
+                }
+            );
+            assert_eq!(balance(accounts.django), Ok(450));
+
+            // The treasury can't match more than it holds outside reservations.
+            set_balance(contract_id(), 200);
+            governor.reserve(50);
+            assert_eq!(donate(&mut governor, accounts.eve, 500), Ok(150));
+            assert_eq!(balance(accounts.eve), Ok(650));
+            assert_eq!(balance(contract_id()), Ok(50));
+        }
+
+        #[ink::test]
+        fn nft_treasury_works() {
+            use crate::testing::{
+                is_nft_approved,
+                nft_owner,
+                set_nft_owner,
+            };
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let collection = AccountId::from([0x07; 32]);
+            let (owned, foreign) = (Id::U32(1), Id::U32(2));
+            set_nft_owner(collection, owned.clone(), contract_id());
+            set_nft_owner(collection, foreign.clone(), accounts.bob);
+            assert_eq!(
+                governor.register_nft(collection, foreign.clone()),
+                Err(GovernorError::NotNftOwner)
+            );
+            assert_eq!(governor.register_nft(collection, owned.clone()), Ok(()));
+            assert_eq!(governor.register_nft(collection, owned.clone()), Ok(()));
+            assert_eq!(governor.nft_collections(), vec![collection]);
+            assert_eq!(governor.nft_holdings(collection), vec![owned.clone()]);
+
+            assert_eq!(
+                governor.propose_nft_transfer(
+                    collection,
+                    foreign,
+                    accounts.eve,
+                    ONE_MINUTE
+                ),
+                Err(GovernorError::NftNotHeld)
+            );
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(
+                governor.propose_nft_approval(
+                    collection,
+                    accounts.django,
+                    Some(owned.clone()),
+                    true,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                governor.propose_nft_transfer(
+                    collection,
+                    owned.clone(),
+                    accounts.eve,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            for proposal_id in (0..2).map(ProposalId) {
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+            }
+            end_vote(&governor, ProposalId(0));
+            for proposal_id in (0..2).map(ProposalId) {
+                assert_eq!(governor.execute(proposal_id), Ok(()));
+            }
+            assert!(is_nft_approved(
+                collection,
+                contract_id(),
+                accounts.django,
+                Some(owned.clone())
+            ));
+            assert_eq!(nft_owner(collection, owned), Some(accounts.eve));
+            assert_eq!(governor.nft_collections(), Vec::<AccountId>::new());
+            assert_eq!(governor.nft_holdings(collection), Vec::<Id>::new());
+        }
+
+        #[ink::test]
+        fn revenue_routing_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.revenue_sources = vec![(accounts.frank, String::from("royalties"))];
+            config.revenue_split = (20, 30);
+            governor.store_config(&config);
+            let token = config.governance_token;
+            let (native, psp22) = (Asset::Native, Asset::Psp22(token));
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(
+                governor.collect_revenue(),
+                Err(GovernorError::UnknownRevenueSource)
+            );
+            set_sender(accounts.frank);
+            assert_eq!(governor.collect_revenue(), Ok(()));
+            assert_eq!(governor.revenue_collected(accounts.frank, native), 100);
+            assert_eq!(governor.revenue_pool(RevenuePool::Contributors, native), 20);
+            assert_eq!(governor.revenue_pool(RevenuePool::Distribution, native), 30);
+
+            set_token_balance(contract_id(), 500);
+            assert_eq!(
+                governor.collect_token_revenue(token, 600),
+                Err(GovernorError::RevenueNotReceived)
+            );
+            assert_eq!(governor.collect_token_revenue(token, 500), Ok(()));
+            assert_eq!(
+                governor.collect_token_revenue(token, 1),
+                Err(GovernorError::RevenueNotReceived)
+            );
+            assert_eq!(governor.revenue_collected(accounts.frank, psp22), 500);
+            assert_eq!(governor.revenue_pool(RevenuePool::Distribution, psp22), 150);
+
+            // Off-chain, the governor's account is Alice's, so Bob votes.
+            set_sender(accounts.bob);
+            set_token_balance(accounts.bob, 1000);
+            assert_eq!(
+                governor.propose_pool_payout(
+                    RevenuePool::Contributors,
+                    native,
+                    accounts.django,
+                    30,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                governor.propose_pool_payout(
+                    RevenuePool::Distribution,
+                    psp22,
+                    accounts.django,
+                    150,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::PoolDepleted)
+            );
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+            assert_eq!(governor.revenue_pool(RevenuePool::Distribution, psp22), 0);
+            assert_eq!(crate::testing::token_balances(accounts.django).0, 150);
+            assert_eq!(crate::testing::token_balances(contract_id()).0, 350);
+        }
+
+        #[ink::test]
+        fn ledger_records_treasury_movements() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(40);
+            set_sender(accounts.frank);
+            assert_eq!(governor.collect_fee(), Ok(()));
+
+            set_block_timestamp(LEDGER_PERIOD);
+            set_sender(accounts.bob);
+            set_token_balance(accounts.bob, 100);
+            assert_eq!(governor.propose(accounts.django, 300, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+
+            assert_eq!(governor.ledger_length(), 2);
+            assert_eq!(
+                governor.ledger_entry(1),
+                Some(LedgerEntry {
+                    category: LedgerCategory::Investment,
+                    flow: Flow::Expense,
+                    asset: Asset::Native,
+                    counterparty: accounts.django,
+                    proposal_id: Some(ProposalId(0)),
+                    amount: 300,
+                    timestamp: LEDGER_PERIOD + ONE_MINUTE * 1000,
+                })
+            );
+            assert_eq!(
+                governor.ledger_totals(0, LedgerCategory::Fee, Asset::Native),
+                LedgerTotals {
+                    income: 40,
+                    expense: 0,
+                }
+            );
+            assert_eq!(
+                governor.ledger_totals(1, LedgerCategory::Investment, Asset::Native),
+                LedgerTotals {
+                    income: 0,
+                    expense: 300,
+                }
+            );
+            assert_eq!(
+                governor.ledger_totals(1, LedgerCategory::Fee, Asset::Native),
+                LedgerTotals::default()
+            );
+        }
+
+        #[ink::test]
+        fn lapsed_dues_suspend_voting() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.dues = 10;
+            config.dues_period = 100;
+            config.dues_grace_period = 50;
+            governor.store_config(&config);
+            set_token_balance(accounts.bob, 100);
+            set_token_balance(accounts.charlie, 100);
+            set_sender(accounts.charlie);
+            assert_eq!(governor.delegate(Some(accounts.bob)), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::For),
+                Err(GovernorError::DuesLapsed)
+            );
+
+            let pay_dues = |governor: &mut Governor, amount| {
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
+                    amount,
+                );
+                governor.pay_dues()
+            };
+            assert_eq!(
+                pay_dues(&mut governor, 15),
+                Err(GovernorError::InvalidDuesPayment)
+            );
+            assert_eq!(pay_dues(&mut governor, 20), Ok(200));
+            // Charlie hasn't paid, so the power delegated to Bob doesn't count.
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(
+                governor
+                    .proposal_votes
+                    .get(ProposalId(0))
+                    .unwrap()
+                    .for_votes as u128,
+                math::PRECISION / 2
+            );
+
+            set_block_timestamp(249);
+            assert!(governor.in_good_standing(accounts.bob));
+            set_block_timestamp(250);
+            assert!(!governor.in_good_standing(accounts.bob));
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(
+                governor.vote(ProposalId(1), VoteType::For),
+                Err(GovernorError::DuesLapsed)
+            );
+            // Paying the arrears cures the lapse.
+            assert_eq!(pay_dues(&mut governor, 10), Ok(300));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+        }
+
+        #[ink::test]
+        fn tiers_gate_rights() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.guardians = vec![accounts.charlie, accounts.django];
+            config.tiers = Some(TierPolicy {
+                member_stake: 100,
+                partner_stake: 500,
+                weights: [0, 50, 100],
+                propose: Tier::Member,
+                fast_track: Tier::Partner,
+                committee: Tier::Partner,
+            });
+            governor.store_config(&config);
+            set_token_balance(accounts.bob, 50);
+            set_token_balance(accounts.charlie, 100);
+            set_token_balance(accounts.django, 500);
+            assert_eq!(governor.tier(accounts.bob), Ok(Some(Tier::Observer)));
+
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.propose(accounts.eve, 100, ONE_MINUTE),
+                Err(GovernorError::TierTooLow)
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(governor.propose(accounts.eve, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(
+                governor.propose_fast_track(accounts.eve, 100),
+                Err(GovernorError::TierTooLow)
+            );
+            // Members vote with half their balance.
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(
+                governor
+                    .proposal_votes
+                    .get(ProposalId(0))
+                    .unwrap()
+                    .for_votes as u128,
+                math::ratio(50, 650)
+            );
+
+            set_sender(accounts.django);
+            assert_eq!(governor.propose_fast_track(accounts.eve, 100), Ok(()));
+            assert_eq!(
+                governor.propose_tier_assignment(accounts.bob, Tier::Partner, ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(2));
+            assert_eq!(governor.execute(ProposalId(2)), Ok(()));
+            assert_eq!(governor.tier(accounts.bob), Ok(Some(Tier::Partner)));
+            set_sender(accounts.bob);
+            assert_eq!(governor.propose(accounts.eve, 100, ONE_MINUTE), Ok(()));
+
+            // Stakes moved in within the current block don't count.
+            set_block_timestamp(1000);
+            set_token_balance(accounts.eve, 500);
+            set_sender(accounts.eve);
+            assert_eq!(governor.tier(accounts.eve), Ok(Some(Tier::Observer)));
+            assert_eq!(
+                governor.propose(accounts.eve, 100, ONE_MINUTE),
+                Err(GovernorError::TierTooLow)
+            );
+            set_block_timestamp(1001);
+            assert_eq!(governor.tier(accounts.eve), Ok(Some(Tier::Partner)));
+            assert_eq!(governor.propose(accounts.eve, 100, ONE_MINUTE), Ok(()));
+        }
+
+        #[ink::test]
+        fn membership_proposals_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.membership_shares = 10;
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(
+                governor.propose_membership(
+                    accounts.bob,
+                    MembershipChange::Remove,
+                    ONE_MINUTE
+                ),
+                Err(GovernorError::NotMember)
+            );
+            assert_eq!(
+                governor.propose_membership(
+                    accounts.bob,
+                    MembershipChange::Add,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                governor.propose_membership(
+                    accounts.bob,
+                    MembershipChange::Add,
+                    ONE_MINUTE
+                ),
+                Err(GovernorError::MembershipPending)
+            );
+            assert_eq!(
+                governor.pending_membership(accounts.bob),
+                Some(ProposalId(0))
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            assert_eq!(governor.pending_membership(accounts.bob), None);
+            assert!(governor.is_member(accounts.bob));
+            assert_eq!(governor.member_count(), 1);
+            assert_eq!(governor.shares(accounts.bob), 10);
+
+            assert_eq!(
+                governor.propose_membership(
+                    accounts.bob,
+                    MembershipChange::Remove,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(1));
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+            assert!(!governor.is_member(accounts.bob));
+            assert_eq!(governor.member_count(), 0);
+            assert_eq!(governor.shares(accounts.bob), 0);
+            assert_eq!(governor.total_shares(), 0);
+        }
+
+        #[ink::test]
+        fn guild_kick_converts_shares_to_loot() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.membership_shares = 10;
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 100);
+            set_token_balance(accounts.bob, 50);
+            for (proposal_id, account) in [
+                (ProposalId(0), accounts.bob),
+                (ProposalId(1), accounts.charlie),
+            ] {
+                assert_eq!(
+                    governor.propose_membership(
+                        account,
+                        MembershipChange::Add,
+                        ONE_MINUTE
+                    ),
+                    Ok(())
+                );
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+                end_vote(&governor, proposal_id);
+                assert_eq!(governor.execute(proposal_id), Ok(()));
+            }
+            assert_eq!(
+                governor.propose_membership(
+                    accounts.bob,
+                    MembershipChange::Kick,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(2));
+            assert_eq!(governor.execute(ProposalId(2)), Ok(()));
+            assert!(!governor.is_member(accounts.bob));
+            assert!(governor.is_kicked(accounts.bob));
+            assert_eq!(governor.shares(accounts.bob), 0);
+            assert_eq!(governor.loot(accounts.bob), 10);
+            assert_eq!(governor.total_shares(), 10);
+
+            assert_eq!(governor.propose(accounts.django, 1, ONE_MINUTE), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.vote(ProposalId(3), VoteType::For),
+                Err(GovernorError::Kicked)
+            );
+            assert_eq!(
+                governor.redeem_loot(11),
+                Err(GovernorError::InsufficientLoot)
+            );
+            // Fee deposits and revenue pools are owed to others and aren't
+            // redeemed against.
+            set_sender(accounts.charlie);
+            assert_eq!(governor.delegate(Some(accounts.django)), Ok(()));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(150);
+            assert_eq!(governor.fund_delegation_fee(), Ok(()));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            governor
+                .revenue_pools
+                .insert((RevenuePool::Contributors, Asset::Native), &50);
+            set_balance(contract_id(), 600);
+            assert_eq!(governor.redeemable_balance(), 400);
+            set_sender(accounts.bob);
+            // 4 of 10 loot against 20 outstanding shares and loot.
+            assert_eq!(governor.redeem_loot(4), Ok(80));
+            assert_eq!(governor.loot(accounts.bob), 6);
+            assert_eq!(governor.total_loot(), 6);
+        }
+
+        #[ink::test]
+        fn capital_accounts_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            set_token_balance(accounts.bob, 50);
+            set_sender(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(governor.deposit_capital(), Err(GovernorError::NotMember));
+            set_sender(accounts.alice);
+            assert_eq!(
+                governor.propose_membership(
+                    accounts.bob,
+                    MembershipChange::Add,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.deposit_capital(), Ok(0));
+            assert_eq!(governor.capital_account(accounts.bob).contributed, 100);
+            let entry = governor.ledger_entry(governor.ledger_length() - 1).unwrap();
+            assert_eq!(entry.category, LedgerCategory::Capital);
+
+            // A distribution to the member is allocated to its account.
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            set_sender(accounts.alice);
+            governor
+                .revenue_pools
+                .insert((RevenuePool::Distribution, Asset::Native), &40);
+            assert_eq!(
+                governor.propose_pool_payout(
+                    RevenuePool::Distribution,
+                    Asset::Native,
+                    accounts.bob,
+                    40,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(1));
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+            assert_eq!(governor.capital_account(accounts.bob).allocated, 40);
+
+            // Exiting through loot is a withdrawal.
+            governor.loot.insert(accounts.bob, &10);
+            governor.total_loot.set(&10);
+            set_balance(contract_id(), 300);
+            set_sender(accounts.bob);
+            assert_eq!(governor.redeem_loot(10), Ok(300));
+            assert_eq!(
+                governor.capital_account(accounts.bob),
+                CapitalAccount {
+                    contributed: 100,
+                    allocated: 40,
+                    withdrawn: 300,
+                }
+            );
+        }
+
+        #[ink::test]
+        fn lockups_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.deposit_lockup = 10_000;
+            config.membership_shares = 10;
+            config.settlement_period = 10_000;
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(
+                governor.propose_membership(
+                    accounts.bob,
+                    MembershipChange::Add,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            let deposited_at = ONE_MINUTE * 1000;
+            set_sender(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(governor.deposit_capital(), Ok(0));
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(
+                governor.lockup_end(accounts.bob),
+                Some(deposited_at + 10_000)
+            );
+
+            governor.loot.insert(accounts.bob, &10);
+            governor.total_loot.set(&10);
+            assert_eq!(
+                governor.redeem_loot(10),
+                Err(GovernorError::LockedUp {
+                    until: deposited_at + 10_000
+                })
+            );
+            // Bob queues a redemption once the deposit lockup is over, but
+            // governance locks him up again before it is settled.
+            set_block_timestamp(deposited_at + 10_000);
+            assert_eq!(governor.request_redemption(5), Ok(0));
+            set_sender(accounts.alice);
+            let until = deposited_at + 100_000;
+            assert_eq!(
+                governor.propose_lockup(accounts.bob, until, ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(
+                governor.lockup_change(ProposalId(1)),
+                Some((accounts.bob, until))
+            );
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(1));
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+            assert_eq!(governor.settle_redemptions(), Ok(0));
+            assert_eq!(governor.shares(accounts.bob), 10);
+            assert_eq!(governor.redemption_queue(), (1, 2));
+            set_block_timestamp(until - 1);
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.redeem_loot(10),
+                Err(GovernorError::LockedUp { until })
+            );
+            set_block_timestamp(until);
+            assert_eq!(governor.lockup_end(accounts.bob), None);
+            assert_eq!(governor.settle_redemptions(), Ok(1));
+            assert_eq!(governor.shares(accounts.bob), 5);
+            assert_eq!(governor.redemption_queue(), (2, 2));
+            set_balance(contract_id(), 300);
+            assert_eq!(governor.redeem_loot(10), Ok(200));
+        }
+
+        #[ink::test]
+        fn deposit_windows_mint_shares_at_nav() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.membership_shares = 10;
+            config.deposit_windows = Some(DepositWindows {
+                period: 100_000,
+                length: 10_000,
+            });
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(
+                governor.propose_membership(
+                    accounts.bob,
+                    MembershipChange::Add,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            set_sender(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(500);
+            assert!(!governor.is_deposit_window_open());
+            assert_eq!(
+                governor.deposit_capital(),
+                Err(GovernorError::DepositWindowClosed { opens_at: 100_000 })
+            );
+
+            // 10 shares are worth the 1000 held before the deposit.
+            set_block_timestamp(100_000);
+            set_balance(contract_id(), 1500);
+            assert!(governor.is_deposit_window_open());
+            assert_eq!(governor.deposit_capital(), Ok(5));
+            assert_eq!(governor.shares(accounts.bob), 15);
+            assert_eq!(governor.total_shares(), 15);
+            assert_eq!(governor.capital_account(accounts.bob).contributed, 500);
+            set_block_timestamp(110_000);
+            assert_eq!(
+                governor.deposit_capital(),
+                Err(GovernorError::DepositWindowClosed { opens_at: 200_000 })
+            );
+        }
+
+        #[ink::test]
+        fn redemption_queue_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.membership_shares = 10;
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 100);
+            for (proposal_id, account) in [
+                (ProposalId(0), accounts.bob),
+                (ProposalId(1), accounts.charlie),
+            ] {
+                assert_eq!(
+                    governor.propose_membership(
+                        account,
+                        MembershipChange::Add,
+                        ONE_MINUTE
+                    ),
+                    Ok(())
+                );
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+                end_vote(&governor, proposal_id);
+                assert_eq!(governor.execute(proposal_id), Ok(()));
+            }
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.request_redemption(5),
+                Err(GovernorError::RedemptionsDisabled)
+            );
+            config.settlement_period = 10_000;
+            config.liquidity_buffer = 50;
+            governor.store_config(&config);
+            assert_eq!(
+                governor.request_redemption(11),
+                Err(GovernorError::InsufficientShares)
+            );
+            assert_eq!(governor.request_redemption(6), Ok(0));
+            set_sender(accounts.charlie);
+            assert_eq!(governor.request_redemption(10), Ok(1));
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.request_redemption(5),
+                Err(GovernorError::InsufficientShares)
+            );
+            assert_eq!(governor.queued_shares(accounts.bob), 6);
+            assert_eq!(governor.redemption_queue(), (0, 2));
+
+            // A share is worth 1000 / 20. Half the treasury is kept as a buffer, so
+            // Charlie's request waits behind Bob's.
+            set_balance(accounts.bob, 0);
+            assert_eq!(governor.settle_redemptions(), Ok(1));
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    accounts.bob
+                ),
+                Ok(300)
+            );
+            assert_eq!(governor.shares(accounts.bob), 4);
+            assert_eq!(governor.total_shares(), 14);
+            assert_eq!(governor.queued_shares(accounts.bob), 0);
+            assert_eq!(governor.capital_account(accounts.bob).withdrawn, 300);
+            assert_eq!(governor.redemption_queue(), (1, 2));
+            let settled_at = ONE_MINUTE * 2000;
+            assert_eq!(
+                governor.settle_redemptions(),
+                Err(GovernorError::SettlementNotDue {
+                    due_at: settled_at + 10_000
+                })
+            );
+
+            set_block_timestamp(settled_at + 10_000);
+            assert_eq!(governor.settle_redemptions(), Ok(0));
+            config.liquidity_buffer = 0;
+            governor.store_config(&config);
+            set_block_timestamp(settled_at + 20_000);
+            assert_eq!(governor.settle_redemptions(), Ok(1));
+            assert_eq!(governor.shares(accounts.charlie), 0);
+            assert_eq!(governor.redemption_queue(), (2, 2));
+            assert_eq!(governor.redeemable_balance(), 200);
+        }
+
+        #[ink::test]
+        fn council_terms_and_recall_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.council_term = 100_000;
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(
+                governor.propose_council_change(
+                    accounts.bob,
+                    CouncilChange::Recall,
+                    ONE_MINUTE
+                ),
+                Err(GovernorError::NotOnCouncil)
+            );
+            assert_eq!(
+                governor.propose_council_change(
+                    accounts.bob,
+                    CouncilChange::Elect,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            assert_eq!(governor.council(), vec![accounts.bob]);
+            assert_eq!(governor.council_term_end(accounts.bob), Some(160_000));
+
+            // Council members act as guardians during their term.
+            set_sender(accounts.bob);
+            assert_eq!(governor.propose_fast_track(accounts.django, 1), Ok(()));
+            set_block_timestamp(160_000);
+            assert!(!governor.is_council_member(accounts.bob));
+            assert_eq!(
+                governor.propose_fast_track(accounts.django, 1),
+                Err(GovernorError::NotGuardian)
+            );
+
+            // A re-elected member can be recalled before its term ends.
+            set_sender(accounts.alice);
+            assert_eq!(
+                governor.propose_council_change(
+                    accounts.bob,
+                    CouncilChange::Elect,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(2));
+            assert_eq!(governor.execute(ProposalId(2)), Ok(()));
+            assert_eq!(governor.council_term_end(accounts.bob), Some(320_000));
+            assert_eq!(
+                governor.propose_council_change(
+                    accounts.bob,
+                    CouncilChange::Recall,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(3), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(3));
+            assert_eq!(governor.execute(ProposalId(3)), Ok(()));
+            assert!(governor.council().is_empty());
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.propose_fast_track(accounts.django, 1),
+                Err(GovernorError::NotGuardian)
+            );
+        }
+
+        #[ink::test]
+        fn review_panels_rotate() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let members = [accounts.bob, accounts.charlie, accounts.django];
+            for member in members {
+                governor
+                    .change_membership(&governor.config(), member, MembershipChange::Add)
+                    .unwrap();
+            }
+            assert!(governor.current_reviewers().is_empty());
+            let mut config = governor.config();
+            config.review_panel = 2;
+            config.epoch_length = 10;
+            governor.store_config(&config);
+            assert_eq!(governor.members(), members.to_vec());
+            assert_eq!(
+                governor.current_reviewers(),
+                vec![accounts.bob, accounts.charlie]
+            );
+            set_block_timestamp(10);
+            assert_eq!(
+                governor.current_reviewers(),
+                vec![accounts.django, accounts.bob]
+            );
+            assert!(!governor.is_reviewer(accounts.charlie));
+
+            config.review_rotation = ReviewRotation::Shuffled;
+            governor.store_config(&config);
+            let panel = governor.reviewers(7);
+            assert_eq!(panel.len(), 2);
+            assert!(panel.iter().all(|reviewer| members.contains(reviewer)));
+            assert_eq!(governor.reviewers(7), panel);
+
+            // Removed members drop out of the rotation.
+            governor
+                .change_membership(&config, accounts.bob, MembershipChange::Remove)
+                .unwrap();
+            config.review_panel = 5;
+            governor.store_config(&config);
+            assert_eq!(governor.reviewers(7).len(), 2);
+        }
+
+        #[ink::test]
+        fn endorsements_signal_sentiment() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 75);
+            set_token_balance(accounts.bob, 25);
+            assert_eq!(
+                governor.endorse(ProposalId(0), Sentiment::Endorse),
+                Err(GovernorError::ProposalNotFound)
+            );
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.endorse(ProposalId(0), Sentiment::Oppose), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.endorse(ProposalId(0), Sentiment::Endorse), Ok(()));
+            let quarter = math::percent(25) as u64;
+            assert_eq!(
+                governor.endorsements(ProposalId(0)),
+                Endorsements {
+                    endorsed: quarter,
+                    opposed: 3 * quarter,
+                    signalers: 2,
+                }
+            );
+            // Changing one's mind moves the weight over.
+            set_sender(accounts.alice);
+            assert_eq!(governor.endorse(ProposalId(0), Sentiment::Endorse), Ok(()));
+            assert_eq!(governor.endorsements(ProposalId(0)).endorsed, 4 * quarter);
+            assert_eq!(governor.endorsements(ProposalId(0)).opposed, 0);
+            assert_eq!(
+                governor.sentiment(ProposalId(0), accounts.alice),
+                Some((Sentiment::Endorse, 3 * quarter))
+            );
+            // Endorsements don't count as votes.
+            assert!(!governor.has_voted(ProposalId(0), accounts.alice));
+            // Tokens acquired after the vote started carry no weight.
+            set_block_timestamp(1);
+            set_sender(accounts.charlie);
+            set_token_balance(accounts.charlie, 900);
+            assert_eq!(governor.endorse(ProposalId(0), Sentiment::Oppose), Ok(()));
+            assert_eq!(
+                governor.sentiment(ProposalId(0), accounts.charlie),
+                Some((Sentiment::Oppose, 0))
+            );
+
+            // Scheduled proposals can be endorsed before their vote starts, with
+            // the stake held by the end of the previous block.
+            let mut config = governor.config();
+            config.max_start_delay = 60_000;
+            governor.store_config(&config);
+            set_block_timestamp(2);
+            set_sender(accounts.alice);
+            assert_eq!(
+                governor.propose_at(accounts.django, 100, ONE_MINUTE, 10_000),
+                Ok(())
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(governor.endorse(ProposalId(1), Sentiment::Endorse), Ok(()));
+            assert_eq!(
+                governor.sentiment(ProposalId(1), accounts.charlie),
+                Some((Sentiment::Endorse, math::ratio(900, 1000) as u64))
+            );
+            set_sender(accounts.eve);
+            set_token_balance(accounts.eve, 100);
+            assert_eq!(governor.endorse(ProposalId(1), Sentiment::Oppose), Ok(()));
+            assert_eq!(
+                governor.sentiment(ProposalId(1), accounts.eve),
+                Some((Sentiment::Oppose, 0))
+            );
+        }
+
+        #[ink::test]
+        fn external_weight_source_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            set_token_balance(accounts.bob, 900);
+            let source = AccountId::from([0x0f; 32]);
+            let mut config = governor.config();
+            config.weight_source = Some(source);
+            governor.store_config(&config);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(
+                governor.vote(ProposalId(0), VoteType::For),
+                Err(GovernorError::WeightSourceFailed)
+            );
+            // Power comes from the source, not the token.
+            crate::testing::set_voting_power(source, accounts.alice, 30);
+            crate::testing::set_voting_power(source, accounts.bob, 10);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            let votes = governor.proposal_votes.get(ProposalId(0)).unwrap();
+            assert_eq!(votes.for_votes, math::percent(75) as u64);
+            assert_eq!(votes.against_vote, math::percent(25) as u64);
+        }
+
+        #[ink::test]
+        fn voting_thresholds_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 60);
+            set_token_balance(accounts.bob, 30);
+            set_token_balance(accounts.charlie, 10);
+            let supermajority = VotingThresholds {
+                quorum: 0,
+                approval: 75,
+            };
+            assert_eq!(
+                governor.propose_with_thresholds(
+                    accounts.django,
+                    100,
+                    ONE_MINUTE,
+                    supermajority
+                ),
+                Err(GovernorError::InvalidThresholds)
+            );
+            let mut config = governor.config();
+            config.max_requested_quorum = 80;
+            config.max_requested_approval = 75;
+            governor.store_config(&config);
+            // Thresholds may only be stricter than the defaults, within bounds.
+            for (quorum, approval) in [(0, 0), (50, 0), (81, 0), (0, 50), (0, 76)] {
+                assert_eq!(
+                    governor.propose_with_thresholds(
+                        accounts.django,
+                        100,
+                        ONE_MINUTE,
+                        VotingThresholds { quorum, approval }
+                    ),
+                    Err(GovernorError::InvalidThresholds)
+                );
+            }
+            assert_eq!(
+                governor.propose_with_thresholds(
+                    accounts.django,
+                    100,
+                    ONE_MINUTE,
+                    supermajority
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                governor.voting_thresholds(ProposalId(0)),
+                Some(supermajority)
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::ApprovalNotReached)
+            );
 
-        fn contract_id() -> AccountId {
-            ink::env::test::callee::<ink::env::DefaultEnvironment>()
+            set_sender(accounts.alice);
+            for _ in 0..2 {
+                assert_eq!(
+                    governor.propose_with_thresholds(
+                        accounts.django,
+                        100,
+                        ONE_MINUTE,
+                        VotingThresholds {
+                            quorum: 70,
+                            approval: 0,
+                        }
+                    ),
+                    Ok(())
+                );
+            }
+            assert_eq!(governor.current_quorum(ProposalId(1)), Ok(700_000_000_000));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(1));
+            assert_eq!(
+                governor.execute(ProposalId(1)),
+                Err(GovernorError::QuorumNotReached {
+                    needed: 700_000_000_000,
+                    got: 600_000_000_000,
+                })
+            );
+            assert_eq!(governor.execute(ProposalId(2)), Ok(()));
         }
 
-        fn default_accounts(
-        ) -> ink::env::test::DefaultAccounts<ink::env::DefaultEnvironment> {
-            ink::env::test::default_accounts::<ink::env::DefaultEnvironment>()
+        #[ink::test]
+        fn category_voting_periods_work() {
+            let accounts = default_accounts();
+            let mut governor = create_categorized_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            let mut config = governor.config();
+            config.voting_periods = vec![(
+                OPERATIONS,
+                VotingPeriods {
+                    default: 2 * ONE_MINUTE,
+                    min: ONE_MINUTE,
+                    max: 10 * ONE_MINUTE,
+                },
+            )];
+            governor.store_config(&config);
+            for duration in [ONE_MINUTE - 1, 10 * ONE_MINUTE + 1] {
+                assert_eq!(
+                    governor.propose_in_category(
+                        accounts.django,
+                        100,
+                        duration,
+                        OPERATIONS
+                    ),
+                    Err(GovernorError::DurationError)
+                );
+            }
+            // Other categories have no default.
+            assert_eq!(
+                governor.propose_in_category(accounts.django, 100, 0, DEFI),
+                Err(GovernorError::DurationError)
+            );
+            assert_eq!(
+                governor.propose_in_category(accounts.django, 100, 0, OPERATIONS),
+                Ok(())
+            );
+            assert_eq!(
+                governor.propose_in_category(
+                    accounts.django,
+                    100,
+                    10 * ONE_MINUTE,
+                    OPERATIONS
+                ),
+                Ok(())
+            );
+            let vote_ends: Vec<_> = [ProposalId(0), ProposalId(1)]
+                .into_iter()
+                .map(|id| governor.get_proposal(id).unwrap().vote_end())
+                .collect();
+            assert_eq!(vote_ends, [2 * ONE_MINUTE * 1000, 10 * ONE_MINUTE * 1000]);
         }
 
-        fn set_sender(sender: AccountId) {
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(sender);
+        #[ink::test]
+        fn time_weighted_balances_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            let mut config = governor.config();
+            config.twab_window = 10_000;
+            governor.store_config(&config);
+            set_token_balance(accounts.alice, 100);
+            // Bob buys in a second before the vote.
+            set_block_timestamp(9000);
+            set_token_balance(accounts.bob, 100);
+
+            set_block_timestamp(10_000);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            // Alice held 100 tokens throughout and Bob only for the last tenth of
+            // the window, out of a supply of 110 on average.
+            let votes = governor.proposal_votes.get(ProposalId(0)).unwrap();
+            assert_eq!(votes.for_votes, math::ratio(100, 110) as u64);
+            assert_eq!(votes.against_vote, math::ratio(10, 110) as u64);
         }
 
-        fn set_balance(account_id: AccountId, balance: Balance) {
-            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(
-                account_id, balance,
-            )
+        #[ink::test]
+        fn governance_cycles_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            let mut config = governor.config();
+            config.cycle = Some(GovernanceCycle {
+                length: 100_000,
+                submission: 20_000,
+                voting: 60_000,
+            });
+            governor.store_config(&config);
+            set_block_timestamp(5000);
+            assert_eq!(governor.cycle_phase(), Some((0, CyclePhase::Submission)));
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            // Past the submission window, proposals queue for the next cycle.
+            set_block_timestamp(30_000);
+            assert_eq!(governor.cycle_phase(), Some((0, CyclePhase::Voting)));
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            let windows: Vec<_> = [ProposalId(0), ProposalId(1)]
+                .into_iter()
+                .map(|id| {
+                    let proposal = governor.get_proposal(id).unwrap();
+                    (proposal.vote_start(), proposal.vote_end())
+                })
+                .collect();
+            assert_eq!(windows, [(20_000, 80_000), (120_000, 180_000)]);
+
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(
+                governor.vote(ProposalId(1), VoteType::For),
+                Err(GovernorError::VotingNotStarted)
+            );
+            // Passed proposals wait for an execution window.
+            set_block_timestamp(100_000);
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::OutsideExecutionWindow)
+            );
+            set_block_timestamp(180_000);
+            assert_eq!(governor.cycle_phase(), Some((1, CyclePhase::Execution)));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
         }
 
         #[ink::test]
-        fn propose_works() {
+        fn votes_are_weighted_at_vote_start() {
             let accounts = default_accounts();
             let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 600);
+            set_token_balance(accounts.bob, 400);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            // Tokens moved once the vote started don't vote again.
+            set_block_timestamp(1000);
             assert_eq!(
-                governor.propose(accounts.django, 0, 1),
-                Err(GovernorError::AmountShouldNotBeZero)
+                crate::testing::transfer_tokens(accounts.alice, accounts.charlie, 600),
+                Ok(())
             );
+            set_sender(accounts.charlie);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            set_token_balance(accounts.bob, 0);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            let votes = governor.proposal_votes.get(ProposalId(0)).unwrap();
+            assert_eq!(votes.for_votes, math::percent(60) as u64);
+            assert_eq!(votes.against_vote, math::percent(40) as u64);
+        }
+
+        #[ink::test]
+        fn max_vote_weight_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 600);
+            set_token_balance(accounts.bob, 300);
+            set_token_balance(accounts.charlie, 100);
+            let mut config = governor.config();
+            config.max_vote_weight = 25;
+            governor.store_config(&config);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            // Charlie is under the cap, and carries the vote.
+            set_sender(accounts.charlie);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            let votes = governor.proposal_votes.get(ProposalId(0)).unwrap();
+            assert_eq!(votes.for_votes, math::percent(25) as u64);
+            assert_eq!(votes.against_vote, math::percent(35) as u64);
+        }
+
+        #[ink::test]
+        fn config_export_works() {
+            let accounts = default_accounts();
+            let mut governor = create_categorized_contract(1000);
+            let mut config = governor.config();
+            config.quorum = 30;
+            config.guardians = vec![accounts.bob];
+            governor.store_config(&config);
+            let exported = governor.export_config();
+            let clone = Governor::from_export(exported.clone()).unwrap();
+            assert_eq!(clone.config(), config);
+
+            let mut padded = exported.clone();
+            padded.push(0);
+            let mut newer = exported.clone();
+            newer[0] += 1;
+            for invalid in [padded, newer, exported[..10].to_vec()] {
+                assert!(matches!(
+                    Governor::from_export(invalid),
+                    Err(GovernorError::InvalidConfigExport)
+                ));
+            }
+        }
+
+        #[ink::test]
+        fn constitution_amendments_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 60);
+            set_token_balance(accounts.bob, 40);
             assert_eq!(
-                governor.propose(accounts.django, 100, 0),
+                governor.propose_amendment([1; 32], AMENDMENT_DURATION / 1000 - 1),
                 Err(GovernorError::DurationError)
             );
-            let result = governor.propose(accounts.django, 100, 1);
-            assert_eq!(result, Ok(()));
-            let proposal = governor.get_proposal(0).unwrap();
-            let now = governor.now();
             assert_eq!(
-                proposal,
-                Proposal {
-                    to: accounts.django,
-                    amount: 100,
-                    vote_start: 0,
-                    vote_end: now + 1 * ONE_MINUTE,
-                    executed: false,
+                governor.propose_amendment([1; 32], AMENDMENT_DURATION / 1000),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            // A simple majority isn't enough...
+            set_block_timestamp(AMENDMENT_DURATION);
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::SupermajorityNotReached)
+            );
+
+            set_sender(accounts.alice);
+            set_block_timestamp(0);
+            assert_eq!(
+                governor.propose_amendment([2; 32], AMENDMENT_DURATION / 1000),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            // ...and the voting period runs its full length.
+            assert_eq!(
+                governor.execute(ProposalId(1)),
+                Err(GovernorError::VotePeriodNotEnded)
+            );
+            set_block_timestamp(AMENDMENT_DURATION);
+            assert_eq!(governor.execute(ProposalId(1)), Ok(()));
+            assert_eq!(governor.constitution(), Some([2; 32]));
+
+            assert_eq!(
+                governor.propose_amendment([3; 32], AMENDMENT_DURATION / 1000),
+                Ok(())
+            );
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            set_block_timestamp(2 * AMENDMENT_DURATION);
+            assert_eq!(governor.execute(ProposalId(2)), Ok(()));
+            assert_eq!(governor.constitution(), Some([3; 32]));
+            assert_eq!(governor.constitution_versions(), 2);
+            assert_eq!(
+                governor.constitution_version(0),
+                Some(Amendment {
+                    document: [2; 32],
+                    proposal_id: ProposalId(1),
+                    adopted_at: AMENDMENT_DURATION,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn param_changes_are_recorded() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            let original = governor.config();
+            let mut config = original.clone();
+            config.quorum = 30;
+            assert_eq!(
+                governor.propose_config_change(config.clone(), ONE_MINUTE),
+                Ok(())
+            );
+            assert_eq!(governor.config_change(ProposalId(0)), Some(config.clone()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            assert_eq!(governor.config().quorum, 30);
+            assert_eq!(governor.param_history_length(), 1);
+            assert_eq!(
+                governor.param_change(0),
+                Some(ParamChange {
+                    old: original,
+                    new: config,
+                    proposal_id: ProposalId(0),
+                    timestamp: ONE_MINUTE * 1000,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn scheduled_config_changes_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            let original = governor.config();
+            let mut config = original.clone();
+            config.quorum = 30;
+            let vote_end = ONE_MINUTE * 1000;
+            let activates_at = 2 * vote_end;
+            assert_eq!(
+                governor.propose_scheduled_config_change(
+                    config.clone(),
+                    activates_at,
+                    ONE_MINUTE
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                governor.config_activation(ProposalId(0)),
+                Some(activates_at)
+            );
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            set_block_timestamp(vote_end);
+            assert_eq!(governor.execute(ProposalId(0)), Ok(()));
+            assert_eq!(governor.config().quorum, 50);
+            assert_eq!(
+                governor.scheduled_configs(),
+                [ScheduledConfig {
+                    config: config.clone(),
+                    proposal_id: ProposalId(0),
+                    activates_at,
+                }]
+            );
+            assert_eq!(governor.activate_configs(), 0);
+
+            set_block_timestamp(activates_at);
+            assert_eq!(governor.config().quorum, 30);
+            assert_eq!(governor.param_history_length(), 0);
+            assert_eq!(governor.activate_configs(), 1);
+            assert!(governor.scheduled_configs().is_empty());
+            assert_eq!(
+                governor.param_change(0),
+                Some(ParamChange {
+                    old: original,
+                    new: config.clone(),
+                    proposal_id: ProposalId(0),
+                    timestamp: activates_at,
+                })
+            );
+
+            // The change can't take effect before its vote ends.
+            assert_eq!(
+                governor.propose_scheduled_config_change(
+                    config,
+                    activates_at + vote_end - 1,
+                    ONE_MINUTE
+                ),
+                Err(GovernorError::InvalidActivationTime)
+            );
+        }
+
+        #[cfg(feature = "views")]
+        #[ink::test]
+        fn config_at_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            for (proposal_id, quorum) in [(ProposalId(0), 40), (ProposalId(1), 30)] {
+                let mut config = governor.config();
+                config.quorum = quorum;
+                assert_eq!(governor.propose_config_change(config, ONE_MINUTE), Ok(()));
+                assert_eq!(governor.vote(proposal_id, VoteType::For), Ok(()));
+                end_vote(&governor, proposal_id);
+                assert_eq!(governor.execute(proposal_id), Ok(()));
+            }
+            assert_eq!(governor.config_at(5).quorum, 50);
+            assert_eq!(governor.config_at(60_000).quorum, 40);
+            assert_eq!(governor.config_at(150_000).quorum, 30);
+            assert_eq!(governor.param_changes(1, 5).len(), 1);
+        }
+
+        #[ink::test]
+        fn proposals_by_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(100_000);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.propose_many(
+                    (0..2)
+                        .map(|_| {
+                            ProposalInput {
+                                to: accounts.django,
+                                amount: 100,
+                                duration: ONE_MINUTE,
+                            }
+                        })
+                        .collect()
+                ),
+                Ok(vec![ProposalId(1), ProposalId(2)])
+            );
+            set_sender(accounts.alice);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.proposer(ProposalId(1)), Some(accounts.bob));
+            assert_eq!(
+                governor.proposals_by(accounts.alice, 0, 10),
+                [ProposalId(0), ProposalId(3)]
+            );
+            assert_eq!(
+                governor.proposals_by(accounts.bob, 0, 10),
+                [ProposalId(1), ProposalId(2)]
+            );
+            assert!(governor.proposals_by(accounts.charlie, 0, 10).is_empty());
+
+            set_block_timestamp(ONE_MINUTE * 1000 + PRUNE_CHALLENGE_WINDOW);
+            assert_eq!(governor.prune(vec![ProposalId(0), ProposalId(1)]), Ok(()));
+            assert_eq!(governor.proposer(ProposalId(1)), None);
+            assert_eq!(
+                governor.proposals_by(accounts.alice, 0, 10),
+                [ProposalId(3)]
+            );
+            assert_eq!(governor.proposals_by(accounts.bob, 0, 10), [ProposalId(2)]);
+        }
+
+        #[ink::test]
+        fn delegate_profiles_work() {
+            let accounts = default_accounts();
+            let mut governor = create_categorized_contract(1000);
+            let profile = DelegateProfile {
+                statement: [1; 32],
+                contact: [2; 32],
+                categories: vec![OPERATIONS, DEFI, OPERATIONS],
+            };
+            assert_eq!(
+                governor.set_delegate_profile(Some(DelegateProfile {
+                    categories: vec![DEFI, 3],
+                    ..profile.clone()
+                })),
+                Err(GovernorError::UnknownCategory)
+            );
+            assert_eq!(governor.set_delegate_profile(Some(profile.clone())), Ok(()));
+            set_sender(accounts.bob);
+            assert_eq!(governor.set_delegate_profile(Some(profile.clone())), Ok(()));
+            assert_eq!(
+                governor.delegate_profile(accounts.bob),
+                Some(DelegateProfile {
+                    categories: vec![DEFI, OPERATIONS],
+                    ..profile.clone()
+                })
+            );
+            // Updating a profile keeps its place.
+            set_sender(accounts.alice);
+            let updated = DelegateProfile {
+                statement: [3; 32],
+                categories: vec![],
+                ..profile
+            };
+            assert_eq!(governor.set_delegate_profile(Some(updated.clone())), Ok(()));
+            assert_eq!(governor.delegate_profile(accounts.alice), Some(updated));
+            assert_eq!(
+                governor.profiled_delegates(),
+                [accounts.alice, accounts.bob]
+            );
+            assert_eq!(governor.set_delegate_profile(None), Ok(()));
+            assert_eq!(governor.delegate_profile(accounts.alice), None);
+            assert_eq!(governor.profiled_delegates(), [accounts.bob]);
+        }
+
+        #[ink::test]
+        fn delegation_stats_work() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            set_token_balance(accounts.bob, 300);
+            set_token_balance(accounts.charlie, 50);
+            for (delegator, delegate) in [
+                (accounts.bob, accounts.django),
+                (accounts.alice, accounts.django),
+                (accounts.charlie, accounts.eve),
+            ] {
+                set_sender(delegator);
+                assert_eq!(governor.delegate(Some(delegate)), Ok(()));
+            }
+            assert_eq!(
+                governor.delegation_stats(),
+                DelegationStats {
+                    delegated_supply: 450,
+                    active_delegates: 2,
+                }
+            );
+            assert_eq!(
+                governor.top_delegates(10),
+                [(accounts.django, 400), (accounts.eve, 50)]
+            );
+            assert_eq!(governor.top_delegates(1), [(accounts.django, 400)]);
+
+            // Balances count as of delegation.
+            set_token_balance(accounts.charlie, 500);
+            set_sender(accounts.alice);
+            assert_eq!(governor.delegate(Some(accounts.eve)), Ok(()));
+            assert_eq!(governor.delegated_weight(accounts.django), 300);
+            assert_eq!(governor.delegated_weight(accounts.eve), 150);
+            set_sender(accounts.bob);
+            assert_eq!(governor.delegate(None), Ok(()));
+            assert_eq!(
+                governor.delegation_stats(),
+                DelegationStats {
+                    delegated_supply: 150,
+                    active_delegates: 1,
                 }
             );
-            assert_eq!(governor.next_proposal_id(), 1);
+            assert_eq!(governor.top_delegates(10), [(accounts.eve, 150)]);
         }
 
         #[ink::test]
-        fn quorum_not_reached() {
+        fn topic_delegation_works() {
+            let accounts = default_accounts();
+            let mut governor = create_categorized_contract(1000);
+            for account in [accounts.alice, accounts.bob, accounts.charlie] {
+                set_token_balance(account, 100);
+            }
+            set_token_balance(accounts.django, 200);
+            assert_eq!(governor.propose(accounts.eve, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(
+                governor.propose_in_category(accounts.eve, 100, ONE_MINUTE, DEFI),
+                Ok(())
+            );
+            assert_eq!(governor.proposal_category(ProposalId(1)), DEFI);
+
+            // Alice delegates to Bob, except on DeFi deals where Charlie votes.
+            assert_eq!(governor.delegate(Some(accounts.bob)), Ok(()));
+            assert_eq!(
+                governor.delegate_topic(DEFI, Some(accounts.charlie)),
+                Ok(())
+            );
+            assert_eq!(
+                governor.delegate_for(accounts.alice, GENERAL_CATEGORY),
+                Some(accounts.bob)
+            );
+            assert_eq!(
+                governor.delegate_for(accounts.alice, DEFI),
+                Some(accounts.charlie)
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(
+                governor.delegate_topic(DEFI, Some(accounts.alice)),
+                Err(GovernorError::DelegationCycle)
+            );
+            // Outside DeFi, Charlie may still delegate to Alice and on to Bob.
+            assert_eq!(governor.delegate(Some(accounts.alice)), Ok(()));
+            assert_eq!(governor.delegate(None), Ok(()));
+
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::For), Ok(()));
+            set_sender(accounts.charlie);
+            assert_eq!(governor.vote(ProposalId(0), VoteType::Against), Ok(()));
+            assert_eq!(governor.vote(ProposalId(1), VoteType::Against), Ok(()));
+            let tally = |governor: &Governor, proposal_id: ProposalId| {
+                let proposal_vote = governor.proposal_votes.get(proposal_id).unwrap();
+                (proposal_vote.for_votes, proposal_vote.against_vote)
+            };
+            let fifth = math::PRECISION as u64 / 5;
+            assert_eq!(tally(&governor, ProposalId(0)), (2 * fifth, fifth));
+            assert_eq!(tally(&governor, ProposalId(1)), (fifth, 2 * fifth));
+
+            // Without the override, DeFi votes follow the general delegate again.
+            set_sender(accounts.alice);
+            assert_eq!(governor.delegate_topic(DEFI, None), Ok(()));
+            assert_eq!(
+                governor.delegate_for(accounts.alice, DEFI),
+                Some(accounts.bob)
+            );
+            assert_eq!(
+                governor.propose_in_category(accounts.eve, 100, ONE_MINUTE, DEFI),
+                Ok(())
+            );
+            set_sender(accounts.bob);
+            assert_eq!(governor.vote(ProposalId(2), VoteType::For), Ok(()));
+            assert_eq!(tally(&governor, ProposalId(2)), (2 * fifth, 0));
+        }
+
+        #[ink::test]
+        fn delegation_depth_is_capped() {
+            let chain: Vec<AccountId> = (0..=MAX_DELEGATION_DEPTH + 1)
+                .map(|index| AccountId::from([0x10 + index; 32]))
+                .collect();
             let mut governor = create_contract(1000);
-            let result = governor.propose(AccountId::from([0x02; 32]), 100, 1);
-            assert_eq!(result, Ok(()));
-            let voting = governor.vote(0, VoteType::Against);
-            assert_eq!(voting, Ok(()));
-            let execute = governor.execute(0);
-            assert_eq!(execute, Err(GovernorError::QuorumNotReached));
+            for pair in chain.windows(2).take(MAX_DELEGATION_DEPTH as usize) {
+                set_sender(pair[0]);
+                assert_eq!(governor.delegate(Some(pair[1])), Ok(()));
+            }
+            let last = chain.len() - 1;
+            set_sender(chain[last]);
+            assert_eq!(
+                governor.delegate(Some(chain[0])),
+                Err(GovernorError::DelegationTooDeep)
+            );
+
+            // Chains ending at the delegator count as well.
+            set_sender(chain[2]);
+            assert_eq!(governor.delegate(None), Ok(()));
+            set_sender(chain[last]);
+            assert_eq!(governor.delegate(Some(chain[0])), Ok(()));
+            set_sender(chain[2]);
+            assert_eq!(
+                governor.delegate(Some(chain[3])),
+                Err(GovernorError::DelegationTooDeep)
+            );
+        }
+
+        #[ink::test]
+        fn delegation_fees_stream() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(0);
+            set_sender(accounts.charlie);
+            governor.set_delegate_fee(100);
+            set_sender(accounts.bob);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(250);
+            assert_eq!(
+                governor.fund_delegation_fee(),
+                Err(GovernorError::NotDelegating)
+            );
+            assert_eq!(governor.delegate(Some(accounts.charlie)), Ok(()));
+            assert_eq!(governor.fund_delegation_fee(), Ok(()));
+            // The treasury holds the deposit, which proposals can't spend.
+            set_balance(contract_id(), 250);
+            assert_eq!(governor.reserved_balance(), 250);
+            set_sender(accounts.alice);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(governor.propose(accounts.django, 100, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.execute(ProposalId(0)),
+                Err(GovernorError::InsufficientTreasury {
+                    requested: 100,
+                    available: 0,
+                })
+            );
+
+            set_block_timestamp(FEE_PERIOD * 3 / 2);
+            set_sender(accounts.charlie);
+            set_balance(accounts.charlie, 0);
+            assert_eq!(governor.claim_delegate_fees(vec![accounts.bob]), Ok(150));
+            assert_eq!(
+                governor.claim_delegate_fees(vec![accounts.bob]),
+                Err(GovernorError::NothingToClaim)
+            );
+
+            // Leaving the delegate settles the stream and refunds the rest.
+            set_block_timestamp(FEE_PERIOD * 2);
+            set_sender(accounts.bob);
+            set_balance(accounts.bob, 0);
+            assert_eq!(governor.delegate(None), Ok(()));
+            assert_eq!(governor.fee_stream(accounts.bob), None);
+            assert_eq!(
+                ink::env::test::get_account_balance::<ink::env::DefaultEnvironment>(
+                    accounts.bob
+                ),
+                Ok(50)
+            );
+            set_sender(accounts.charlie);
+            assert_eq!(governor.claim_delegate_fees(vec![]), Ok(50));
+            assert_eq!(governor.reserved_balance(), 0);
+        }
+
+        #[ink::test]
+        fn proposal_encoding_is_compact() {
+            let proposal = Proposal {
+                to: AccountId::from([0x02; 32]),
+                vote_start: 1_700_000_000_000,
+                vote_end: 1_700_000_060_000,
+                executed: false,
+                amount: 1_000,
+            };
+            let encoded = proposal.encode();
+            assert!(encoded.len() < 32 + 8 + 8 + 1 + 16);
+            assert_eq!(Proposal::decode(&mut &encoded[..]), Ok(proposal));
+        }
+
+        #[ink::test]
+        fn collect_fee_works() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_sender(accounts.bob);
+            assert_eq!(
+                governor.collect_fee(),
+                Err(GovernorError::AmountShouldNotBeZero)
+            );
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(25);
+            assert_eq!(governor.collect_fee(), Ok(()));
+            assert_eq!(governor.collect_fee(), Ok(()));
+            assert_eq!(governor.fees_collected(accounts.bob), 50);
+            assert_eq!(governor.fees_collected(accounts.charlie), 0);
+            assert_eq!(governor.total_fees_collected.get_or_default(), 50);
+            assert_eq!(ink::env::test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn code_info_works() {
+            let governor = create_contract(1000);
+            let info = governor.code_info();
+            assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+            assert_eq!(info.storage_version, STORAGE_VERSION);
+            assert_eq!(info.events_version, EVENTS_VERSION);
+            assert_eq!(
+                info.features.contains(&String::from("views")),
+                cfg!(feature = "views")
+            );
+            assert_eq!(
+                info.features.contains(&String::from("invariants")),
+                cfg!(feature = "invariants")
+            );
+        }
+
+        #[ink::test]
+        fn attempt_execute_reports_failures() {
+            let accounts = default_accounts();
+            let mut governor = create_contract(1000);
+            set_token_balance(accounts.alice, 100);
+            assert_eq!(governor.propose(accounts.django, 1500, ONE_MINUTE), Ok(()));
+            assert_eq!(governor.vote(ProposalId(0), VoteType::For), Ok(()));
+            let underfunded = GovernorError::InsufficientTreasury {
+                requested: 1500,
+                available: 1000,
+            };
+            end_vote(&governor, ProposalId(0));
+            assert_eq!(
+                governor.attempt_execute(ProposalId(0)),
+                Ok(Some(underfunded))
+            );
+            assert_eq!(
+                governor.attempt_execute(ProposalId(1)),
+                Ok(Some(GovernorError::ProposalNotFound))
+            );
+            type Event = <Governor as ink::reflect::ContractEventBase>::Type;
+            let failures: Vec<_> = ink::env::test::recorded_events()
+                .filter_map(|event| {
+                    match <Event as scale::Decode>::decode(&mut &event.data[..]) {
+                        Ok(Event::OperationFailed(event)) => {
+                            Some((event.op, event.proposal_id, event.error))
+                        }
+                        _ => None,
+                    }
+                })
+                .collect();
+            assert_eq!(
+                failures,
+                [
+                    (Operation::Execute, ProposalId(0), underfunded),
+                    (
+                        Operation::Execute,
+                        ProposalId(1),
+                        GovernorError::ProposalNotFound
+                    ),
+                ]
+            );
+
+            set_balance(contract_id(), 2000);
+            assert_eq!(governor.attempt_execute(ProposalId(0)), Ok(None));
+            assert!(governor.get_proposal(ProposalId(0)).unwrap().executed);
+        }
+
+        #[ink::test]
+        fn events_carry_schema_version() {
+            let mut governor = create_contract(1000);
+            assert_eq!(governor.events_version(), EVENTS_VERSION);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(25);
+            assert_eq!(governor.collect_fee(), Ok(()));
+            let event = ink::env::test::recorded_events().next().unwrap();
+            type Event = <Governor as ink::reflect::ContractEventBase>::Type;
+            match <Event as scale::Decode>::decode(&mut &event.data[..]) {
+                Ok(Event::FeeCollected(event)) => {
+                    assert_eq!(event.schema_version, EVENTS_VERSION)
+                }
+                _ => panic!("expected a FeeCollected event"),
+            }
         }
     }
 }
@@ -312,3 +12282,13 @@ macro_rules! ensure {
         }
     }};
 }
+
+/// Asserts a state invariant when built with the `invariants` feature, and compiles
+/// to nothing otherwise.
+#[macro_export]
+macro_rules! invariant {
+    ( $x:expr, $($message:tt)+ ) => {
+        #[cfg(feature = "invariants")]
+        assert!($x, $($message)+)
+    };
+}
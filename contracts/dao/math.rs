@@ -0,0 +1,130 @@
+//! Fixed-point helpers for voting weights and percentages.
+//!
+//! Weights are fractions of the governance token supply scaled by `PRECISION`, so a
+//! holder of the whole supply has a weight of exactly `PRECISION`. All divisions round
+//! down.
+
+/// Scale of a fixed-point fraction: `PRECISION` represents 1 (100%).
+pub const PRECISION: u128 = 1_000_000_000_000;
+
+/// Computes `a * b / denominator` rounding down, without overflowing on the
+/// intermediate product.
+///
+/// Returns `None` if `denominator` is zero or the result doesn't fit in a `u128`.
+pub fn mul_div(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    if denominator == 0 {
+        return None
+    }
+    if let Some(product) = a.checked_mul(b) {
+        return Some(product / denominator)
+    }
+    let (high, low) = widening_mul(a, b);
+    if high >= denominator {
+        return None
+    }
+    // Long division of the 256-bit product by `denominator`, one bit at a time.
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
+}
+
+/// The fraction `part / whole` as a fixed-point number. Zero if `whole` is zero.
+pub fn ratio(part: u128, whole: u128) -> u128 {
+    mul_div(part, PRECISION, whole).unwrap_or(0)
+}
+
+/// A whole percentage as a fixed-point number.
+pub fn percent(value: u8) -> u128 {
+    PRECISION * value as u128 / 100
+}
+
+/// Linearly moves from `start` towards `end` as `elapsed` goes from zero to
+/// `period`, and stays at `end` from then on.
+pub fn interpolate(start: u128, end: u128, elapsed: u64, period: u64) -> u128 {
+    if elapsed >= period {
+        return end
+    }
+    let (elapsed, period) = (elapsed as u128, period as u128);
+    if start >= end {
+        start - mul_div(start - end, elapsed, period).unwrap_or(0)
+    } else {
+        start + mul_div(end - start, elapsed, period).unwrap_or(0)
+    }
+}
+
+/// Full 256-bit product of `a * b` as `(high, low)` halves.
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let high_high = a_high * b_high;
+
+    let middle = (low_low >> 64) + (high_low & MASK) + (low_high & MASK);
+    let low = (middle << 64) | (low_low & MASK);
+    let high = high_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+    (high, low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio_rounds_down() {
+        assert_eq!(ratio(1, 3), 333_333_333_333);
+        assert_eq!(ratio(2, 3), 666_666_666_666);
+        assert_eq!(ratio(3, 3), PRECISION);
+        assert_eq!(ratio(1, 0), 0);
+    }
+
+    #[test]
+    fn ratio_keeps_small_holders() {
+        // One token out of a billion 18-decimals supply still has a weight.
+        let supply = 1_000_000_000 * 10u128.pow(18);
+        assert_eq!(ratio(10u128.pow(18), supply), 1_000);
+        // Below the precision it truncates to zero.
+        assert_eq!(ratio(1, supply), 0);
+    }
+
+    #[test]
+    fn mul_div_handles_wide_products() {
+        let supply = u128::MAX / 2;
+        assert_eq!(mul_div(supply, PRECISION, supply), Some(PRECISION));
+        assert_eq!(
+            mul_div(supply / 4, PRECISION, supply),
+            Some(PRECISION / 4 - 1)
+        );
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 1), None);
+        assert_eq!(mul_div(1, 1, 0), None);
+    }
+
+    #[test]
+    fn percent_works() {
+        assert_eq!(percent(0), 0);
+        assert_eq!(percent(50), PRECISION / 2);
+        assert_eq!(percent(100), PRECISION);
+    }
+
+    #[test]
+    fn interpolate_works() {
+        assert_eq!(interpolate(100, 40, 0, 60), 100);
+        assert_eq!(interpolate(100, 40, 30, 60), 70);
+        assert_eq!(interpolate(100, 40, 90, 60), 40);
+        assert_eq!(interpolate(40, 100, 15, 60), 55);
+        assert_eq!(interpolate(100, 40, 0, 0), 40);
+    }
+}
@@ -0,0 +1,56 @@
+//! Merkle proofs for tallies aggregated off-chain.
+//!
+//! Nodes are `blake2x256` hashes. Pairs are hashed in sorted order, so a proof is
+//! just the list of sibling hashes from the leaf up to the root.
+
+use ink::env::hash::{
+    Blake2x256,
+    HashOutput,
+};
+
+pub type Node = [u8; 32];
+
+/// Hashes the SCALE encoding of a leaf.
+pub fn leaf<T: scale::Encode>(value: &T) -> Node {
+    hash(&value.encode())
+}
+
+/// Whether `proof` links `leaf` to `root`.
+pub fn verify(root: &Node, leaf: Node, proof: &[Node]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| parent(&node, sibling))
+        == *root
+}
+
+/// Parent of two sibling nodes.
+pub fn parent(a: &Node, b: &Node) -> Node {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut input = [0u8; 64];
+    input[..32].copy_from_slice(first);
+    input[32..].copy_from_slice(second);
+    hash(&input)
+}
+
+fn hash(input: &[u8]) -> Node {
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(input, &mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_works() {
+        let leaves = [leaf(&1u32), leaf(&2u32), leaf(&3u32)];
+        let left = parent(&leaves[0], &leaves[1]);
+        let root = parent(&left, &leaves[2]);
+        assert!(verify(&root, leaves[0], &[leaves[1], leaves[2]]));
+        assert!(verify(&root, leaves[1], &[leaves[0], leaves[2]]));
+        assert!(verify(&root, leaves[2], &[left]));
+        assert!(!verify(&root, leaf(&4u32), &[left]));
+        assert!(!verify(&root, leaves[0], &[leaves[2]]));
+    }
+}
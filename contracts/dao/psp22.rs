@@ -0,0 +1,79 @@
+//! Minimal PSP22 call interface for the governance token and tokens held by the
+//! treasury.
+//!
+//! Only the messages the governor calls, pinned to the standard PSP22 selectors
+//! (`blake2b("PSP22::<message>")`), so any compliant token works regardless of the
+//! library it was built with.
+
+use ink::{
+    prelude::{
+        string::String,
+        vec::Vec,
+    },
+    primitives::AccountId,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+
+type Balance = u128;
+
+/// Selector of `PSP22::total_supply`.
+pub const TOTAL_SUPPLY_SELECTOR: u32 = 0x162d_f8c2;
+/// Selector of `PSP22::balance_of`.
+pub const BALANCE_OF_SELECTOR: u32 = 0x6568_382f;
+/// Selector of `PSP22::transfer`.
+pub const TRANSFER_SELECTOR: u32 = 0xdb20_f9f5;
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    Custom(String),
+    InsufficientBalance,
+    InsufficientAllowance,
+    ZeroRecipientAddress,
+    ZeroSenderAddress,
+    SafeTransferCheckFailed(String),
+}
+
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Total amount of tokens in circulation.
+    #[ink(message, selector = 0x162d_f8c2)]
+    fn total_supply(&self) -> Balance;
+
+    /// Token balance of `owner`.
+    #[ink(message, selector = 0x6568_382f)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    /// Transfers `value` tokens from the caller to `to`.
+    #[ink(message, selector = 0xdb20_f9f5)]
+    fn transfer(
+        &mut self,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selectors_match_the_standard() {
+        assert_eq!(
+            TOTAL_SUPPLY_SELECTOR.to_be_bytes(),
+            ink::selector_bytes!("PSP22::total_supply")
+        );
+        assert_eq!(
+            BALANCE_OF_SELECTOR.to_be_bytes(),
+            ink::selector_bytes!("PSP22::balance_of")
+        );
+        assert_eq!(
+            TRANSFER_SELECTOR.to_be_bytes(),
+            ink::selector_bytes!("PSP22::transfer")
+        );
+    }
+}
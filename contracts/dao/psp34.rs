@@ -0,0 +1,95 @@
+//! Minimal PSP34 call interface for NFTs held by the treasury.
+//!
+//! Only the messages the governor calls, pinned to the standard PSP34 selectors
+//! (`blake2b("PSP34::<message>")`) like the PSP22 interface.
+
+use ink::{
+    prelude::{
+        string::String,
+        vec::Vec,
+    },
+    primitives::AccountId,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Selector of `PSP34::owner_of`.
+pub const OWNER_OF_SELECTOR: u32 = 0x1168_624d;
+/// Selector of `PSP34::transfer`.
+pub const TRANSFER_SELECTOR: u32 = 0x3128_d61b;
+/// Selector of `PSP34::approve`.
+pub const APPROVE_SELECTOR: u32 = 0x1932_a8b0;
+
+/// Token id within a PSP34 collection.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum Id {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP34Error {
+    Custom(String),
+    SelfApprove,
+    NotApproved,
+    TokenExists,
+    TokenNotExists,
+    SafeTransferCheckFailed(String),
+}
+
+#[ink::trait_definition]
+pub trait PSP34 {
+    /// Owner of the token `id`, if it exists.
+    #[ink(message, selector = 0x1168_624d)]
+    fn owner_of(&self, id: Id) -> Option<AccountId>;
+
+    /// Transfers the token `id` from the caller to `to`.
+    #[ink(message, selector = 0x3128_d61b)]
+    fn transfer(
+        &mut self,
+        to: AccountId,
+        id: Id,
+        data: Vec<u8>,
+    ) -> Result<(), PSP34Error>;
+
+    /// Lets `operator` transfer the caller's token `id`, or all of them if `None`.
+    #[ink(message, selector = 0x1932_a8b0)]
+    fn approve(
+        &mut self,
+        operator: AccountId,
+        id: Option<Id>,
+        approved: bool,
+    ) -> Result<(), PSP34Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selectors_match_the_standard() {
+        assert_eq!(
+            OWNER_OF_SELECTOR.to_be_bytes(),
+            ink::selector_bytes!("PSP34::owner_of")
+        );
+        assert_eq!(
+            TRANSFER_SELECTOR.to_be_bytes(),
+            ink::selector_bytes!("PSP34::transfer")
+        );
+        assert_eq!(
+            APPROVE_SELECTOR.to_be_bytes(),
+            ink::selector_bytes!("PSP34::approve")
+        );
+    }
+}
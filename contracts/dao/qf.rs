@@ -0,0 +1,76 @@
+//! Quadratic funding: a matching pool is split between grant candidates in
+//! proportion to the square of the sum of the square roots of the contributions each
+//! received, less the contributions themselves. Many small contributions thus
+//! attract more matching than a few large ones.
+
+use crate::math;
+use ink::prelude::vec::Vec;
+
+type Balance = u128;
+
+/// Integer square root, rounding down.
+pub fn sqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n
+    }
+    // Newton's method from an initial guess above the root.
+    let mut x = 1u128 << ((128 - n.leading_zeros()) / 2 + 1);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x
+        }
+        x = y
+    }
+}
+
+/// Matching each candidate receives out of `pool`, given the sum of the square roots
+/// of each contributor's total (`sqrt_sums`) and the total contributed
+/// (`contributions`) per candidate. Rounding leaves the remainder in the pool.
+pub fn matches(
+    pool: Balance,
+    sqrt_sums: &[u128],
+    contributions: &[Balance],
+) -> Vec<Balance> {
+    let ideal: Vec<u128> = sqrt_sums
+        .iter()
+        .zip(contributions)
+        .map(|(sqrt_sum, contributed)| {
+            sqrt_sum
+                .saturating_mul(*sqrt_sum)
+                .saturating_sub(*contributed)
+        })
+        .collect();
+    let total = ideal
+        .iter()
+        .fold(0u128, |total, ideal| total.saturating_add(*ideal));
+    ideal
+        .into_iter()
+        .map(|ideal| math::mul_div(pool, ideal, total).unwrap_or(0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_rounds_down() {
+        assert_eq!(sqrt(0), 0);
+        assert_eq!(sqrt(1), 1);
+        assert_eq!(sqrt(8), 2);
+        assert_eq!(sqrt(9), 3);
+        assert_eq!(sqrt(10u128.pow(24)), 10u128.pow(12));
+        assert_eq!(sqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    #[test]
+    fn matches_favor_broad_support() {
+        // Four contributions of 25 against a single one of 100.
+        let sqrt_sums = [4 * sqrt(25), sqrt(100)];
+        assert_eq!(matches(1000, &sqrt_sums, &[100, 100]), vec![1000, 0]);
+        // Ideal matches of 2, 0 and 4.
+        assert_eq!(matches(90, &[2, 1, 3], &[2, 1, 5]), vec![30, 0, 60]);
+        assert_eq!(matches(1000, &[1, 0], &[1, 0]), vec![0, 0]);
+    }
+}
@@ -0,0 +1,335 @@
+//! Off-chain test helpers for contracts and tools built on the governor.
+//!
+//! Enabled by the `testing` feature. It swaps the governance token queries, NFT
+//! calls, weight source, price oracle and identity registry queries for in-memory
+//! ledgers (see [`set_token_balance`], [`set_nft_owner`], [`set_voting_power`],
+//! [`set_price`] and [`set_verified`]), so it must never be enabled for a deployed
+//! contract.
+
+use crate::{
+    dao::{
+        Governor,
+        GovernorError,
+        ProposalId,
+        VoteType,
+    },
+    psp22::PSP22Error,
+    psp34::{
+        Id,
+        PSP34Error,
+    },
+};
+use ink::{
+    env::DefaultEnvironment,
+    primitives::AccountId,
+};
+use std::{
+    cell::RefCell,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+};
+
+type Balance = u128;
+
+type NftApproval = (AccountId, AccountId, AccountId, Option<Id>);
+
+thread_local! {
+    static TOKEN_BALANCES: RefCell<BTreeMap<AccountId, Balance>> =
+        RefCell::new(BTreeMap::new());
+    /// Every change of `TOKEN_BALANCES`, with the block timestamp it was made at.
+    static TOKEN_HISTORY: RefCell<Vec<(u64, AccountId, Balance)>> =
+        RefCell::new(Vec::new());
+    static NFT_OWNERS: RefCell<BTreeMap<(AccountId, Id), AccountId>> =
+        RefCell::new(BTreeMap::new());
+    static NFT_APPROVALS: RefCell<BTreeMap<NftApproval, bool>> =
+        RefCell::new(BTreeMap::new());
+    static VOTING_POWER: RefCell<BTreeMap<(AccountId, AccountId), Balance>> =
+        RefCell::new(BTreeMap::new());
+    static PRICES: RefCell<BTreeMap<AccountId, Balance>> = RefCell::new(BTreeMap::new());
+    static VERIFIED: RefCell<BTreeSet<(AccountId, AccountId)>> =
+        RefCell::new(BTreeSet::new());
+}
+
+/// Sets `account`'s balance of the mock governance token. The total supply is the
+/// sum of all balances set so far.
+pub fn set_token_balance(account: AccountId, balance: Balance) {
+    TOKEN_BALANCES.with(|balances| balances.borrow_mut().insert(account, balance));
+    record_token_balance(account, balance);
+}
+
+fn record_token_balance(account: AccountId, balance: Balance) {
+    let now = ink::env::block_timestamp::<DefaultEnvironment>();
+    TOKEN_HISTORY.with(|history| history.borrow_mut().push((now, account, balance)));
+}
+
+/// Mock governance token `balance_of(account)` and `total_supply()`. Every PSP22
+/// token the governor queries shares this ledger.
+pub fn token_balances(account: AccountId) -> (Balance, Balance) {
+    TOKEN_BALANCES.with(|balances| {
+        let balances = balances.borrow();
+        let total_supply = balances.values().sum();
+        (balances.get(&account).copied().unwrap_or(0), total_supply)
+    })
+}
+
+/// Mock governance token `balance_of_at(account, timestamp)` and
+/// `total_supply_at(timestamp)`: the balances as of the last changes made at or
+/// before `timestamp`.
+pub fn token_balances_at(account: AccountId, timestamp: u64) -> (Balance, Balance) {
+    TOKEN_HISTORY.with(|history| {
+        let mut balances = BTreeMap::new();
+        for (_, holder, balance) in history
+            .borrow()
+            .iter()
+            .filter(|(changed_at, ..)| *changed_at <= timestamp)
+        {
+            balances.insert(*holder, *balance);
+        }
+        let total_supply = balances.values().sum();
+        (balances.get(&account).copied().unwrap_or(0), total_supply)
+    })
+}
+
+/// Mock governance token `average_balance_of(account, start, end)` and
+/// `average_total_supply(start, end)`: the balances weighed by how long (in
+/// seconds) they were held between `start` and `end`.
+pub fn token_averages(account: AccountId, start: u64, end: u64) -> (Balance, Balance) {
+    let seconds = Balance::from(end.saturating_sub(start) / 1000);
+    if seconds == 0 {
+        return token_balances_at(account, end)
+    }
+    let mut changes: Vec<u64> = TOKEN_HISTORY.with(|history| {
+        history
+            .borrow()
+            .iter()
+            .map(|(changed_at, ..)| *changed_at)
+            .filter(|changed_at| start < *changed_at && *changed_at < end)
+            .collect()
+    });
+    changes.insert(0, start);
+    changes.push(end);
+    changes.dedup();
+    let (mut balance, mut total_supply) = (0, 0);
+    for period in changes.windows(2) {
+        let held = Balance::from((period[1] - period[0]) / 1000);
+        let (period_balance, period_supply) = token_balances_at(account, period[0]);
+        balance += period_balance * held;
+        total_supply += period_supply * held;
+    }
+    (balance / seconds, total_supply / seconds)
+}
+
+/// Mock PSP22 `transfer(to, amount)` called by `from`.
+pub fn transfer_tokens(
+    from: AccountId,
+    to: AccountId,
+    amount: Balance,
+) -> Result<(), PSP22Error> {
+    TOKEN_BALANCES.with(|balances| {
+        let mut balances = balances.borrow_mut();
+        let balance = balances.get(&from).copied().unwrap_or(0);
+        if balance < amount {
+            return Err(PSP22Error::InsufficientBalance)
+        }
+        balances.insert(from, balance - amount);
+        let received = balances.entry(to).or_insert(0);
+        *received += amount;
+        let received = *received;
+        record_token_balance(from, balance - amount);
+        record_token_balance(to, received);
+        Ok(())
+    })
+}
+
+/// Sets `account`'s power in the mock weight source `source`, at any time.
+pub fn set_voting_power(source: AccountId, account: AccountId, power: Balance) {
+    VOTING_POWER.with(|powers| powers.borrow_mut().insert((source, account), power));
+}
+
+/// Mock weight source `voting_power_of(account, _)` and `total_voting_power(_)`,
+/// the sum of the powers set for `source`. `None` for a source without any power
+/// set, like a failed query.
+pub fn voting_power(source: AccountId, account: AccountId) -> Option<(Balance, Balance)> {
+    VOTING_POWER.with(|powers| {
+        let powers = powers.borrow();
+        let mut total = None;
+        for ((_, holder), power) in powers.iter().filter(|((of, _), _)| *of == source) {
+            let (power_of, total_power) = total.get_or_insert((0, 0));
+            if *holder == account {
+                *power_of = *power;
+            }
+            *total_power += power;
+        }
+        total
+    })
+}
+
+/// Sets the price quoted by the mock price oracle `oracle`.
+pub fn set_price(oracle: AccountId, price: Balance) {
+    PRICES.with(|prices| prices.borrow_mut().insert(oracle, price));
+}
+
+/// Mock price oracle `price()`. `None` for an oracle without a price set, like a
+/// failed query.
+pub fn price(oracle: AccountId) -> Option<Balance> {
+    PRICES.with(|prices| prices.borrow().get(&oracle).copied())
+}
+
+/// Marks `account` as verified, or not, by the mock identity registry `gate`.
+pub fn set_verified(gate: AccountId, account: AccountId, verified: bool) {
+    VERIFIED.with(|accounts| {
+        let mut accounts = accounts.borrow_mut();
+        if verified {
+            accounts.insert((gate, account));
+        } else {
+            accounts.remove(&(gate, account));
+        }
+    });
+}
+
+/// Mock identity registry `is_verified(account)`.
+pub fn is_verified(gate: AccountId, account: AccountId) -> bool {
+    VERIFIED.with(|accounts| accounts.borrow().contains(&(gate, account)))
+}
+
+/// Sets the owner of the token `id` of the mock PSP34 `collection`.
+pub fn set_nft_owner(collection: AccountId, id: Id, owner: AccountId) {
+    NFT_OWNERS.with(|owners| owners.borrow_mut().insert((collection, id), owner));
+}
+
+/// Mock PSP34 `owner_of(id)`.
+pub fn nft_owner(collection: AccountId, id: Id) -> Option<AccountId> {
+    NFT_OWNERS.with(|owners| owners.borrow().get(&(collection, id)).copied())
+}
+
+/// Mock PSP34 `transfer(to, id)` called by `from`.
+pub fn transfer_nft(
+    collection: AccountId,
+    from: AccountId,
+    to: AccountId,
+    id: Id,
+) -> Result<(), PSP34Error> {
+    NFT_OWNERS.with(|owners| {
+        let mut owners = owners.borrow_mut();
+        let owner = owners
+            .get_mut(&(collection, id))
+            .ok_or(PSP34Error::TokenNotExists)?;
+        if *owner != from {
+            return Err(PSP34Error::NotApproved)
+        }
+        *owner = to;
+        Ok(())
+    })
+}
+
+/// Mock PSP34 `approve(operator, id, approved)` called by `owner`.
+pub fn approve_nft(
+    collection: AccountId,
+    owner: AccountId,
+    operator: AccountId,
+    id: Option<Id>,
+    approved: bool,
+) -> Result<(), PSP34Error> {
+    NFT_APPROVALS.with(|approvals| {
+        approvals
+            .borrow_mut()
+            .insert((collection, owner, operator, id), approved)
+    });
+    Ok(())
+}
+
+/// Whether `owner` let `operator` transfer the token `id` of the mock `collection`,
+/// or all of them if `None`.
+pub fn is_nft_approved(
+    collection: AccountId,
+    owner: AccountId,
+    operator: AccountId,
+    id: Option<Id>,
+) -> bool {
+    NFT_APPROVALS.with(|approvals| {
+        approvals
+            .borrow()
+            .get(&(collection, owner, operator, id))
+            .copied()
+            .unwrap_or(false)
+    })
+}
+
+pub fn default_accounts() -> ink::env::test::DefaultAccounts<DefaultEnvironment> {
+    ink::env::test::default_accounts::<DefaultEnvironment>()
+}
+
+pub fn set_caller(caller: AccountId) {
+    ink::env::test::set_caller::<DefaultEnvironment>(caller);
+}
+
+/// Sets the native balance of `account`, e.g. to fund the governor's treasury.
+pub fn fund(account: AccountId, balance: Balance) {
+    ink::env::test::set_account_balance::<DefaultEnvironment>(account, balance);
+}
+
+/// Moves the block timestamp forward by `millis`.
+pub fn advance_block_time(millis: u64) {
+    let now = ink::env::block_timestamp::<DefaultEnvironment>();
+    ink::env::test::set_block_timestamp::<DefaultEnvironment>(now + millis);
+}
+
+/// Builds a proposal and the ballots cast on it.
+///
+/// ```ignore
+/// let proposal_id = ProposalScenario::new(recipient, 100, 60)
+///     .vote(accounts.alice, 600, VoteType::For)
+///     .vote(accounts.bob, 400, VoteType::Against)
+///     .run(&mut governor)?;
+/// ```
+pub struct ProposalScenario {
+    proposer: Option<AccountId>,
+    to: AccountId,
+    amount: Balance,
+    duration: u64,
+    votes: Vec<(AccountId, Balance, VoteType)>,
+}
+
+impl ProposalScenario {
+    pub fn new(to: AccountId, amount: Balance, duration: u64) -> Self {
+        Self {
+            proposer: None,
+            to,
+            amount,
+            duration,
+            votes: Vec::new(),
+        }
+    }
+
+    /// Submits the proposal as `proposer` instead of the current caller.
+    pub fn proposer(mut self, proposer: AccountId) -> Self {
+        self.proposer = Some(proposer);
+        self
+    }
+
+    /// Gives `voter` `balance` governance tokens and casts their ballot.
+    pub fn vote(mut self, voter: AccountId, balance: Balance, vote: VoteType) -> Self {
+        self.votes.push((voter, balance, vote));
+        self
+    }
+
+    /// Creates the proposal on `governor`, casts the ballots in order and returns
+    /// the new proposal's id. The caller is left as the last voter.
+    pub fn run(self, governor: &mut Governor) -> Result<ProposalId, GovernorError> {
+        for (voter, balance, _) in &self.votes {
+            set_token_balance(*voter, *balance);
+        }
+        if let Some(proposer) = self.proposer {
+            set_caller(proposer);
+        }
+        let proposal_id = governor.next_proposal_id();
+        governor.propose(self.to, self.amount, self.duration)?;
+        for (voter, _, vote) in self.votes {
+            set_caller(voter);
+            governor.vote(proposal_id, vote)?;
+        }
+        Ok(proposal_id)
+    }
+}
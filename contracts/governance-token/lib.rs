@@ -3,8 +3,13 @@
 #[openbrush::implementation(PSP22, PSP22Metadata)]
 #[openbrush::contract]
 pub mod governance_token {
+    use ink::storage::Mapping;
     use openbrush::traits::Storage;
 
+    /// A balance from `timestamp` on, and the sum of the balance over time (in
+    /// balance-seconds) until `timestamp`: `(timestamp, balance, cumulative)`.
+    type Checkpoint = (Timestamp, Balance, Balance);
+
     #[ink(storage)]
     #[derive(Default, Storage)]
     pub struct GovernanceToken {
@@ -12,6 +17,28 @@ pub mod governance_token {
         psp22: psp22::Data,
         #[storage_field]
         metadata: metadata::Data,
+        /// Balances of each account, or the total supply under `None`, from a
+        /// timestamp on, by index in chronological order.
+        checkpoints: Mapping<(Option<AccountId>, u32), Checkpoint>,
+        checkpoint_counts: Mapping<Option<AccountId>, u32>,
+    }
+
+    #[overrider(psp22::Internal)]
+    fn _after_token_transfer(
+        &mut self,
+        from: Option<&AccountId>,
+        to: Option<&AccountId>,
+        _amount: &Balance,
+    ) -> Result<(), PSP22Error> {
+        for account in [from, to].into_iter().flatten() {
+            let balance = psp22::Internal::_balance_of(self, account);
+            self.checkpoint(Some(*account), balance);
+        }
+        if from.is_none() || to.is_none() {
+            let total_supply = psp22::Internal::_total_supply(self);
+            self.checkpoint(None, total_supply);
+        }
+        Ok(())
     }
 
     impl GovernanceToken {
@@ -28,5 +55,77 @@ pub mod governance_token {
 
             instance
         }
+
+        /// Balance of `owner` as of `timestamp`, after the transfers made up to then.
+        #[ink(message)]
+        pub fn balance_of_at(&self, owner: AccountId, timestamp: Timestamp) -> Balance {
+            self.checkpoint_at(Some(owner), timestamp).map_or(0, |(_, balance, _)| balance)
+        }
+
+        /// Total supply as of `timestamp`.
+        #[ink(message)]
+        pub fn total_supply_at(&self, timestamp: Timestamp) -> Balance {
+            self.checkpoint_at(None, timestamp).map_or(0, |(_, balance, _)| balance)
+        }
+
+        /// Average balance of `owner` from `start` until `end`, each balance weighed
+        /// by how long (in seconds) it was held. The balance as of `end` if they are
+        /// less than a second apart.
+        #[ink(message)]
+        pub fn average_balance_of(&self, owner: AccountId, start: Timestamp, end: Timestamp) -> Balance {
+            self.average(Some(owner), start, end)
+        }
+
+        /// Average total supply from `start` until `end`, see `average_balance_of`.
+        #[ink(message)]
+        pub fn average_total_supply(&self, start: Timestamp, end: Timestamp) -> Balance {
+            self.average(None, start, end)
+        }
+
+        fn checkpoint(&mut self, key: Option<AccountId>, balance: Balance) {
+            let now = self.env().block_timestamp();
+            let count = self.checkpoint_counts.get(key).unwrap_or_default();
+            let last = count
+                .checked_sub(1)
+                .and_then(|last| self.checkpoints.get((key, last)).map(|checkpoint| (last, checkpoint)));
+            let (index, cumulative) = match last {
+                Some((last, (at, _, cumulative))) if at == now => (last, cumulative),
+                Some((_, checkpoint)) => (count, Self::accumulate(checkpoint, now)),
+                None => (0, 0),
+            };
+            self.checkpoints.insert((key, index), &(now, balance, cumulative));
+            self.checkpoint_counts.insert(key, &(index + 1));
+        }
+
+        fn average(&self, key: Option<AccountId>, start: Timestamp, end: Timestamp) -> Balance {
+            let seconds = Balance::from(end.saturating_sub(start) / 1000);
+            if seconds == 0 {
+                return self.checkpoint_at(key, end).map_or(0, |(_, balance, _)| balance)
+            }
+            let cumulative = |timestamp| {
+                self.checkpoint_at(key, timestamp)
+                    .map_or(0, |checkpoint| Self::accumulate(checkpoint, timestamp))
+            };
+            cumulative(end).saturating_sub(cumulative(start)) / seconds
+        }
+
+        /// `cumulative` of `checkpoint` carried forward to `timestamp`.
+        fn accumulate((at, balance, cumulative): Checkpoint, timestamp: Timestamp) -> Balance {
+            let seconds = timestamp.saturating_sub(at) / 1000;
+            cumulative.saturating_add(balance.saturating_mul(seconds.into()))
+        }
+
+        fn checkpoint_at(&self, key: Option<AccountId>, timestamp: Timestamp) -> Option<Checkpoint> {
+            // Number of checkpoints at or before `timestamp`.
+            let (mut low, mut high) = (0, self.checkpoint_counts.get(key).unwrap_or_default());
+            while low < high {
+                let mid = low + (high - low) / 2;
+                match self.checkpoints.get((key, mid)) {
+                    Some((at, ..)) if at <= timestamp => low = mid + 1,
+                    _ => high = mid,
+                }
+            }
+            low.checked_sub(1).and_then(|index| self.checkpoints.get((key, index)))
+        }
     }
 }